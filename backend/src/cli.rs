@@ -0,0 +1,144 @@
+//! Command line arguments of the indexer.
+//!
+//! The defaults index the testnet contract the indexer was written for, so
+//! running it without arguments behaves as before.
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use clap::{value_parser, Arg, Command};
+use concordium_rust_sdk::{
+  types::{AbsoluteBlockHeight, ContractAddress},
+  v2::Endpoint,
+};
+
+use crate::store::FlushPolicy;
+
+pub const DEFAULT_ENDPOINT: &str = "http://node.testnet.concordium.com:20000";
+pub const DEFAULT_CONTRACT_INDEX: &str = "7418";
+pub const DEFAULT_CONTRACT_SUBINDEX: &str = "0";
+pub const DEFAULT_START_HEIGHT: &str = "7921000";
+pub const DEFAULT_DB_PATH: &str = "events.db";
+pub const DEFAULT_FLUSH_EVERY: &str = "100";
+pub const DEFAULT_MAX_RETRIES: &str = "10";
+
+/// The parsed arguments of the indexer.
+#[derive(Debug)]
+pub struct Args {
+  /// The gRPC endpoint of the node.
+  pub endpoint: Endpoint,
+  /// The contract whose events are indexed.
+  pub contract: ContractAddress,
+  /// The height of the first block to index, unless the database has a
+  /// checkpoint to resume from.
+  pub start_height: AbsoluteBlockHeight,
+  /// Path of the SQLite database the events are stored in.
+  pub db_path: String,
+  /// When the buffered events are written to the database.
+  pub flush_policy: FlushPolicy,
+  /// Reconnects in a row without indexing a block before giving up.
+  pub max_retries: u32,
+  /// Webhook every stored event is POSTed to.
+  pub webhook_url: Option<String>,
+}
+
+fn command() -> Command {
+  Command::new("backend")
+    .about("Indexes the events of a ciphers_nft contract.")
+    .arg(
+      Arg::new("endpoint")
+        .long("endpoint")
+        .help("gRPC endpoint of the node")
+        .default_value(DEFAULT_ENDPOINT),
+    )
+    .arg(
+      Arg::new("contract-index")
+        .long("contract-index")
+        .help("Index of the contract to index")
+        .value_parser(value_parser!(u64))
+        .default_value(DEFAULT_CONTRACT_INDEX),
+    )
+    .arg(
+      Arg::new("contract-subindex")
+        .long("contract-subindex")
+        .help("Subindex of the contract to index")
+        .value_parser(value_parser!(u64))
+        .default_value(DEFAULT_CONTRACT_SUBINDEX),
+    )
+    .arg(
+      Arg::new("start-height")
+        .long("start-height")
+        .help("Height of the first block to index")
+        .value_parser(value_parser!(u64))
+        .default_value(DEFAULT_START_HEIGHT),
+    )
+    .arg(
+      Arg::new("db-path")
+        .long("db-path")
+        .help("Path of the SQLite database the events are stored in")
+        .default_value(DEFAULT_DB_PATH),
+    )
+    .arg(
+      Arg::new("flush-every")
+        .long("flush-every")
+        .value_name("N|block")
+        .help("Write the events to the database every N events or at the end of every block")
+        .value_parser(FlushPolicy::from_str)
+        .default_value(DEFAULT_FLUSH_EVERY),
+    )
+    .arg(
+      Arg::new("max-retries")
+        .long("max-retries")
+        .help("Reconnects in a row without indexing a block before giving up")
+        .value_parser(value_parser!(u32))
+        .default_value(DEFAULT_MAX_RETRIES),
+    )
+    .arg(
+      Arg::new("webhook-url")
+        .long("webhook-url")
+        .help("Webhook every stored event is POSTed to"),
+    )
+}
+
+/// Parse the arguments, the first of which is the program name. Invalid or
+/// unknown arguments are returned as a [`clap::Error`], which prints the
+/// usage. An endpoint that is not a URL with a scheme is rejected.
+pub fn parse_args<I, T>(args: I) -> anyhow::Result<Args>
+where
+  I: IntoIterator<Item = T>,
+  T: Into<std::ffi::OsString> + Clone,
+{
+  let matches = command().try_get_matches_from(args)?;
+  let url = matches
+    .get_one::<String>("endpoint")
+    .expect("Endpoint has a default");
+  let endpoint = Endpoint::from_shared(url.clone())
+    .with_context(|| format!("Invalid endpoint URL {:?}.", url))?;
+  if endpoint.uri().scheme().is_none() {
+    bail!(
+      "Invalid endpoint URL {:?}: missing the scheme, such as http://.",
+      url
+    );
+  }
+  let get = |name: &str| {
+    *matches
+      .get_one::<u64>(name)
+      .expect("Argument has a default")
+  };
+
+  Ok(Args {
+    endpoint,
+    contract: ContractAddress::new(get("contract-index"), get("contract-subindex")),
+    start_height: AbsoluteBlockHeight::from(get("start-height")),
+    db_path: matches
+      .get_one::<String>("db-path")
+      .expect("Database path has a default")
+      .clone(),
+    flush_policy: *matches
+      .get_one::<FlushPolicy>("flush-every")
+      .expect("Flush policy has a default"),
+    max_retries: *matches
+      .get_one::<u32>("max-retries")
+      .expect("Max retries has a default"),
+    webhook_url: matches.get_one::<String>("webhook-url").cloned(),
+  })
+}
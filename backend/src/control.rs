@@ -0,0 +1,102 @@
+//! Pausing and resuming the indexer at runtime.
+//!
+//! While paused, the indexer stops processing new blocks but keeps its
+//! connection to the node, so it resumes from the block it stopped at.
+use std::{future::Future, pin::pin, sync::Arc};
+
+use concordium_rust_sdk::{types::AbsoluteBlockHeight, v2::FinalizedBlockInfo};
+use futures::{Stream, StreamExt};
+use tokio::sync::watch;
+
+/// Shared paused flag of the indexer. Clones control the same flag.
+#[derive(Debug, Clone)]
+pub struct PauseControl {
+  paused: Arc<watch::Sender<bool>>,
+}
+
+impl Default for PauseControl {
+  fn default() -> Self {
+    PauseControl {
+      paused: Arc::new(watch::channel(false).0),
+    }
+  }
+}
+
+impl PauseControl {
+  pub fn pause(&self) {
+    self.paused.send_replace(true);
+  }
+
+  pub fn resume(&self) {
+    self.paused.send_replace(false);
+  }
+
+  pub fn is_paused(&self) -> bool {
+    *self.paused.borrow()
+  }
+
+  /// Wait until the indexer is not paused. Returns immediately when it is
+  /// running.
+  pub async fn wait_resumed(&self) {
+    let mut paused = self.paused.subscribe();
+    // The sender lives as long as `self`, so waiting cannot fail.
+    let _ = paused.wait_for(|paused| !paused).await;
+  }
+
+  /// Pause on `SIGUSR1` and resume on `SIGUSR2` until the process exits.
+  #[cfg(unix)]
+  pub fn listen_signals(&self) -> std::io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut pause = signal(SignalKind::user_defined1())?;
+    let mut resume = signal(SignalKind::user_defined2())?;
+    let control = self.clone();
+    tokio::spawn(async move {
+      loop {
+        tokio::select! {
+          Some(()) = pause.recv() => {
+            println!("Pausing indexer.");
+            control.pause();
+          }
+          Some(()) = resume.recv() => {
+            println!("Resuming indexer.");
+            control.resume();
+          }
+          else => break,
+        }
+      }
+    });
+    Ok(())
+  }
+}
+
+/// Processes the finalized blocks passed by [`index_blocks`].
+pub trait BlockProcessor {
+  /// Process a block. An error stops the indexing before the block.
+  fn process(&mut self, block: FinalizedBlockInfo) -> impl Future<Output = anyhow::Result<()>>;
+}
+
+/// Process the blocks in order, waiting before each block while `control` is
+/// paused, and advance `height` past every processed block, so indexing
+/// resumes from `height` after a pause or an error.
+pub async fn index_blocks<S, P>(
+  blocks: S,
+  height: &mut AbsoluteBlockHeight,
+  control: &PauseControl,
+  processor: &mut P,
+) -> anyhow::Result<()>
+where
+  S: Stream<Item = FinalizedBlockInfo>,
+  P: BlockProcessor,
+{
+  let mut blocks = pin!(blocks);
+  while let Some(block) = blocks.next().await {
+    if control.is_paused() {
+      println!("Paused before block {}.", block.height);
+      control.wait_resumed().await;
+    }
+    processor.process(block).await?;
+    *height = AbsoluteBlockHeight::from(block.height.height + 1);
+  }
+  Ok(())
+}
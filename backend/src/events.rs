@@ -0,0 +1,76 @@
+//! Decoding of the events logged by the contract.
+//!
+//! Every event starts with a tag byte selecting the [`ContractEvent`] variant,
+//! so each log is decoded on its own, whatever the order the contract logs
+//! them in.
+use ciphers_nft::events::ContractEvent;
+use concordium_rust_sdk::types::smart_contracts::concordium_contracts_common::from_bytes;
+use serde_json::{json, Value};
+
+/// Decode a logged event, `None` if it is not a [`ContractEvent`].
+pub fn decode_event(bytes: &[u8]) -> Option<ContractEvent> {
+  from_bytes(bytes).ok()
+}
+
+/// The decoded event as JSON, with its variant as `type`. Events that cannot
+/// be decoded have the type `Unknown` and their raw bytes.
+pub fn event_value(bytes: &[u8]) -> Value {
+  match decode_event(bytes) {
+    Some(ContractEvent::Mint(event)) => json!({
+      "type": "Mint",
+      "token_id": event.token_id.to_string(),
+      "amount": event.amount.0,
+      "owner": event.owner.to_string(),
+    }),
+    Some(ContractEvent::Transfer(event)) => json!({
+      "type": "Transfer",
+      "token_id": event.token_id.to_string(),
+      "amount": event.amount.0,
+      "from": event.from.to_string(),
+      "to": event.to.to_string(),
+    }),
+    Some(ContractEvent::Burn(event)) => json!({
+      "type": "Burn",
+      "token_id": event.token_id.to_string(),
+      "amount": event.amount.0,
+      "owner": event.owner.to_string(),
+    }),
+    Some(ContractEvent::TokenMetadata(event)) => json!({
+      "type": "TokenMetadata",
+      "token_id": event.token_id.to_string(),
+      "metadata_url": event.metadata_url.url,
+    }),
+    Some(ContractEvent::Minted(event)) => json!({
+      "type": "Minted",
+      "token_id": event.token_id.to_string(),
+      "mint_count": event.mint_count,
+      "timestamp": event.timestamp,
+      "token_uri": event.token_uri.url,
+    }),
+    Some(ContractEvent::Deploy(event)) => json!({
+      "type": "Deploy",
+      "name": event.name,
+      "symbol": event.symbol,
+      "contract_uri": event.contract_uri.url,
+      "minter": event.minter.to_string(),
+      "mint_start": event.mint_start,
+      "mint_deadline": event.mint_deadline,
+      "max_total_supply": event.max_total_supply,
+      "contract_version": event.contract_version,
+    }),
+    Some(ContractEvent::Withdraw(event)) => json!({
+      "type": "Withdraw",
+      "receiver": event.receiver.to_string(),
+      "amount": event.amount.micro_ccd,
+    }),
+    Some(ContractEvent::OwnershipTransferred(event)) => json!({
+      "type": "OwnershipTransferred",
+      "previous_owner": event.previous_owner.to_string(),
+      "new_owner": event.new_owner.to_string(),
+    }),
+    None => json!({
+      "type": "Unknown",
+      "bytes": hex::encode(bytes),
+    }),
+  }
+}
@@ -0,0 +1,9 @@
+//! Indexer for the `ciphers_nft` contract.
+pub mod cli;
+pub mod control;
+pub mod events;
+pub mod ownership;
+pub mod retry;
+pub mod reverted;
+pub mod store;
+pub mod webhook;
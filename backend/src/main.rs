@@ -1,97 +1,226 @@
 //! Test the `GetBlockTransactionEvents` endpoint.
 use anyhow::Context;
+use backend::{
+  cli::parse_args,
+  control::{index_blocks, BlockProcessor, PauseControl},
+  events::event_value,
+  retry::Backoff,
+  reverted::{failed_update, reverted_update, RevertedStats},
+  store::{EventStore, FlushPolicy, StoredEvent},
+  webhook::{event_json, Webhook, WebhookQueue},
+};
 use concordium_rust_sdk::{
-  cis2::{TokenAmount, TokenId},
-  contract_client::MetadataUrl,
-  smart_contracts::common::{AccountAddress, Cursor, Get, ParseError, ParseResult, Read},
-  types::smart_contracts::concordium_contracts_common::Deserial,
-  types::{
-    smart_contracts::ContractEvent, AbsoluteBlockHeight, AccountTransactionEffects, Address,
-    BlockItemSummaryDetails, ContractAddress, ContractTraceElement, InstanceUpdatedEvent,
-  },
-  v2::{self, Endpoint},
+  types::{AbsoluteBlockHeight, ContractAddress},
+  v2::{self, FinalizedBlockInfo},
 };
 use futures::StreamExt;
-use hex;
-use serde::Deserialize;
-
-#[derive(Debug)]
-pub struct MintEvent {
-  pub token_id: TokenId,
-  pub amount: TokenAmount,
-  // pub owner: AccountAddress,
-}
-
-impl Deserial for MintEvent {
-  fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
-    let token_id: TokenId = source.get()?;
-    let amount: TokenAmount = source.get()?;
-
-    println!("token_id: {:?}", token_id);
-    println!("amount: {:?}", amount);
+use std::time::Duration;
 
-    Ok(MintEvent { token_id, amount })
-  }
-}
+/// Bodies waiting for delivery to the webhook before new ones are dropped.
+const WEBHOOK_QUEUE_CAPACITY: usize = 10_000;
+/// Time the queued events are still delivered for after shutting down.
+const WEBHOOK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
 
 struct App {
   endpoint: v2::Endpoint,
+  /// The next block to index, advanced once a block is processed.
   height: AbsoluteBlockHeight,
+  contract: ContractAddress,
+  /// Path of the SQLite database the events are stored in.
+  db_path: String,
+  /// When the buffered events are written to the database, set with
+  /// `--flush-every N` or `--flush-every block`.
+  flush_policy: FlushPolicy,
+  backoff: Backoff,
+  /// Reconnects in a row without indexing a block before giving up.
+  max_retries: u32,
+  /// Webhook every stored event is POSTed to once its block is indexed, set
+  /// with `--webhook-url`.
+  webhook: Option<WebhookQueue>,
+  /// Paused with `SIGUSR1` and resumed with `SIGUSR2`.
+  control: PauseControl,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-  let app = {
-    let app = App {
-      endpoint: Endpoint::from_static("http://node.testnet.concordium.com:20000"),
-      height: AbsoluteBlockHeight::from(7_921_000),
-    };
-    App::from(app)
+  let args = match parse_args(std::env::args()) {
+    Ok(args) => args,
+    Err(error) => match error.downcast::<clap::Error>() {
+      Ok(error) => error.exit(),
+      Err(error) => {
+        eprintln!("Error: {:#}", error);
+        std::process::exit(2);
+      }
+    },
+  };
+  let mut app = App {
+    endpoint: args.endpoint,
+    height: args.start_height,
+    contract: args.contract,
+    db_path: args.db_path,
+    flush_policy: args.flush_policy,
+    backoff: Backoff::default(),
+    max_retries: args.max_retries,
+    webhook: args
+      .webhook_url
+      .map(|url| Webhook::new(url).spawn(WEBHOOK_QUEUE_CAPACITY)),
+    control: PauseControl::default(),
   };
+  #[cfg(unix)]
+  app
+    .control
+    .listen_signals()
+    .context("Cannot listen to signals.")?;
 
-  let mut client = v2::Client::new(app.endpoint)
+  let mut store =
+    EventStore::open(&app.db_path, app.flush_policy).context("Cannot open database.")?;
+  if let Some(height) = store.resume_height().context("Cannot read checkpoint.")? {
+    println!("Resuming after checkpoint at {}.", height - 1);
+    app.height = AbsoluteBlockHeight::from(height);
+  }
+
+  let result = tokio::select! {
+    result = run(&mut app, &mut store) => result,
+    _ = tokio::signal::ctrl_c() => {
+      println!("Shutting down.");
+      Ok(())
+    }
+  };
+  // Write the buffered events so none are lost on shutdown.
+  store.flush().context("Cannot flush events.")?;
+  if let Some(webhook) = app.webhook {
+    if tokio::time::timeout(WEBHOOK_SHUTDOWN_TIMEOUT, webhook.close())
+      .await
+      .is_err()
+    {
+      eprintln!("Stopped delivering the queued events to the webhook.");
+    }
+  }
+  result
+}
+
+/// Index the finalized blocks, reconnecting after connection and stream
+/// errors. The delay between reconnects grows with every attempt in a row
+/// that does not index a block, and the error is returned after
+/// `app.max_retries` of them.
+async fn run(app: &mut App, store: &mut EventStore) -> anyhow::Result<()> {
+  let mut retries = 0;
+  loop {
+    let start = app.height;
+    let error = match index(app, store).await {
+      Ok(()) => return Ok(()),
+      Err(error) => error,
+    };
+    // Drop the events of the interrupted block, it is indexed again.
+    store.flush().context("Cannot flush events.")?;
+    store
+      .truncate_from(app.height.height)
+      .context("Cannot remove events of the interrupted block.")?;
+
+    if app.height > start {
+      retries = 0;
+    }
+    retries += 1;
+    if retries > app.max_retries {
+      return Err(error.context(format!("Giving up after {} retries.", app.max_retries)));
+    }
+    let delay = app.backoff.delay(retries);
+    eprintln!(
+      "Error: {:#}. Reconnecting from {} in {:?}, attempt {} of {}.",
+      error, app.height, delay, retries, app.max_retries
+    );
+    tokio::time::sleep(delay).await;
+  }
+}
+
+/// Index the finalized blocks from `app.height`, storing the events of the
+/// watched contract.
+async fn index(app: &mut App, store: &mut EventStore) -> anyhow::Result<()> {
+  let mut client = v2::Client::new(app.endpoint.clone())
     .await
     .context("Cannot connect.")?;
-
   println!("Getting finalized blocks from {}.", app.height);
 
-  let mut receiver = client.get_finalized_blocks_from(app.height).await?;
-  while let Some(v) = receiver.next().await {
-    let bi = client.get_block_info(v.block_hash).await?;
+  let receiver = client.get_finalized_blocks_from(app.height).await?;
+  let blocks = futures::stream::unfold(receiver, |mut receiver| async move {
+    receiver.next().await.map(|block| (block, receiver))
+  });
+  let mut indexer = BlockIndexer {
+    client,
+    store,
+    contract: app.contract,
+    webhook: app.webhook.as_ref(),
+    reverted_stats: RevertedStats::default(),
+  };
+  index_blocks(blocks, &mut app.height, &app.control, &mut indexer).await
+}
+
+/// Stores the events of the watched contract in the blocks passed by
+/// [`index_blocks`].
+struct BlockIndexer<'a> {
+  client: v2::Client,
+  store: &'a mut EventStore,
+  contract: ContractAddress,
+  webhook: Option<&'a WebhookQueue>,
+  reverted_stats: RevertedStats,
+}
+
+impl BlockProcessor for BlockIndexer<'_> {
+  async fn process(&mut self, v: FinalizedBlockInfo) -> anyhow::Result<()> {
+    let bi = self.client.get_block_info(v.block_hash).await?;
+    let mut webhook_events = Vec::new();
     if bi.response.transaction_count > 0 {
-      let mut events = client
+      let mut events = self
+        .client
         .get_block_transaction_events(v.block_hash)
         .await?
         .response;
       while let Some(event) = events.next().await.transpose()? {
-        if event
-          .affected_contracts()
-          .contains(&ContractAddress::new(7418, 0))
-        {
-          let events: Vec<ContractEvent> = event
-            .contract_update_logs()
-            .unwrap()
-            .flat_map(|(_, events)| events)
-            .skip(1)
-            .cloned()
-            .collect();
-
-          println!("EVENTS \n {:?}", events);
-
-          for event in events {
-            println!("EVENT \n {}", event.to_string());
-            let test: MintEvent = event.parse()?;
-            println!("{:?}", test);
+        if let Some(reverted) = reverted_update(&event, self.contract) {
+          self.reverted_stats.record(&reverted);
+          if let Some(failed) = failed_update(&event) {
+            println!(
+              "REVERTED {} in tx {} with code {}: {}, {} reverted so far",
+              failed.entrypoint,
+              failed.tx_hash,
+              failed.reject_code,
+              failed.reason,
+              self.reverted_stats.total
+            );
+          }
+        }
+        if let Some(logs) = event.contract_update_logs() {
+          for (_, events) in logs.filter(|(address, _)| *address == self.contract) {
+            for contract_event in events {
+              let stored = StoredEvent {
+                block_height: v.height.height,
+                block_hash: v.block_hash.to_string(),
+                tx_hash: event.hash.to_string(),
+                event: contract_event.as_ref().to_vec(),
+              };
+              println!(
+                "EVENT block {} tx {}: {}",
+                stored.block_height,
+                stored.tx_hash,
+                event_value(&stored.event)
+              );
+              if self.webhook.is_some() {
+                webhook_events.push(event_json(&stored));
+              }
+              self.store.push(stored)?;
+            }
           }
-
-          // println!(
-          //   "Transaction {} with sender {}.",
-          //   &event.hash,
-          //   event.sender_account().unwrap()
-          // );
         }
       }
     }
+    self.store.end_block(v.height.height)?;
+    // The events of an interrupted block are not delivered, the block is
+    // indexed again.
+    if let Some(webhook) = self.webhook {
+      webhook_events
+        .into_iter()
+        .for_each(|body| webhook.push(body));
+    }
+    Ok(())
   }
-  Ok(())
 }
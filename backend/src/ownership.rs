@@ -0,0 +1,95 @@
+//! Reconstruction of the token owners from the events of the contract.
+use std::collections::BTreeMap;
+
+use ciphers_nft::{
+  cis2::{ContractTokenAmount, ContractTokenId},
+  events::ContractEvent,
+};
+use concordium_rust_sdk::types::Address;
+
+/// The number of copies of every token held by each address, built by
+/// applying the events in order. Unique tokens have a single copy, editions
+/// can be held by several addresses.
+#[derive(Debug, Default)]
+pub struct Ownership {
+  balances: BTreeMap<(ContractTokenId, Address), u32>,
+}
+
+impl Ownership {
+  /// Update the balances with an event. Events not changing ownership are
+  /// ignored.
+  pub fn apply(&mut self, event: &ContractEvent) {
+    match event {
+      ContractEvent::Mint(mint) => self.add(mint.token_id, mint.owner, mint.amount),
+      ContractEvent::Transfer(transfer) => {
+        self.remove(transfer.token_id, transfer.from, transfer.amount);
+        self.add(transfer.token_id, transfer.to, transfer.amount);
+      }
+      ContractEvent::Burn(burn) => self.remove(burn.token_id, burn.owner, burn.amount),
+      _ => {}
+    }
+  }
+
+  fn add(&mut self, token_id: ContractTokenId, address: Address, amount: ContractTokenAmount) {
+    if amount.0 == 0 {
+      return;
+    }
+    let balance = self.balances.entry((token_id, address)).or_insert(0);
+    *balance = balance.saturating_add(u32::from(amount.0));
+  }
+
+  fn remove(&mut self, token_id: ContractTokenId, address: Address, amount: ContractTokenAmount) {
+    let key = (token_id, address);
+    let Some(balance) = self.balances.get_mut(&key) else {
+      return;
+    };
+    *balance = balance.saturating_sub(u32::from(amount.0));
+    if *balance == 0 {
+      self.balances.remove(&key);
+    }
+  }
+
+  /// The number of copies of a token held by an address.
+  pub fn balance_of(&self, token_id: &ContractTokenId, address: &Address) -> u32 {
+    self
+      .balances
+      .get(&(*token_id, *address))
+      .copied()
+      .unwrap_or(0)
+  }
+
+  /// Every address holding a copy of a token, with its number of copies.
+  pub fn owners_of(&self, token_id: &ContractTokenId) -> Vec<(Address, u32)> {
+    self
+      .balances
+      .iter()
+      .filter(|((token, _), _)| token == token_id)
+      .map(|((_, address), balance)| (*address, *balance))
+      .collect()
+  }
+
+  /// The only address holding a token, `None` once it is burned or while
+  /// the copies of an edition are held by several addresses.
+  pub fn owner_of(&self, token_id: &ContractTokenId) -> Option<Address> {
+    match self.owners_of(token_id)[..] {
+      [(owner, _)] => Some(owner),
+      _ => None,
+    }
+  }
+
+  /// The `top_n` addresses owning the most tokens, with their token count
+  /// counting every copy of an edition.
+  /// Addresses with the same count are ordered by address.
+  pub fn leaderboard(&self, top_n: usize) -> Vec<(Address, u32)> {
+    let mut counts: BTreeMap<Address, u32> = BTreeMap::new();
+    for ((_, owner), balance) in &self.balances {
+      let count = counts.entry(*owner).or_insert(0);
+      *count = count.saturating_add(*balance);
+    }
+    let mut leaderboard: Vec<(Address, u32)> = counts.into_iter().collect();
+    // The sort is stable, so ties keep the address order of the map.
+    leaderboard.sort_by(|(_, a), (_, b)| b.cmp(a));
+    leaderboard.truncate(top_n);
+    leaderboard
+  }
+}
@@ -0,0 +1,33 @@
+//! Backoff between reconnects to the node.
+//!
+//! The delay doubles with every failed attempt in a row, up to a cap, so a
+//! node that is down for a while is not flooded with connection attempts.
+use std::time::Duration;
+
+/// Exponential backoff, capped at `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+  /// Delay before the first retry.
+  pub initial: Duration,
+  /// Longest delay between retries.
+  pub max: Duration,
+}
+
+impl Default for Backoff {
+  fn default() -> Self {
+    Backoff {
+      initial: Duration::from_secs(1),
+      max: Duration::from_secs(60),
+    }
+  }
+}
+
+impl Backoff {
+  /// Delay before retry `attempt`, counting from 1.
+  pub fn delay(&self, attempt: u32) -> Duration {
+    let factor = 1u32
+      .checked_shl(attempt.saturating_sub(1))
+      .unwrap_or(u32::MAX);
+    self.initial.saturating_mul(factor).min(self.max)
+  }
+}
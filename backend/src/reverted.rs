@@ -0,0 +1,108 @@
+//! Detection of contract updates that were rejected by the watched contract.
+use std::collections::BTreeMap;
+
+use ciphers_nft::error::{error_from_reject_code, ContractError};
+use concordium_rust_sdk::types::{
+  hashes::TransactionHash,
+  smart_contracts::{concordium_contracts_common::OwnedEntrypointName, OwnedReceiveName},
+  AccountTransactionEffects, BlockItemSummary, BlockItemSummaryDetails, ContractAddress,
+  RejectReason,
+};
+
+/// An update of the watched contract that was rejected by the contract.
+#[derive(Debug, PartialEq)]
+pub struct RevertedUpdate {
+  pub contract: ContractAddress,
+  pub receive_name: OwnedReceiveName,
+  /// The reject code returned by the contract.
+  pub reject_code: i32,
+  /// The contract error for the reject code, if the code is known.
+  pub error: Option<ContractError>,
+}
+
+/// A contract update that failed, with the context needed to diagnose it.
+#[derive(Debug, PartialEq)]
+pub struct FailedUpdate {
+  pub tx_hash: TransactionHash,
+  pub contract: ContractAddress,
+  pub entrypoint: OwnedEntrypointName,
+  /// The reject code returned by the contract.
+  pub reject_code: i32,
+  /// The contract error for the reject code, or a note that it is unknown.
+  pub reason: String,
+}
+
+/// Returns the contract, receive name and reject code if the transaction is
+/// an update rejected by a contract.
+fn rejected_receive(
+  summary: &BlockItemSummary,
+) -> Option<(ContractAddress, &OwnedReceiveName, i32)> {
+  let BlockItemSummaryDetails::AccountTransaction(details) = &summary.details else {
+    return None;
+  };
+  let AccountTransactionEffects::None {
+    reject_reason:
+      RejectReason::RejectedReceive {
+        reject_reason,
+        contract_address,
+        receive_name,
+        ..
+      },
+    ..
+  } = &details.effects
+  else {
+    return None;
+  };
+  Some((*contract_address, receive_name, *reject_reason))
+}
+
+/// Returns the reverted update if the transaction is an update rejected by
+/// `contract`.
+pub fn reverted_update(
+  summary: &BlockItemSummary,
+  contract: ContractAddress,
+) -> Option<RevertedUpdate> {
+  let (contract_address, receive_name, reject_code) = rejected_receive(summary)?;
+  if contract_address != contract {
+    return None;
+  }
+
+  Some(RevertedUpdate {
+    contract,
+    receive_name: receive_name.clone(),
+    reject_code,
+    error: error_from_reject_code(reject_code),
+  })
+}
+
+/// Returns the failed update if the transaction is an update rejected by any
+/// contract.
+pub fn failed_update(summary: &BlockItemSummary) -> Option<FailedUpdate> {
+  let (contract, receive_name, reject_code) = rejected_receive(summary)?;
+  let reason = match error_from_reject_code(reject_code) {
+    Some(error) => format!("{:?}", error),
+    None => format!("unknown reject code {}", reject_code),
+  };
+
+  Some(FailedUpdate {
+    tx_hash: summary.hash,
+    contract,
+    entrypoint: receive_name.as_receive_name().entrypoint_name().to_owned(),
+    reject_code,
+    reason,
+  })
+}
+
+/// Counts of the reverted updates seen by the indexer.
+#[derive(Debug, Default)]
+pub struct RevertedStats {
+  pub total: u64,
+  pub by_reject_code: BTreeMap<i32, u64>,
+}
+
+impl RevertedStats {
+  pub fn record(&mut self, update: &RevertedUpdate) {
+    self.total += 1;
+    *self.by_reject_code.entry(update.reject_code).or_insert(0) += 1;
+  }
+}
@@ -0,0 +1,293 @@
+//! Storage of the events logged by the watched contract in SQLite.
+//!
+//! Events are buffered in memory and written in a single transaction per
+//! flush, which is much faster than a write per event during backfills.
+//!
+//! Besides the raw events, the decoded mints, transfers and metadata updates
+//! are written to their own tables, and the height of the last completed
+//! block to the `checkpoints` table in the same transaction, so a restarted
+//! indexer resumes after it.
+use std::{path::Path, str::FromStr};
+
+use ciphers_nft::events::ContractEvent;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+use crate::events::decode_event;
+
+/// The tables holding a row per event, all with the block and transaction the
+/// event was found in.
+const EVENT_TABLES: [&str; 4] = ["events", "mints", "transfers", "metadata_updates"];
+
+/// When the buffered events are written to the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlushPolicy {
+  /// Flush once this many events are buffered.
+  Events(usize),
+  /// Flush at the end of every block.
+  Block,
+}
+
+impl FromStr for FlushPolicy {
+  type Err = String;
+
+  /// Parse `block` or a positive number of events.
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    if value == "block" {
+      return Ok(FlushPolicy::Block);
+    }
+    match value.parse::<usize>() {
+      Ok(events) if events > 0 => Ok(FlushPolicy::Events(events)),
+      _ => Err(format!(
+        "expected `block` or a positive number of events, found {:?}",
+        value
+      )),
+    }
+  }
+}
+
+/// An event logged by the contract together with where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredEvent {
+  pub block_height: u64,
+  pub block_hash: String,
+  pub tx_hash: String,
+  /// The event as serialized by the contract.
+  pub event: Vec<u8>,
+}
+
+/// Buffered writer of [`StoredEvent`]s.
+///
+/// The buffer is flushed when the store is dropped, but errors are only
+/// reported by an explicit [`EventStore::flush`].
+pub struct EventStore {
+  conn: Connection,
+  policy: FlushPolicy,
+  buffer: Vec<StoredEvent>,
+  /// Height of the last completed block, not yet written as checkpoint.
+  checkpoint: Option<u64>,
+}
+
+impl EventStore {
+  /// Open the database at `path`, creating it and its tables if needed.
+  pub fn open(path: impl AsRef<Path>, policy: FlushPolicy) -> rusqlite::Result<Self> {
+    Self::new(Connection::open(path)?, policy)
+  }
+
+  /// Open a database that only lives as long as the store.
+  pub fn open_in_memory(policy: FlushPolicy) -> rusqlite::Result<Self> {
+    Self::new(Connection::open_in_memory()?, policy)
+  }
+
+  fn new(conn: Connection, policy: FlushPolicy) -> rusqlite::Result<Self> {
+    conn.execute_batch(
+      "CREATE TABLE IF NOT EXISTS events (
+        id INTEGER PRIMARY KEY,
+        block_height INTEGER NOT NULL,
+        block_hash TEXT NOT NULL,
+        tx_hash TEXT NOT NULL,
+        event BLOB NOT NULL
+      );
+      CREATE TABLE IF NOT EXISTS mints (
+        id INTEGER PRIMARY KEY,
+        block_height INTEGER NOT NULL,
+        block_hash TEXT NOT NULL,
+        tx_hash TEXT NOT NULL,
+        token_id TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        owner TEXT NOT NULL
+      );
+      CREATE TABLE IF NOT EXISTS transfers (
+        id INTEGER PRIMARY KEY,
+        block_height INTEGER NOT NULL,
+        block_hash TEXT NOT NULL,
+        tx_hash TEXT NOT NULL,
+        token_id TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        from_address TEXT NOT NULL,
+        to_address TEXT NOT NULL
+      );
+      CREATE TABLE IF NOT EXISTS metadata_updates (
+        id INTEGER PRIMARY KEY,
+        block_height INTEGER NOT NULL,
+        block_hash TEXT NOT NULL,
+        tx_hash TEXT NOT NULL,
+        token_id TEXT NOT NULL,
+        metadata_url TEXT NOT NULL
+      );
+      CREATE TABLE IF NOT EXISTS checkpoints (
+        id INTEGER PRIMARY KEY CHECK (id = 0),
+        block_height INTEGER NOT NULL
+      );",
+    )?;
+    Ok(EventStore {
+      conn,
+      policy,
+      buffer: Vec::new(),
+      checkpoint: None,
+    })
+  }
+
+  /// The height to resume indexing from, the block after the checkpoint, or
+  /// `None` if no block was completed yet.
+  ///
+  /// Events of blocks after the checkpoint were flushed before their block
+  /// completed, and are removed so they are not stored twice once the block
+  /// is indexed again.
+  pub fn resume_height(&mut self) -> rusqlite::Result<Option<u64>> {
+    let Some(height) = self.checkpoint()? else {
+      return Ok(None);
+    };
+    self.truncate_from(height + 1)?;
+    Ok(Some(height + 1))
+  }
+
+  /// Remove the written events of the blocks from `height` on, so the blocks
+  /// can be indexed again. Buffered events are not affected.
+  pub fn truncate_from(&mut self, height: u64) -> rusqlite::Result<()> {
+    let tx = self.conn.transaction()?;
+    for table in EVENT_TABLES {
+      tx.execute(
+        &format!("DELETE FROM {} WHERE block_height >= ?1", table),
+        [height],
+      )?;
+    }
+    tx.commit()
+  }
+
+  /// Height of the last completed block written to the database.
+  pub fn checkpoint(&self) -> rusqlite::Result<Option<u64>> {
+    self
+      .conn
+      .query_row(
+        "SELECT block_height FROM checkpoints WHERE id = 0",
+        [],
+        |row| row.get(0),
+      )
+      .optional()
+  }
+
+  /// Buffer an event, flushing if the buffer is full.
+  pub fn push(&mut self, event: StoredEvent) -> rusqlite::Result<()> {
+    self.buffer.push(event);
+    match self.policy {
+      FlushPolicy::Events(size) if self.buffer.len() >= size => self.flush(),
+      _ => Ok(()),
+    }
+  }
+
+  /// Mark the end of the block at `height`, flushing with
+  /// [`FlushPolicy::Block`]. The block becomes the checkpoint on the next
+  /// flush.
+  pub fn end_block(&mut self, height: u64) -> rusqlite::Result<()> {
+    self.checkpoint = Some(height);
+    match self.policy {
+      FlushPolicy::Block => self.flush(),
+      FlushPolicy::Events(_) => Ok(()),
+    }
+  }
+
+  /// Write all buffered events and the checkpoint in a single transaction.
+  pub fn flush(&mut self) -> rusqlite::Result<()> {
+    if self.buffer.is_empty() && self.checkpoint.is_none() {
+      return Ok(());
+    }
+    let tx = self.conn.transaction()?;
+    {
+      let mut insert = tx.prepare_cached(
+        "INSERT INTO events (block_height, block_hash, tx_hash, event) VALUES (?1, ?2, ?3, ?4)",
+      )?;
+      for event in &self.buffer {
+        insert.execute(params![
+          event.block_height,
+          event.block_hash,
+          event.tx_hash,
+          event.event
+        ])?;
+        insert_decoded(&tx, event)?;
+      }
+      if let Some(height) = self.checkpoint {
+        tx.execute(
+          "INSERT OR REPLACE INTO checkpoints (id, block_height) VALUES (0, ?1)",
+          [height],
+        )?;
+      }
+    }
+    tx.commit()?;
+    self.buffer.clear();
+    self.checkpoint = None;
+    Ok(())
+  }
+
+  /// Number of events waiting to be flushed.
+  pub fn buffered(&self) -> usize {
+    self.buffer.len()
+  }
+
+  /// Number of events written to the database.
+  pub fn stored(&self) -> rusqlite::Result<u64> {
+    self
+      .conn
+      .query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))
+  }
+
+  /// The database connection, for queries over the stored tables.
+  pub fn connection(&self) -> &Connection {
+    &self.conn
+  }
+}
+
+/// Write the row of a mint, transfer or metadata update. Other events are
+/// only stored raw.
+fn insert_decoded(tx: &Transaction, event: &StoredEvent) -> rusqlite::Result<()> {
+  let (height, block, hash) = (event.block_height, &event.block_hash, &event.tx_hash);
+  match decode_event(&event.event) {
+    Some(ContractEvent::Mint(mint)) => tx
+      .prepare_cached(
+        "INSERT INTO mints (block_height, block_hash, tx_hash, token_id, amount, owner)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+      )?
+      .execute(params![
+        height,
+        block,
+        hash,
+        mint.token_id.to_string(),
+        mint.amount.0,
+        mint.owner.to_string()
+      ])?,
+    Some(ContractEvent::Transfer(transfer)) => tx
+      .prepare_cached(
+        "INSERT INTO transfers
+        (block_height, block_hash, tx_hash, token_id, amount, from_address, to_address)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+      )?
+      .execute(params![
+        height,
+        block,
+        hash,
+        transfer.token_id.to_string(),
+        transfer.amount.0,
+        transfer.from.to_string(),
+        transfer.to.to_string()
+      ])?,
+    Some(ContractEvent::TokenMetadata(metadata)) => tx
+      .prepare_cached(
+        "INSERT INTO metadata_updates (block_height, block_hash, tx_hash, token_id, metadata_url)
+        VALUES (?1, ?2, ?3, ?4, ?5)",
+      )?
+      .execute(params![
+        height,
+        block,
+        hash,
+        metadata.token_id.to_string(),
+        metadata.metadata_url.url
+      ])?,
+    _ => 0,
+  };
+  Ok(())
+}
+
+impl Drop for EventStore {
+  fn drop(&mut self) {
+    let _ = self.flush();
+  }
+}
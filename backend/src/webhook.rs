@@ -0,0 +1,138 @@
+//! Delivery of the events of the contract to a webhook.
+//!
+//! Every event is POSTed as JSON to the webhook URL. Failed deliveries are
+//! retried, and errors are returned to the caller so the indexer can log them
+//! and continue.
+//!
+//! The indexer queues the events of a block once the block is indexed, and a
+//! [`WebhookQueue`] delivers them in the background so a slow webhook does not
+//! hold up indexing. Delivery is at least once: the blocks after the last
+//! checkpoint are indexed again after a restart, and their events are
+//! delivered again. Events are only lost, with an error message, when all
+//! attempts fail, when the queue is full, or when they are still queued once
+//! the indexer gives up waiting for them at shutdown.
+use std::time::Duration;
+
+use anyhow::bail;
+use serde_json::{json, Value};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use crate::{events::event_value, store::StoredEvent};
+
+/// Time after which a request to the webhook fails, so a webhook that never
+/// answers does not stall the delivery.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Client POSTing events to a webhook URL.
+pub struct Webhook {
+  client: reqwest::Client,
+  url: String,
+  /// Number of attempts after the first one failed.
+  retries: u32,
+  /// Delay before the first retry, doubled for every following retry.
+  retry_delay: Duration,
+}
+
+/// The client used for the requests to the webhook, failing requests after
+/// `timeout`.
+fn client(timeout: Duration) -> reqwest::Client {
+  reqwest::Client::builder()
+    .timeout(timeout)
+    .build()
+    .expect("TLS backend cannot be initialized")
+}
+
+impl Webhook {
+  /// Webhook retrying failed deliveries three times, failing requests after
+  /// [`REQUEST_TIMEOUT`].
+  pub fn new(url: impl Into<String>) -> Self {
+    Webhook {
+      client: client(REQUEST_TIMEOUT),
+      url: url.into(),
+      retries: 3,
+      retry_delay: Duration::from_millis(500),
+    }
+  }
+
+  /// Set the time after which a request to the webhook fails.
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.client = client(timeout);
+    self
+  }
+
+  /// Set the number of retries and the delay before the first retry.
+  pub fn with_retries(mut self, retries: u32, retry_delay: Duration) -> Self {
+    self.retries = retries;
+    self.retry_delay = retry_delay;
+    self
+  }
+
+  /// POST the JSON body to the webhook, retrying on errors and non-2xx
+  /// responses. Returns the last error once all attempts failed.
+  pub async fn post(&self, body: &Value) -> anyhow::Result<()> {
+    let mut delay = self.retry_delay;
+    let mut attempt = 0;
+    loop {
+      let error = match self.client.post(&self.url).json(body).send().await {
+        Ok(response) if response.status().is_success() => return Ok(()),
+        Ok(response) => anyhow::anyhow!("Webhook responded with {}", response.status()),
+        Err(error) => error.into(),
+      };
+      if attempt == self.retries {
+        bail!("Webhook failed after {} attempts: {}", attempt + 1, error);
+      }
+      attempt += 1;
+      tokio::time::sleep(delay).await;
+      delay *= 2;
+    }
+  }
+
+  /// Deliver the bodies pushed to the returned queue in the background, in
+  /// the order they are pushed. At most `capacity` bodies wait for delivery.
+  pub fn spawn(self, capacity: usize) -> WebhookQueue {
+    let (sender, mut receiver) = mpsc::channel::<Value>(capacity);
+    let task = tokio::spawn(async move {
+      while let Some(body) = receiver.recv().await {
+        if let Err(error) = self.post(&body).await {
+          eprintln!("Cannot deliver event of tx {}: {}", body["tx_hash"], error);
+        }
+      }
+    });
+    WebhookQueue { sender, task }
+  }
+}
+
+/// Bodies waiting to be POSTed to a [`Webhook`] by a background task, see
+/// [`Webhook::spawn`].
+pub struct WebhookQueue {
+  sender: mpsc::Sender<Value>,
+  task: JoinHandle<()>,
+}
+
+impl WebhookQueue {
+  /// Queue a body for delivery. A body that does not fit in the queue is
+  /// dropped with an error message.
+  pub fn push(&self, body: Value) {
+    if let Err(error) = self.sender.try_send(body) {
+      eprintln!("Cannot queue event for the webhook: {}", error);
+    }
+  }
+
+  /// Wait until every queued body is delivered or failed.
+  pub async fn close(self) {
+    drop(self.sender);
+    // The task only ends by draining the queue, it does not panic.
+    let _ = self.task.await;
+  }
+}
+
+/// The JSON body POSTed for a stored event, with the event decoded by
+/// [`event_value`].
+pub fn event_json(stored: &StoredEvent) -> Value {
+  json!({
+    "block_height": stored.block_height,
+    "block_hash": stored.block_hash,
+    "tx_hash": stored.tx_hash,
+    "event": event_value(&stored.event),
+  })
+}
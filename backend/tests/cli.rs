@@ -0,0 +1,82 @@
+//! Tests for the command line arguments of the indexer.
+use backend::{cli::parse_args, store::FlushPolicy};
+use concordium_rust_sdk::types::{AbsoluteBlockHeight, ContractAddress};
+
+/// Test that the defaults index the testnet contract from the original start
+/// height.
+#[test]
+fn test_default_args() {
+  let args = parse_args(["backend"]).expect("Default arguments");
+  assert_eq!(
+    args.endpoint.uri().to_string(),
+    "http://node.testnet.concordium.com:20000/"
+  );
+  assert_eq!(args.contract, ContractAddress::new(7418, 0));
+  assert_eq!(args.start_height, AbsoluteBlockHeight::from(7_921_000));
+  assert_eq!(args.db_path, "events.db");
+  assert_eq!(args.flush_policy, FlushPolicy::Events(100));
+  assert_eq!(args.max_retries, 10);
+  assert_eq!(args.webhook_url, None);
+}
+
+/// Test that every argument overrides its default.
+#[test]
+fn test_custom_args() {
+  let args = parse_args([
+    "backend",
+    "--endpoint",
+    "http://localhost:20001",
+    "--contract-index",
+    "12",
+    "--contract-subindex",
+    "3",
+    "--start-height",
+    "42",
+    "--db-path",
+    "/tmp/indexer.db",
+    "--flush-every",
+    "block",
+    "--max-retries",
+    "3",
+    "--webhook-url",
+    "http://localhost:8080/events",
+  ])
+  .expect("Custom arguments");
+  assert_eq!(args.endpoint.uri().to_string(), "http://localhost:20001/");
+  assert_eq!(args.contract, ContractAddress::new(12, 3));
+  assert_eq!(args.start_height, AbsoluteBlockHeight::from(42));
+  assert_eq!(args.db_path, "/tmp/indexer.db");
+  assert_eq!(args.flush_policy, FlushPolicy::Block);
+  assert_eq!(args.max_retries, 3);
+  assert_eq!(
+    args.webhook_url.as_deref(),
+    Some("http://localhost:8080/events")
+  );
+}
+
+/// Test that endpoints that are not URLs, non-numeric indices and invalid
+/// flush intervals are rejected.
+#[test]
+fn test_invalid_args() {
+  for endpoint in ["not a url", "node.testnet.concordium.com:20000"] {
+    let error = parse_args(["backend", "--endpoint", endpoint]).expect_err("Invalid endpoint");
+    assert!(
+      error.to_string().contains("Invalid endpoint URL"),
+      "{}",
+      error
+    );
+  }
+  let error = parse_args(["backend", "--contract-index", "abc"]).expect_err("Invalid index");
+  assert!(error.downcast_ref::<clap::Error>().is_some());
+  for flush_every in ["0", "never"] {
+    let error = parse_args(["backend", "--flush-every", flush_every]).expect_err("Invalid flush");
+    assert!(error.downcast_ref::<clap::Error>().is_some());
+  }
+}
+
+/// Test that `--flush-every` takes a number of events.
+#[test]
+fn test_flush_every_events() {
+  let args = parse_args(["backend", "--flush-every", "25"]).expect("Flush every 25 events");
+  assert_eq!(args.flush_policy, FlushPolicy::Events(25));
+}
@@ -0,0 +1,87 @@
+//! Tests for pausing and resuming the indexer.
+use std::time::Duration;
+
+use backend::control::{index_blocks, BlockProcessor, PauseControl};
+use concordium_rust_sdk::{
+  types::{hashes::BlockHash, AbsoluteBlockHeight},
+  v2::FinalizedBlockInfo,
+};
+use futures::Stream;
+
+/// The height after the last finalized block.
+const END_HEIGHT: u64 = 103;
+
+/// The finalized blocks from `height` until [`END_HEIGHT`].
+fn blocks_from(height: AbsoluteBlockHeight) -> impl Stream<Item = FinalizedBlockInfo> {
+  futures::stream::iter(
+    (height.height..END_HEIGHT).map(|height| FinalizedBlockInfo {
+      block_hash: BlockHash::new([0; 32]),
+      height: AbsoluteBlockHeight::from(height),
+    }),
+  )
+}
+
+/// Records the processed heights and pauses after processing `pause_at`.
+struct Recorder {
+  control: PauseControl,
+  pause_at: u64,
+  processed: Vec<u64>,
+}
+
+impl BlockProcessor for Recorder {
+  async fn process(&mut self, block: FinalizedBlockInfo) -> anyhow::Result<()> {
+    self.processed.push(block.height.height);
+    if block.height.height == self.pause_at {
+      self.control.pause();
+    }
+    Ok(())
+  }
+}
+
+#[tokio::test]
+async fn test_paused_indexer_resumes_from_next_height() {
+  let control = PauseControl::default();
+  let mut recorder = Recorder {
+    control: control.clone(),
+    pause_at: 100,
+    processed: Vec::new(),
+  };
+  let mut height = AbsoluteBlockHeight::from(100);
+
+  let indexing = index_blocks(blocks_from(height), &mut height, &control, &mut recorder);
+  tokio::time::timeout(Duration::from_millis(50), indexing)
+    .await
+    .expect_err("Indexer waits while paused");
+  assert!(control.is_paused());
+  assert_eq!(recorder.processed, [100]);
+  assert_eq!(height, AbsoluteBlockHeight::from(101));
+
+  control.resume();
+  tokio::time::timeout(
+    Duration::from_secs(5),
+    index_blocks(blocks_from(height), &mut height, &control, &mut recorder),
+  )
+  .await
+  .expect("Blocks processed in time")
+  .expect("Blocks processed");
+  assert_eq!(recorder.processed, [100, 101, 102]);
+  assert_eq!(height, AbsoluteBlockHeight::from(END_HEIGHT));
+}
+
+#[tokio::test]
+async fn test_failed_block_is_indexed_again() {
+  struct Failing;
+
+  impl BlockProcessor for Failing {
+    async fn process(&mut self, _block: FinalizedBlockInfo) -> anyhow::Result<()> {
+      anyhow::bail!("Cannot process the block.")
+    }
+  }
+
+  let control = PauseControl::default();
+  let mut height = AbsoluteBlockHeight::from(100);
+  index_blocks(blocks_from(height), &mut height, &control, &mut Failing)
+    .await
+    .expect_err("Processing fails");
+  assert_eq!(height, AbsoluteBlockHeight::from(100));
+}
@@ -0,0 +1,61 @@
+//! Tests for the decoding of the events of the contract.
+use backend::events::{decode_event, event_value};
+use ciphers_nft::{
+  cis2::{ContractTokenAmount, ContractTokenId},
+  events::{BurnEvent, ContractEvent, MintEvent, TransferEvent},
+};
+use concordium_rust_sdk::{
+  id::types::AccountAddress,
+  types::{smart_contracts::concordium_contracts_common::to_bytes, Address},
+};
+use serde_json::{json, Value};
+
+const USER: AccountAddress = AccountAddress([1; 32]);
+const USER2: AccountAddress = AccountAddress([2; 32]);
+
+/// Test that every log is decoded on its own, whatever the order of the
+/// events, and that unknown events are kept as raw bytes.
+#[test]
+fn test_decode_events_in_any_order() {
+  let token_id = ContractTokenId::from(7);
+  let amount = ContractTokenAmount::from(1);
+  let logs = [
+    to_bytes(&ContractEvent::Transfer(TransferEvent {
+      token_id,
+      amount,
+      from: Address::Account(USER),
+      to: Address::Account(USER2),
+    })),
+    to_bytes(&ContractEvent::Mint(MintEvent {
+      token_id,
+      amount,
+      owner: Address::Account(USER),
+    })),
+    to_bytes(&ContractEvent::Burn(BurnEvent {
+      token_id,
+      amount,
+      owner: Address::Account(USER2),
+    })),
+    vec![200, 1, 2],
+  ];
+
+  let types: Vec<Value> = logs
+    .iter()
+    .map(|log| event_value(log)["type"].clone())
+    .collect();
+  assert_eq!(
+    types,
+    vec![
+      json!("Transfer"),
+      json!("Mint"),
+      json!("Burn"),
+      json!("Unknown")
+    ]
+  );
+  assert!(matches!(
+    decode_event(&logs[1]),
+    Some(ContractEvent::Mint(event)) if event.token_id == token_id
+  ));
+  assert_eq!(decode_event(&logs[3]), None);
+  assert_eq!(event_value(&logs[3])["bytes"], json!("c80102"));
+}
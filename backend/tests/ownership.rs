@@ -0,0 +1,110 @@
+//! Tests for the reconstruction of token ownership.
+use backend::ownership::Ownership;
+use ciphers_nft::{
+  cis2::{ContractTokenAmount, ContractTokenId},
+  events::{BurnEvent, ContractEvent, MintEvent, TransferEvent},
+};
+use concordium_rust_sdk::{id::types::AccountAddress, types::Address};
+
+const ALICE: Address = Address::Account(AccountAddress([1; 32]));
+const BOB: Address = Address::Account(AccountAddress([2; 32]));
+const CHARLIE: Address = Address::Account(AccountAddress([3; 32]));
+
+fn mint(token_id: u32, owner: Address) -> ContractEvent {
+  ContractEvent::Mint(MintEvent {
+    token_id: ContractTokenId::from(token_id),
+    amount: ContractTokenAmount::from(1),
+    owner,
+  })
+}
+
+fn transfer(token_id: u32, from: Address, to: Address) -> ContractEvent {
+  ContractEvent::Transfer(TransferEvent {
+    token_id: ContractTokenId::from(token_id),
+    amount: ContractTokenAmount::from(1),
+    from,
+    to,
+  })
+}
+
+fn burn(token_id: u32, owner: Address) -> ContractEvent {
+  ContractEvent::Burn(BurnEvent {
+    token_id: ContractTokenId::from(token_id),
+    amount: ContractTokenAmount::from(1),
+    owner,
+  })
+}
+
+#[test]
+fn test_leaderboard_orders_by_count_then_address() {
+  let events = [
+    mint(1, ALICE),
+    mint(2, ALICE),
+    mint(3, BOB),
+    mint(4, CHARLIE),
+    mint(5, CHARLIE),
+    mint(6, CHARLIE),
+    transfer(6, CHARLIE, BOB),
+  ];
+  let mut ownership = Ownership::default();
+  for event in &events {
+    ownership.apply(event);
+  }
+
+  assert_eq!(ownership.owner_of(&ContractTokenId::from(6)), Some(BOB));
+  // Every address owns two tokens, so they are ordered by address.
+  assert_eq!(
+    ownership.leaderboard(10),
+    vec![(ALICE, 2), (BOB, 2), (CHARLIE, 2)]
+  );
+
+  ownership.apply(&transfer(1, ALICE, CHARLIE));
+  assert_eq!(ownership.leaderboard(2), vec![(CHARLIE, 3), (BOB, 2)]);
+}
+
+#[test]
+fn test_burn_removes_owner() {
+  let mut ownership = Ownership::default();
+  for event in &[mint(1, ALICE), mint(2, ALICE), transfer(1, ALICE, BOB)] {
+    ownership.apply(event);
+  }
+  ownership.apply(&burn(1, BOB));
+
+  assert_eq!(ownership.owner_of(&ContractTokenId::from(1)), None);
+  assert_eq!(ownership.owners_of(&ContractTokenId::from(1)), vec![]);
+  assert_eq!(ownership.leaderboard(10), vec![(ALICE, 1)]);
+}
+
+#[test]
+fn test_edition_balances() {
+  let edition = ContractTokenId::from(7);
+  let mut ownership = Ownership::default();
+  for event in &[
+    mint(7, ALICE),
+    mint(7, ALICE),
+    mint(7, ALICE),
+    mint(7, BOB),
+    transfer(7, ALICE, BOB),
+  ] {
+    ownership.apply(event);
+  }
+
+  assert_eq!(ownership.balance_of(&edition, &ALICE), 2);
+  assert_eq!(ownership.balance_of(&edition, &BOB), 2);
+  assert_eq!(ownership.owners_of(&edition), vec![(ALICE, 2), (BOB, 2)]);
+  assert_eq!(ownership.owner_of(&edition), None);
+  assert_eq!(ownership.leaderboard(10), vec![(ALICE, 2), (BOB, 2)]);
+
+  // A transfer or burn of one copy keeps the other copies.
+  ownership.apply(&transfer(7, BOB, CHARLIE));
+  ownership.apply(&burn(7, ALICE));
+  assert_eq!(
+    ownership.owners_of(&edition),
+    vec![(ALICE, 1), (BOB, 1), (CHARLIE, 1)]
+  );
+
+  ownership.apply(&burn(7, ALICE));
+  ownership.apply(&burn(7, BOB));
+  assert_eq!(ownership.owner_of(&edition), Some(CHARLIE));
+  assert_eq!(ownership.leaderboard(10), vec![(CHARLIE, 1)]);
+}
@@ -0,0 +1,18 @@
+//! Tests for the backoff between reconnects.
+use std::time::Duration;
+
+use backend::retry::Backoff;
+
+/// Test that the delay doubles with every attempt until it reaches the cap.
+#[test]
+fn test_backoff_doubles_up_to_cap() {
+  let backoff = Backoff {
+    initial: Duration::from_millis(500),
+    max: Duration::from_secs(10),
+  };
+  let delays: Vec<u64> = (1..=7)
+    .map(|attempt| backoff.delay(attempt).as_millis() as u64)
+    .collect();
+  assert_eq!(delays, [500, 1000, 2000, 4000, 8000, 10_000, 10_000]);
+  assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(10));
+}
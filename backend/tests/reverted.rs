@@ -0,0 +1,109 @@
+//! Tests for the detection of reverted updates.
+use backend::reverted::{
+  failed_update, reverted_update, FailedUpdate, RevertedStats, RevertedUpdate,
+};
+use ciphers_nft::error::{ContractError, CustomContractError};
+use concordium_rust_sdk::id::types::AccountAddress;
+use concordium_rust_sdk::{
+  common::types::Amount,
+  types::{
+    hashes::TransactionHash,
+    smart_contracts::{
+      concordium_contracts_common::OwnedEntrypointName, OwnedParameter, OwnedReceiveName,
+    },
+    AccountTransactionDetails, AccountTransactionEffects, BlockItemSummary,
+    BlockItemSummaryDetails, ContractAddress, Energy, RejectReason, TransactionIndex,
+    TransactionType,
+  },
+};
+
+const CONTRACT: ContractAddress = ContractAddress {
+  index: 7418,
+  subindex: 0,
+};
+
+/// A summary of an update of `contract_address` rejected with `reject_code`.
+fn rejected_update(contract_address: ContractAddress, reject_code: i32) -> BlockItemSummary {
+  BlockItemSummary {
+    index: TransactionIndex { index: 0 },
+    energy_cost: Energy { energy: 1000 },
+    hash: TransactionHash::new([0; 32]),
+    details: BlockItemSummaryDetails::AccountTransaction(AccountTransactionDetails {
+      cost: Amount::from_micro_ccd(100),
+      sender: AccountAddress([1; 32]),
+      effects: AccountTransactionEffects::None {
+        transaction_type: Some(TransactionType::Update),
+        reject_reason: RejectReason::RejectedReceive {
+          reject_reason: reject_code,
+          contract_address,
+          receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.mint".to_string()),
+          parameter: OwnedParameter::empty(),
+        },
+      },
+    }),
+  }
+}
+
+#[test]
+fn test_reverted_update_captures_reject_reason() {
+  let summary = rejected_update(CONTRACT, -42000003);
+
+  let reverted = reverted_update(&summary, CONTRACT).expect("Reverted update");
+  assert_eq!(
+    reverted,
+    RevertedUpdate {
+      contract: CONTRACT,
+      receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.mint".to_string()),
+      reject_code: -42000003,
+      error: Some(ContractError::Unauthorized),
+    }
+  );
+}
+
+#[test]
+fn test_reverted_update_maps_custom_errors() {
+  // `MintDeadlineReached` is the seventh variant of `CustomContractError`.
+  let summary = rejected_update(CONTRACT, -7);
+
+  let reverted = reverted_update(&summary, CONTRACT).expect("Reverted update");
+  assert_eq!(
+    reverted.error,
+    Some(ContractError::Custom(
+      CustomContractError::MintDeadlineReached
+    ))
+  );
+
+  let mut stats = RevertedStats::default();
+  stats.record(&reverted);
+  stats.record(&reverted);
+  assert_eq!(stats.total, 2);
+  assert_eq!(stats.by_reject_code.get(&-7), Some(&2));
+}
+
+#[test]
+fn test_reverted_update_ignores_other_contracts() {
+  let summary = rejected_update(ContractAddress::new(1, 0), -42000003);
+
+  assert_eq!(reverted_update(&summary, CONTRACT), None);
+}
+
+#[test]
+fn test_failed_update_extracts_transaction_context() {
+  // `MintingPaused` is the twelfth variant of `CustomContractError`.
+  let summary = rejected_update(ContractAddress::new(1, 0), -12);
+
+  let failed = failed_update(&summary).expect("Failed update");
+  assert_eq!(
+    failed,
+    FailedUpdate {
+      tx_hash: TransactionHash::new([0; 32]),
+      contract: ContractAddress::new(1, 0),
+      entrypoint: OwnedEntrypointName::new_unchecked("mint".to_string()),
+      reject_code: -12,
+      reason: "Custom(MintingPaused)".to_string(),
+    }
+  );
+
+  let failed = failed_update(&rejected_update(CONTRACT, -1000)).expect("Failed update");
+  assert_eq!(failed.reason, "unknown reject code -1000");
+}
@@ -0,0 +1,185 @@
+//! Tests for the buffered event storage.
+use backend::store::{EventStore, FlushPolicy, StoredEvent};
+use ciphers_nft::{
+  cis2::{ContractTokenAmount, ContractTokenId},
+  events::{ContractEvent, MintEvent, TransferEvent},
+};
+use concordium_rust_sdk::{
+  id::types::AccountAddress,
+  types::{smart_contracts::concordium_contracts_common::to_bytes, Address},
+};
+
+const USER: AccountAddress = AccountAddress([1; 32]);
+const USER2: AccountAddress = AccountAddress([2; 32]);
+
+fn stored_event(index: u64) -> StoredEvent {
+  StoredEvent {
+    block_height: 7_921_000 + index / 10,
+    block_hash: format!("block-{}", index / 10),
+    tx_hash: format!("tx-{}", index),
+    event: vec![254, index as u8],
+  }
+}
+
+#[test]
+fn test_store_flushes_every_batch_and_on_final_flush() {
+  let mut store = EventStore::open_in_memory(FlushPolicy::Events(10)).expect("Open store");
+
+  // Two and a half batches.
+  for index in 0..25 {
+    store.push(stored_event(index)).expect("Push event");
+  }
+  assert_eq!(store.stored().expect("Count events"), 20);
+  assert_eq!(store.buffered(), 5);
+
+  store.flush().expect("Flush");
+  assert_eq!(store.stored().expect("Count events"), 25);
+  assert_eq!(store.buffered(), 0);
+}
+
+#[test]
+fn test_store_flushes_at_block_boundary() {
+  let mut store = EventStore::open_in_memory(FlushPolicy::Block).expect("Open store");
+
+  for index in 0..3 {
+    store.push(stored_event(index)).expect("Push event");
+  }
+  assert_eq!(store.stored().expect("Count events"), 0);
+
+  store.end_block(7_921_000).expect("End block");
+  assert_eq!(store.stored().expect("Count events"), 3);
+}
+
+#[test]
+fn test_parse_flush_policy() {
+  assert_eq!("block".parse(), Ok(FlushPolicy::Block));
+  assert_eq!("25".parse(), Ok(FlushPolicy::Events(25)));
+  for invalid in ["0", "-1", "never"] {
+    assert!(invalid.parse::<FlushPolicy>().is_err());
+  }
+}
+
+/// Raw events as logged by the contract in one block: a mint of token 7, its
+/// metadata URL, a transfer and an event the indexer does not know.
+fn fixture_events() -> Vec<Vec<u8>> {
+  let token_id = ContractTokenId::from(7);
+  let amount = ContractTokenAmount::from(1);
+  let mut metadata = vec![251, 4, 7, 0, 0, 0, 11, 0];
+  metadata.extend_from_slice(b"ipfs://nft7");
+  metadata.push(0);
+  vec![
+    to_bytes(&ContractEvent::Mint(MintEvent {
+      token_id,
+      amount,
+      owner: Address::Account(USER),
+    })),
+    metadata,
+    to_bytes(&ContractEvent::Transfer(TransferEvent {
+      token_id,
+      amount,
+      from: Address::Account(USER),
+      to: Address::Account(USER2),
+    })),
+    vec![200, 1, 2],
+  ]
+}
+
+#[test]
+fn test_store_writes_decoded_rows_and_checkpoint() {
+  let mut store = EventStore::open_in_memory(FlushPolicy::Block).expect("Open store");
+  assert_eq!(store.resume_height().expect("Resume height"), None);
+
+  for (index, event) in fixture_events().into_iter().enumerate() {
+    store
+      .push(StoredEvent {
+        block_height: 7_921_000,
+        block_hash: "block-0".to_string(),
+        tx_hash: format!("tx-{}", index),
+        event,
+      })
+      .expect("Push event");
+  }
+  store.end_block(7_921_000).expect("End block");
+
+  let conn = store.connection();
+  let mint: (u64, String, String, String, u8, String) = conn
+    .query_row(
+      "SELECT block_height, block_hash, tx_hash, token_id, amount, owner FROM mints",
+      [],
+      |row| {
+        Ok((
+          row.get(0)?,
+          row.get(1)?,
+          row.get(2)?,
+          row.get(3)?,
+          row.get(4)?,
+          row.get(5)?,
+        ))
+      },
+    )
+    .expect("Mint row");
+  assert_eq!(
+    mint,
+    (
+      7_921_000,
+      "block-0".to_string(),
+      "tx-0".to_string(),
+      ContractTokenId::from(7).to_string(),
+      1,
+      Address::Account(USER).to_string()
+    )
+  );
+  let metadata: (String, String, String) = conn
+    .query_row(
+      "SELECT tx_hash, token_id, metadata_url FROM metadata_updates",
+      [],
+      |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+    .expect("Metadata row");
+  assert_eq!(
+    metadata,
+    (
+      "tx-1".to_string(),
+      ContractTokenId::from(7).to_string(),
+      "ipfs://nft7".to_string()
+    )
+  );
+  let transfer: (String, String, String) = conn
+    .query_row(
+      "SELECT tx_hash, from_address, to_address FROM transfers",
+      [],
+      |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+    .expect("Transfer row");
+  assert_eq!(
+    transfer,
+    (
+      "tx-2".to_string(),
+      Address::Account(USER).to_string(),
+      Address::Account(USER2).to_string()
+    )
+  );
+  assert_eq!(store.stored().expect("Count events"), 4);
+  assert_eq!(store.checkpoint().expect("Checkpoint"), Some(7_921_000));
+}
+
+#[test]
+fn test_store_resumes_after_checkpoint() {
+  let mut store = EventStore::open_in_memory(FlushPolicy::Events(10)).expect("Open store");
+  for index in 0..10 {
+    store.push(stored_event(index)).expect("Push event");
+  }
+  store.end_block(7_921_000).expect("End block");
+  // The first events of the next block are flushed before it completes.
+  for index in 10..20 {
+    store.push(stored_event(index)).expect("Push event");
+  }
+  assert_eq!(store.stored().expect("Count events"), 20);
+  assert_eq!(store.checkpoint().expect("Checkpoint"), Some(7_921_000));
+
+  assert_eq!(
+    store.resume_height().expect("Resume height"),
+    Some(7_921_001)
+  );
+  assert_eq!(store.stored().expect("Count events"), 10);
+}
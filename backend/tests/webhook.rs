@@ -0,0 +1,125 @@
+//! Tests for the delivery of events to a webhook.
+use std::time::Duration;
+
+use backend::{
+  store::StoredEvent,
+  webhook::{event_json, Webhook},
+};
+use ciphers_nft::{
+  cis2::{ContractTokenAmount, ContractTokenId},
+  events::{ContractEvent, MintEvent},
+};
+use concordium_rust_sdk::{
+  id::types::AccountAddress,
+  types::{smart_contracts::concordium_contracts_common::to_bytes, Address},
+};
+use mockito::Matcher;
+use serde_json::json;
+
+const USER: AccountAddress = AccountAddress([1; 32]);
+
+fn mint_event() -> StoredEvent {
+  let event = ContractEvent::Mint(MintEvent {
+    token_id: ContractTokenId::from(7),
+    amount: ContractTokenAmount::from(1),
+    owner: Address::Account(USER),
+  });
+  StoredEvent {
+    block_height: 7_921_000,
+    block_hash: "block".to_string(),
+    tx_hash: "tx".to_string(),
+    event: to_bytes(&event),
+  }
+}
+
+#[tokio::test]
+async fn test_webhook_posts_decoded_event() {
+  let mut server = mockito::Server::new_async().await;
+  let mock = server
+    .mock("POST", "/events")
+    .match_body(Matcher::PartialJson(json!({
+      "block_height": 7_921_000,
+      "tx_hash": "tx",
+      "event": {
+        "type": "Mint",
+        "token_id": ContractTokenId::from(7).to_string(),
+        "amount": 1,
+        "owner": Address::Account(USER).to_string(),
+      },
+    })))
+    .with_status(200)
+    .create_async()
+    .await;
+
+  let webhook = Webhook::new(format!("{}/events", server.url()));
+  webhook
+    .post(&event_json(&mint_event()))
+    .await
+    .expect("Post event");
+  mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_webhook_retries_on_error_status() {
+  let mut server = mockito::Server::new_async().await;
+  let mock = server
+    .mock("POST", "/events")
+    .with_status(500)
+    .expect(3)
+    .create_async()
+    .await;
+
+  let webhook =
+    Webhook::new(format!("{}/events", server.url())).with_retries(2, Duration::from_millis(1));
+  webhook
+    .post(&event_json(&mint_event()))
+    .await
+    .expect_err("Post did not fail");
+  mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_webhook_times_out() {
+  // A webhook that accepts the connection but never answers.
+  let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+    .await
+    .expect("Bind listener");
+  let address = listener.local_addr().expect("Listener address");
+  let server = tokio::spawn(async move {
+    let mut connections = Vec::new();
+    while let Ok((connection, _)) = listener.accept().await {
+      connections.push(connection);
+    }
+  });
+
+  let webhook = Webhook::new(format!("http://{}/events", address))
+    .with_timeout(Duration::from_millis(50))
+    .with_retries(1, Duration::from_millis(1));
+  tokio::time::timeout(
+    Duration::from_secs(5),
+    webhook.post(&event_json(&mint_event())),
+  )
+  .await
+  .expect("Post did not time out")
+  .expect_err("Post did not fail");
+  server.abort();
+}
+
+#[tokio::test]
+async fn test_webhook_queue_delivers_in_background() {
+  let mut server = mockito::Server::new_async().await;
+  let mock = server
+    .mock("POST", "/events")
+    .match_body(Matcher::PartialJson(json!({ "tx_hash": "tx" })))
+    .with_status(200)
+    .expect(2)
+    .create_async()
+    .await;
+
+  let queue = Webhook::new(format!("{}/events", server.url())).spawn(8);
+  for _ in 0..2 {
+    queue.push(event_json(&mint_event()));
+  }
+  queue.close().await;
+  mock.assert_async().await;
+}
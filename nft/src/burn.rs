@@ -0,0 +1,93 @@
+use concordium_std::*;
+
+use crate::{
+  cis2::{ContractTokenAmount, ContractTokenId},
+  error::{ContractError, ContractResult, CustomContractError},
+  events::{BurnEvent, ContractEvent},
+  state::State,
+};
+
+/// The parameter for the contract function `burn` which burns a token.
+#[derive(Serial, Deserial, SchemaType)]
+pub struct BurnParams {
+  /// The token to burn.
+  pub token_id: ContractTokenId,
+  /// The current owner of the token.
+  pub owner: Address,
+}
+
+/// Burn a copy of a token, removing the token from the contract with its last
+/// copy. The mint `counter` is not decremented, so mint counts stay stable
+/// and burned tokens still count towards `max_total_supply`.
+/// Can only be called by the owner of the token or one of its operators.
+/// With `burn_requires_admin` set, the sender must also be the contract owner,
+/// so the token owner consents by making the contract owner its operator.
+/// The deposit of a token minted with `mintWithDeposit` is transferred to the
+/// sender burning its last copy, which must then be an account.
+/// Logs a `Burn` event.
+///
+/// It rejects if:
+/// - Fails to parse parameter.
+/// - The token does not exist.
+/// - The sender is not the owner or an operator of the owner.
+/// - `burn_requires_admin` is set and the sender is not the contract owner.
+/// - The token is frozen or locked.
+/// - The token is not owned by `owner`.
+/// - The token has a deposit and the sender is not an account.
+/// - Fails to transfer the deposit to the sender.
+/// - Fails to log event.
+#[receive(
+  contract = "ciphers_nft",
+  name = "burn",
+  parameter = "BurnParams",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_burn(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  let params: BurnParams = ctx.parameter_cursor().get()?;
+  let sender = ctx.sender();
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+  let state = host.state_mut();
+
+  ensure!(
+    state.contains_token(&params.token_id),
+    ContractError::InvalidTokenId
+  );
+  ensure!(
+    params.owner == sender || state.is_operator(&sender, &params.owner, block_time),
+    ContractError::Unauthorized
+  );
+  ensure!(
+    !state.burn_requires_admin || sender.matches_account(&state.owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+  ensure!(
+    !state.frozen.contains(&params.token_id),
+    CustomContractError::TokenFrozen.into()
+  );
+  ensure!(
+    state.locked_until(&params.token_id, block_time).is_none(),
+    CustomContractError::TokenLocked.into()
+  );
+  let deposit = state.burn(&params.token_id, &params.owner)?;
+  if let Some(deposit) = deposit {
+    let Address::Account(burner) = sender else {
+      bail!(CustomContractError::InvalidAddress.into());
+    };
+    host
+      .invoke_transfer(&burner, deposit)
+      .map_err(CustomContractError::from)?;
+  }
+
+  logger.log(&ContractEvent::Burn(BurnEvent {
+    token_id: params.token_id,
+    amount: ContractTokenAmount::from(1),
+    owner: params.owner,
+  }))?;
+  Ok(())
+}
@@ -7,8 +7,8 @@
 //!
 //! In this example the contract is initialized with no tokens, and tokens can
 //! be minted through a `mint` contract function, which will only succeed for
-//! the contract owner. No functionality to burn token is defined in this
-//! example.
+//! the contract owner. Tokens can be burned by their owner through a `burn`
+//! contract function.
 //!
 //! Note: The word 'address' refers to either an account address or a
 //! contract address.
@@ -25,14 +25,23 @@ use concordium_cis2::*;
 use concordium_std::*;
 
 use crate::{
-  error::{ContractError, ContractResult},
-  state::State,
+  error::{ContractError, ContractResult, CustomContractError},
+  state::{State, NO_EXPIRY},
 };
 
 /// List of supported standards by this contract address.
 pub const SUPPORTS_STANDARDS: [StandardIdentifier<'static>; 2] =
   [CIS0_STANDARD_IDENTIFIER, CIS2_STANDARD_IDENTIFIER];
 
+/// Identifier signaling that the extra entrypoints of this contract, such as
+/// `viewSettings`, `getMintCountTokenID` and `viewAddress`, are available.
+pub const CIPHERS_NFT_EXT_STANDARD_IDENTIFIER: StandardIdentifier<'static> =
+  StandardIdentifier::new_unchecked("CIPHERS_NFT_EXT");
+
+/// List of custom standards supported by this contract address.
+pub const EXTENDED_STANDARDS: [StandardIdentifier<'static>; 1] =
+  [CIPHERS_NFT_EXT_STANDARD_IDENTIFIER];
+
 // Types
 
 /// Contract token ID type.
@@ -56,7 +65,11 @@ pub struct SetImplementorsParams {
   pub implementors: Vec<ContractAddress>,
 }
 
-type TransferParameter = TransferParams<ContractTokenId, ContractTokenAmount>;
+pub type TransferParameter = TransferParams<ContractTokenId, ContractTokenAmount>;
+
+/// Maximum number of transfers in one batch. Every transfer logs an event, so
+/// like minting a batch is bounded by the limit on the number of logs.
+pub const MAX_TRANSFER_BATCH: usize = 32;
 
 /// Execute a list of token transfers, in the order of the list.
 ///
@@ -65,11 +78,13 @@ type TransferParameter = TransferParams<ContractTokenId, ContractTokenAmount>;
 ///
 /// It rejects if:
 /// - It fails to parse the parameter.
+/// - The batch has more than `MAX_TRANSFER_BATCH` transfers.
 /// - Any of the transfers fail to be executed, which could be if:
 ///     - The `token_id` does not exist.
 ///     - The sender is not the owner of the token, or an operator for this
 ///       specific `token_id` and `from` address.
 ///     - The token is not owned by the `from`.
+///     - The receiver is a contract outside the enforced receiver whitelist.
 /// - Fails to log event.
 /// - Any of the receive hook function calls rejects.
 #[receive(
@@ -87,58 +102,170 @@ fn contract_transfer(
 ) -> ContractResult<()> {
   // Parse the parameter.
   let TransferParams(transfers): TransferParameter = ctx.parameter_cursor().get()?;
+  ensure!(
+    transfers.len() <= MAX_TRANSFER_BATCH,
+    CustomContractError::BatchTooLarge.into()
+  );
   // Get the sender who invoked this contract function.
   let sender = ctx.sender();
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+
+  for transfer in transfers {
+    execute_transfer(
+      host,
+      logger,
+      sender,
+      block_time,
+      transfer,
+      TransferMode::Atomic,
+    )?;
+  }
+  Ok(())
+}
+
+/// How a batch of transfers handles a failing transfer.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+pub enum TransferMode {
+  /// Reject the whole batch, like `transfer`.
+  Atomic,
+  /// Skip the transfer and report its error.
+  BestEffort,
+}
+
+/// The parameter for the contract function `transferWithMode`.
+#[derive(Serialize, SchemaType)]
+pub struct TransferWithModeParams {
+  pub mode: TransferMode,
+  pub transfers: Vec<Transfer<ContractTokenId, ContractTokenAmount>>,
+}
+
+/// The outcome of every transfer of the batch, in order: `None` if it was
+/// executed, otherwise the error it was skipped with.
+pub type TransferReport = Vec<Option<ContractError>>;
 
-  for Transfer {
+/// Execute a list of token transfers like `transfer`, with the given
+/// `TransferMode`.
+/// In `BestEffort` mode a transfer that is unauthorized or invalid for the
+/// state is skipped and reported, and the other transfers are executed. A
+/// rejecting receive hook still rejects the whole batch, since the token is
+/// already credited to the receiving contract when the hook is invoked.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The batch has more than `MAX_TRANSFER_BATCH` transfers.
+/// - In `Atomic` mode, any of the transfers fails, see `transfer`.
+/// - Fails to log event.
+/// - Any of the receive hook function calls rejects.
+#[receive(
+  contract = "ciphers_nft",
+  name = "transferWithMode",
+  parameter = "TransferWithModeParams",
+  return_value = "TransferReport",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_transfer_with_mode(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<TransferReport> {
+  let params: TransferWithModeParams = ctx.parameter_cursor().get()?;
+  ensure!(
+    params.transfers.len() <= MAX_TRANSFER_BATCH,
+    CustomContractError::BatchTooLarge.into()
+  );
+  let sender = ctx.sender();
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+
+  let mut report = Vec::with_capacity(params.transfers.len());
+  for transfer in params.transfers {
+    report.push(execute_transfer(
+      host,
+      logger,
+      sender,
+      block_time,
+      transfer,
+      params.mode,
+    )?);
+  }
+  Ok(report)
+}
+
+/// Execute a single transfer sent by `sender`, log the `Transfer` event and
+/// invoke the receive hook if the receiver is a contract.
+/// In `BestEffort` mode, a transfer failing the authorization or the state
+/// checks leaves the state unchanged and its error is returned instead.
+pub(crate) fn execute_transfer(
+  host: &mut Host<State>,
+  logger: &mut Logger,
+  sender: Address,
+  block_time: u64,
+  transfer: Transfer<ContractTokenId, ContractTokenAmount>,
+  mode: TransferMode,
+) -> ContractResult<Option<ContractError>> {
+  let Transfer {
     token_id,
     amount,
     from,
     to,
     data,
-  } in transfers
-  {
-    let (state, builder) = host.state_and_builder();
-    // Authenticate the sender for this transfer
-    ensure!(
-      from == sender || state.is_operator(&sender, &from),
-      ContractError::Unauthorized
-    );
-    let to_address = to.address();
-    // Update the contract state
-    state.transfer(&token_id, amount, &from, &to_address, builder)?;
+  } = transfer;
+  let (state, builder) = host.state_and_builder();
+  let to_address = to.address();
+  // Authenticate the sender for this transfer and update the contract state.
+  // `State::transfer` does not modify the state when it fails.
+  let result = if let Err(error) = state.check_receiver(&to_address) {
+    Err(error)
+  } else if from == sender {
+    state.transfer(&token_id, amount, &from, &to_address, block_time, builder)
+  } else if state.is_operator(&sender, &from, block_time) {
+    if from == to_address && state.reject_operator_self_transfers {
+      Err(CustomContractError::OperatorSelfTransfer.into())
+    } else {
+      state.transfer(&token_id, amount, &from, &to_address, block_time, builder)
+    }
+  } else {
+    Err(ContractError::Unauthorized)
+  };
+  if let Err(error) = result {
+    return match mode {
+      TransferMode::Atomic => Err(error),
+      TransferMode::BestEffort => Ok(Some(error)),
+    };
+  }
 
-    // Log transfer event
-    logger.log(&Cis2Event::Transfer(TransferEvent {
+  // Log transfer event
+  logger.log(&Cis2Event::Transfer(TransferEvent {
+    token_id,
+    amount,
+    from,
+    to: to_address,
+  }))?;
+
+  // If the receiver is a contract: invoke the receive hook function.
+  if let Receiver::Contract(address, function) = to {
+    let parameter = OnReceivingCis2Params {
       token_id,
       amount,
       from,
-      to: to_address,
-    }))?;
-
-    // If the receiver is a contract: invoke the receive hook function.
-    if let Receiver::Contract(address, function) = to {
-      let parameter = OnReceivingCis2Params {
-        token_id,
-        amount,
-        from,
-        data,
-      };
-      host.invoke_contract(
-        &address,
-        &parameter,
-        function.as_entrypoint_name(),
-        Amount::zero(),
-      )?;
-    }
+      data,
+    };
+    host.invoke_contract(
+      &address,
+      &parameter,
+      function.as_entrypoint_name(),
+      Amount::zero(),
+    )?;
   }
-  Ok(())
+  Ok(None)
 }
 
 /// Enable or disable addresses as operators of the sender address.
 /// Logs an `UpdateOperator` event.
 ///
 /// It rejects if:
+/// - Operators are disabled.
 /// - It fails to parse the parameter.
 /// - Fails to log event.
 #[receive(
@@ -159,10 +286,14 @@ fn contract_update_operator(
   // Get the sender who invoked this contract function.
   let sender = ctx.sender();
   let (state, builder) = host.state_and_builder();
+  ensure!(
+    state.operators_enabled,
+    CustomContractError::OperatorsDisabled.into()
+  );
   for param in params {
     // Update the operator in the state.
     match param.update {
-      OperatorUpdate::Add => state.add_operator(&sender, &param.operator, builder),
+      OperatorUpdate::Add => state.add_operator(&sender, &param.operator, NO_EXPIRY, builder)?,
       OperatorUpdate::Remove => state.remove_operator(&sender, &param.operator),
     }
 
@@ -179,6 +310,97 @@ fn contract_update_operator(
   Ok(())
 }
 
+/// A single update of an operator, like `UpdateOperator`, with an optional
+/// expiry for added operators.
+#[derive(Debug, Serialize, SchemaType)]
+pub struct UpdateOperatorWithExpiry {
+  pub update: OperatorUpdate,
+  pub operator: Address,
+  /// The operator is no longer an operator after this time. `None` for an
+  /// operator that never expires. Ignored when removing an operator.
+  pub expiry: Option<Timestamp>,
+}
+
+/// The parameter type for the contract function `updateOperatorWithExpiry`.
+#[derive(Debug, Serialize, SchemaType)]
+#[concordium(transparent)]
+pub struct UpdateOperatorWithExpiryParams(
+  #[concordium(size_length = 2)] pub Vec<UpdateOperatorWithExpiry>,
+);
+
+/// Like `updateOperator`, but added operators can expire. Expired operators
+/// are not operators anymore, and can be removed by anyone with
+/// `sweepExpiredOperators`.
+/// Logs an `UpdateOperator` event.
+///
+/// It rejects if:
+/// - Operators are disabled.
+/// - It fails to parse the parameter.
+/// - Fails to log event.
+#[receive(
+  contract = "ciphers_nft",
+  name = "updateOperatorWithExpiry",
+  parameter = "UpdateOperatorWithExpiryParams",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_update_operator_with_expiry(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  let UpdateOperatorWithExpiryParams(params) = ctx.parameter_cursor().get()?;
+  let sender = ctx.sender();
+  let (state, builder) = host.state_and_builder();
+  ensure!(
+    state.operators_enabled,
+    CustomContractError::OperatorsDisabled.into()
+  );
+  for param in params {
+    match param.update {
+      OperatorUpdate::Add => {
+        let expiry = param.expiry.unwrap_or(NO_EXPIRY);
+        state.add_operator(&sender, &param.operator, expiry, builder)?
+      }
+      OperatorUpdate::Remove => state.remove_operator(&sender, &param.operator),
+    }
+
+    logger.log(
+      &Cis2Event::<ContractTokenId, ContractTokenAmount>::UpdateOperator(UpdateOperatorEvent {
+        owner: sender,
+        operator: param.operator,
+        update: param.update,
+      }),
+    )?;
+  }
+
+  Ok(())
+}
+
+/// Remove the expired operators of an owner, reclaiming their state. Can be
+/// called by anyone, since expired operators have no rights left. Returns the
+/// number of operators removed.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+  contract = "ciphers_nft",
+  name = "sweepExpiredOperators",
+  parameter = "Address",
+  return_value = "u32",
+  error = "ContractError",
+  mutable
+)]
+fn contract_sweep_expired_operators(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+) -> ContractResult<u32> {
+  let owner: Address = ctx.parameter_cursor().get()?;
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+  Ok(host.state_mut().sweep_expired_operators(&owner, block_time))
+}
+
 /// Takes a list of queries. Each query is an owner address and some address to
 /// check as an operator of the owner address.
 ///
@@ -197,11 +419,14 @@ fn contract_operator_of(
 ) -> ContractResult<OperatorOfQueryResponse> {
   // Parse the parameter.
   let params: OperatorOfQueryParams = ctx.parameter_cursor().get()?;
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
   // Build the response.
   let mut response = Vec::with_capacity(params.queries.len());
   for query in params.queries {
     // Query the state for address being an operator of owner.
-    let is_operator = host.state().is_operator(&query.address, &query.owner);
+    let is_operator = host
+      .state()
+      .is_operator(&query.address, &query.owner, block_time);
     response.push(is_operator);
   }
   let result = OperatorOfQueryResponse::from(response);
@@ -215,7 +440,8 @@ type ContractBalanceOfQueryParams = BalanceOfQueryParams<ContractTokenId>;
 /// of TokenAmounts used by this contract.
 type ContractBalanceOfQueryResponse = BalanceOfQueryResponse<ContractTokenAmount>;
 
-/// Get the balance of given token IDs and addresses.
+/// Get the balance of given token IDs and addresses: 1 or 0 for unique
+/// tokens, and the number of copies owned for editions.
 ///
 /// It rejects if:
 /// - It fails to parse the parameter.
@@ -249,10 +475,13 @@ fn contract_balance_of(
 pub type ContractTokenMetadataQueryParams = TokenMetadataQueryParams<ContractTokenId>;
 
 /// Get the token metadata URLs and checksums given a list of token IDs.
+/// Reserved token IDs, set with `setReservedUri`, return their reserved URI
+/// whether or not they are minted. Tokens not yet revealed with `revealBatch`
+/// return the placeholder URI without a hash.
 ///
 /// It rejects if:
 /// - It fails to parse the parameter.
-/// - Any of the queried `token_id` does not exist.
+/// - Any of the queried `token_id` is not reserved and does not exist.
 #[receive(
   contract = "ciphers_nft",
   name = "tokenMetadata",
@@ -269,6 +498,13 @@ fn contract_token_metadata(
   // Build the response.
   let mut response = Vec::with_capacity(params.queries.len());
   for token_id in params.queries {
+    if let Some(reserved_uri) = host.state().reserved_uris.get(&token_id) {
+      response.push(MetadataUrl {
+        url: reserved_uri.to_string(),
+        hash: None,
+      });
+      continue;
+    }
     // Check the token exists.
     ensure!(
       host.state().contains_token(&token_id),
@@ -280,9 +516,20 @@ fn contract_token_metadata(
       .get(&token_id)
       .ok_or(ContractError::InvalidTokenId)?;
 
-    let metadata_url = MetadataUrl {
-      url: token_uri.to_string(),
-      hash: None,
+    let mint_count = host
+      .state()
+      .mint_count
+      .get(&token_id)
+      .map_or(0, |mint_count| *mint_count);
+    let metadata_url = match host.state().unrevealed_uri(mint_count) {
+      Some(placeholder_uri) => MetadataUrl {
+        url: placeholder_uri.clone(),
+        hash: None,
+      },
+      None => MetadataUrl {
+        url: token_uri.to_string(),
+        hash: host.state().token_hashes.get(&token_id).map(|hash| *hash),
+      },
     };
     response.push(metadata_url);
   }
@@ -312,7 +559,8 @@ fn contract_supports(
   // Build the response.
   let mut response = Vec::with_capacity(params.queries.len());
   for std_id in params.queries {
-    if SUPPORTS_STANDARDS.contains(&std_id.as_standard_identifier()) {
+    let std_id_ref = std_id.as_standard_identifier();
+    if SUPPORTS_STANDARDS.contains(&std_id_ref) || EXTENDED_STANDARDS.contains(&std_id_ref) {
       response.push(SupportResult::Support);
     } else {
       response.push(host.state().have_implementors(&std_id));
@@ -338,8 +586,8 @@ fn contract_supports(
 fn contract_set_implementor(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
   // Authorize the sender.
   ensure!(
-    ctx.sender().matches_account(&ctx.owner()),
-    ContractError::Unauthorized
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
   );
   // Parse the parameter.
   let params: SetImplementorsParams = ctx.parameter_cursor().get()?;
@@ -0,0 +1,159 @@
+use concordium_std::*;
+
+use crate::{
+  cis2::ContractTokenId,
+  error::{ContractError, ContractResult, CustomContractError},
+  mint::{mint_tokens, MintParams},
+  state::State,
+};
+
+/// The parameter for the contract function `assignClaims` which assigns a
+/// number of tokens to an account.
+#[derive(Serial, Deserial, SchemaType)]
+pub struct AssignClaimsParams {
+  /// Account that can claim the tokens.
+  pub account: AccountAddress,
+  /// A collection of tokens to assign.
+  #[concordium(size_length = 1)] // max size of 255
+  pub tokens: Vec<ContractTokenId>,
+  /// The metadata URL for each token.
+  #[concordium(size_length = 1)] // max size of 255
+  pub token_uris: Vec<String>,
+}
+
+/// Assign tokens to an account, which can later mint them to itself with
+/// `claim`. Adds to the tokens already assigned to the account.
+/// Can only be called by the contract owner.
+///
+/// It rejects if:
+/// - The sender is not the contract instance owner.
+/// - Fails to parse parameter.
+/// - The tokens and URIs arrays are not of the same length.
+/// - Any of the tokens is already minted.
+#[receive(
+  contract = "ciphers_nft",
+  name = "assignClaims",
+  parameter = "AssignClaimsParams",
+  error = "ContractError",
+  mutable
+)]
+fn contract_assign_claims(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: AssignClaimsParams = ctx.parameter_cursor().get()?;
+  ensure!(
+    params.tokens.len() == params.token_uris.len(),
+    CustomContractError::ArraysNotSameLength.into()
+  );
+  let state = host.state_mut();
+  for token_id in params.tokens.iter() {
+    ensure!(
+      !state.contains_token(token_id),
+      CustomContractError::TokenIdAlreadyExists.into()
+    );
+  }
+
+  let mut claims = state.claims.entry(params.account).or_insert_with(Vec::new);
+  claims.extend(params.tokens.into_iter().zip(params.token_uris));
+  Ok(())
+}
+
+/// Mint the tokens assigned to the sender to itself and remove the
+/// assignment. The mint is subject to the same checks as `mint` and logs the
+/// same events.
+///
+/// It rejects if:
+/// - The claim deadline has passed.
+/// - The sender has no tokens assigned.
+/// - Any of the tokens fails to be minted, see `mint`.
+#[receive(
+  contract = "ciphers_nft",
+  name = "claim",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_claim(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  let sender = ctx.sender();
+  let Address::Account(account) = sender else {
+    bail!(CustomContractError::NoClaim.into());
+  };
+  let (state, builder) = host.state_and_builder();
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+  ensure!(
+    !state.claims_expired(block_time),
+    CustomContractError::ClaimExpired.into()
+  );
+  let claims = state
+    .claims
+    .remove_and_get(&account)
+    .ok_or(CustomContractError::NoClaim)?;
+
+  let (tokens, token_uris): (Vec<_>, Vec<_>) = claims.into_iter().unzip();
+  let params = MintParams {
+    owners: vec![sender; tokens.len()],
+    tokens,
+    token_uris,
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_tokens(ctx, state, builder, logger, params)
+}
+
+/// Clear the claims that were not claimed before the claim deadline. With a
+/// recipient, such as a treasury, the unclaimed tokens are minted to it
+/// instead, subject to the same checks as `mint` and logging the same events.
+/// Can only be called by the contract owner.
+///
+/// It rejects if:
+/// - The sender is not the contract instance owner.
+/// - Fails to parse parameter.
+/// - The claim deadline has not passed or there is no claim deadline.
+/// - Any of the tokens fails to be minted, see `mint`.
+#[receive(
+  contract = "ciphers_nft",
+  name = "reclaimUnclaimed",
+  parameter = "Option<Address>",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_reclaim_unclaimed(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let recipient: Option<Address> = ctx.parameter_cursor().get()?;
+  let (state, builder) = host.state_and_builder();
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+  ensure!(
+    state.claims_expired(block_time),
+    CustomContractError::ClaimNotExpired.into()
+  );
+
+  let claims = state.take_claims();
+  let Some(recipient) = recipient else {
+    return Ok(());
+  };
+  let (tokens, token_uris): (Vec<_>, Vec<_>) = claims.into_iter().unzip();
+  let params = MintParams {
+    owners: vec![recipient; tokens.len()],
+    tokens,
+    token_uris,
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_tokens(ctx, state, builder, logger, params)
+}
@@ -1,6 +1,10 @@
 use concordium_std::*;
 
-use crate::{cis2::ContractTokenId, state::State};
+use crate::{
+  cis2::ContractTokenId,
+  getters::{ViewItems, MAX_VIEW_ITEMS},
+  state::State,
+};
 
 #[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
 pub struct ViewAddressState {
@@ -21,41 +25,149 @@ pub struct ViewState {
   pub mint_start: u64,
   pub mint_deadline: u64,
   pub max_total_supply: u32,
+  pub mint_price: Amount,
+  pub contract_balance: Amount,
+  /// Whether addresses, tokens, URIs or operators were left out, see
+  /// `MAX_VIEW_ITEMS`.
+  pub truncated: bool,
 }
 
-/// View function that returns the entire contents of the state. Meant for
-/// TESTING ONLY.
+/// View function that returns the entire contents of the state, with every
+/// list truncated to `MAX_VIEW_ITEMS`. Meant for TESTING ONLY.
 #[receive(contract = "ciphers_nft", name = "view", return_value = "ViewState")]
 fn contract_view(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<ViewState> {
   let state = host.state();
 
+  let mut truncated = false;
   let mut inner_state = Vec::new();
   for (k, a_state) in state.address_state.iter() {
-    let owned_tokens = a_state.owned_tokens.iter().map(|x| *x).collect();
-    let operators = a_state.operators.iter().map(|x| *x).collect();
+    if inner_state.len() == MAX_VIEW_ITEMS {
+      truncated = true;
+      break;
+    }
+    let owned_tokens: ViewItems<_> = a_state.owned_tokens.iter().map(|x| *x).collect();
+    let operators: ViewItems<_> = a_state.operators.iter().map(|(x, _)| *x).collect();
+    truncated |= owned_tokens.truncated || operators.truncated;
     inner_state.push((
       *k,
       ViewAddressState {
-        owned_tokens,
-        operators,
+        owned_tokens: owned_tokens.items,
+        operators: operators.items,
       },
     ));
   }
-  let all_tokens = state.all_tokens.iter().map(|x| *x).collect();
-  let token_uris = state.token_uris.iter().map(|(_, v)| v.clone()).collect();
-  let mint_count = state.mint_count.iter().map(|(k, v)| (*k, *v)).collect();
+  let all_tokens: ViewItems<_> = state.all_tokens.iter().map(|x| *x).collect();
+  let token_uris: ViewItems<_> = state.token_uris.iter().map(|(_, v)| v.clone()).collect();
+  let mint_count: ViewItems<_> = state.mint_count.iter().map(|(k, v)| (*k, *v)).collect();
+  truncated |= all_tokens.truncated || token_uris.truncated || mint_count.truncated;
 
   Ok(ViewState {
     state: inner_state,
-    all_tokens,
-    token_uris,
+    all_tokens: all_tokens.items,
+    token_uris: token_uris.items,
     name: state.name.clone(),
     symbol: state.symbol.clone(),
     contract_uri: state.contract_uri.clone(),
     counter: state.counter,
-    mint_count,
+    mint_count: mint_count.items,
     mint_start: state.mint_start,
     mint_deadline: state.mint_deadline,
     max_total_supply: state.max_total_supply,
+    mint_price: state.mint_price,
+    contract_balance: host.self_balance(),
+    truncated,
+  })
+}
+
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct ViewLight {
+  /// Number of addresses with a state in the contract.
+  pub addresses: u32,
+  pub counter: u32,
+  pub burned_counter: u32,
+  pub mint_start: u64,
+  pub mint_deadline: u64,
+  pub max_total_supply: u32,
+  pub mint_price: Amount,
+  pub minting_paused: bool,
+  pub contract_balance: Amount,
+}
+
+/// View the counts and settings of the contract, without the tokens, URIs
+/// and strings returned by `view`, so it stays cheap for large collections.
+#[receive(
+  contract = "ciphers_nft",
+  name = "viewLight",
+  return_value = "ViewLight"
+)]
+fn contract_view_light(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<ViewLight> {
+  let state = host.state();
+
+  Ok(ViewLight {
+    addresses: state.address_state.iter().count() as u32,
+    counter: state.counter,
+    burned_counter: state.burned_counter,
+    mint_start: state.mint_start,
+    mint_deadline: state.mint_deadline,
+    max_total_supply: state.max_total_supply,
+    mint_price: state.mint_price,
+    minting_paused: state.minting_paused,
+    contract_balance: host.self_balance(),
+  })
+}
+
+/// The parameter for the contract function `viewPaginated`.
+#[derive(Serialize, SchemaType, Debug)]
+pub struct PaginationParams {
+  /// Number of tokens to skip.
+  pub skip: u32,
+  /// Number of tokens to return, at most `MAX_VIEW_ITEMS`.
+  pub take: u32,
+}
+
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct ViewPage {
+  pub all_tokens: Vec<ContractTokenId>,
+  /// The URIs of `all_tokens`, in the same order.
+  pub token_uris: Vec<String>,
+  /// Number of tokens in the contract.
+  pub total: u32,
+}
+
+/// View a page of the tokens and their URIs, in the order of `view`. Unlike
+/// `view`, the addresses are left out, so the size of a page is bounded.
+#[receive(
+  contract = "ciphers_nft",
+  name = "viewPaginated",
+  parameter = "PaginationParams",
+  return_value = "ViewPage"
+)]
+fn contract_view_paginated(ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<ViewPage> {
+  let params: PaginationParams = ctx.parameter_cursor().get()?;
+  let state = host.state();
+
+  let take = (params.take as usize).min(MAX_VIEW_ITEMS);
+  let all_tokens: Vec<ContractTokenId> = state
+    .all_tokens
+    .iter()
+    .skip(params.skip as usize)
+    .take(take)
+    .map(|x| *x)
+    .collect();
+  let token_uris = all_tokens
+    .iter()
+    .map(|token_id| {
+      state
+        .token_uris
+        .get(token_id)
+        .map(|uri| uri.clone())
+        .unwrap_or_default()
+    })
+    .collect();
+
+  Ok(ViewPage {
+    all_tokens,
+    token_uris,
+    total: state.counter - state.burned_counter,
   })
 }
@@ -27,6 +27,88 @@ pub enum CustomContractError {
   Cis2ClientError,
   /// Not a valid address
   InvalidAddress,
+  /// Minting is paused by the contract owner
+  MintingPaused,
+  /// Royalty basis points exceed 10000
+  InvalidRoyalty,
+  /// The token URI is already used by another token
+  DuplicateUri,
+  /// The account has no tokens assigned to claim
+  NoClaim,
+  /// The owner already has the maximum number of operators
+  OperatorCapExceeded,
+  /// The minter is locked and can no longer be changed
+  MinterLocked,
+  /// Treasury basis points exceed 10000
+  InvalidFeeSplit,
+  /// The change must be queued with `queueAdminAction`
+  TimelockRequired,
+  /// The admin action is queued with a shorter delay than the timelock
+  TimelockTooShort,
+  /// The admin action cannot be executed yet
+  TimelockNotExpired,
+  /// No admin action is queued with the given ID
+  UnknownAdminAction,
+  /// Transfers are blocked until the mint is complete
+  TradingNotOpen,
+  /// The nonce does not prove the work required to mint
+  InvalidMintProof,
+  /// The account minted too recently through `publicMint`
+  MintCooldown,
+  /// The account reached the maximum number of tokens minted through
+  /// `publicMint`
+  AccountMintCapReached,
+  /// The sender is not the contract owner. Token-level authorization failures
+  /// use `Unauthorized` instead.
+  NotOwner,
+  /// The claim deadline has passed
+  ClaimExpired,
+  /// The claim deadline has not passed yet
+  ClaimNotExpired,
+  /// Operators are disabled for this contract
+  OperatorsDisabled,
+  /// The token is frozen
+  TokenFrozen,
+  /// The token is locked until a later time
+  TokenLocked,
+  /// The token was minted too recently to be transferred
+  ResaleLocked,
+  /// An operator transferred a token to its owner
+  OperatorSelfTransfer,
+  /// Minting and transfers are paused
+  ContractPaused,
+  /// The attached amount does not pay for the minted tokens
+  InsufficientPayment,
+  /// Failed to transfer CCD from the contract
+  InvokeTransferError,
+  /// The batch has more than `MAX_TRANSFER_BATCH` transfers
+  BatchTooLarge,
+  /// The receiving contract is not in the receiver whitelist
+  ReceiverNotAllowed,
+  /// The permit message is meant for another contract
+  WrongContract,
+  /// The permit message is expired
+  PermitExpired,
+  /// The permit signature is not valid for the signer
+  WrongSignature,
+  /// The permit nonce is not the next nonce of the signer
+  NonceMismatch,
+  /// The permit message expires after the replay window
+  PermitOutsideWindow,
+  /// The permit message was already executed within the replay window
+  PermitReplayed,
+  /// The permit entrypoint is not supported
+  WrongEntryPoint,
+  /// Minting is locked permanently by the contract owner
+  MintingLocked,
+  /// The max supply of a token or of the contract is out of range
+  InvalidMaxSupply,
+  /// `onReceivingCis2` cannot be chosen as the `transferAndCall` entrypoint
+  ReservedEntrypoint,
+  /// A balance does not fit a `ContractTokenAmount`
+  AmountOverflow,
+  /// The max supply of a minted token can only change through the timelock
+  MaxSupplyLocked,
 }
 
 /// Wrapping the custom errors in a type with CIS2 errors.
@@ -44,6 +126,13 @@ impl From<LogError> for CustomContractError {
   }
 }
 
+/// Mapping errors related to CCD transfers to CustomContractError.
+impl From<TransferError> for CustomContractError {
+  fn from(_te: TransferError) -> Self {
+    Self::InvokeTransferError
+  }
+}
+
 /// Mapping errors related to contract invocations to CustomContractError.
 impl<T> From<CallContractError<T>> for CustomContractError {
   fn from(_cce: CallContractError<T>) -> Self {
@@ -63,3 +152,24 @@ impl<T> From<Cis2ClientError<T>> for CustomContractError {
     CustomContractError::Cis2ClientError
   }
 }
+
+impl CustomContractError {
+  /// Map the reject code of a rejected update back to the custom error. The
+  /// derived `Reject` gives the variant at index `i` the code `-(i + 1)`,
+  /// which matches the tag the variant is serialized with.
+  pub fn from_reject_code(code: i32) -> Option<Self> {
+    let tag = u8::try_from(code.checked_neg()? - 1).ok()?;
+    from_bytes(&[tag]).ok()
+  }
+}
+
+/// Map the reject code of a rejected update back to the contract error.
+/// Returns `None` for codes that are not produced by this contract.
+pub fn error_from_reject_code(code: i32) -> Option<ContractError> {
+  match code {
+    -42000001 => Some(Cis2Error::InvalidTokenId),
+    -42000002 => Some(Cis2Error::InsufficientFunds),
+    -42000003 => Some(Cis2Error::Unauthorized),
+    _ => CustomContractError::from_reject_code(code).map(Cis2Error::Custom),
+  }
+}
@@ -1,4 +1,6 @@
-use concordium_cis2::{MetadataUrl, MINT_EVENT_TAG, TOKEN_METADATA_EVENT_TAG, TRANSFER_EVENT_TAG};
+use concordium_cis2::{
+  MetadataUrl, BURN_EVENT_TAG, MINT_EVENT_TAG, TOKEN_METADATA_EVENT_TAG, TRANSFER_EVENT_TAG,
+};
 use concordium_std::{collections::BTreeMap, schema::SchemaType, *};
 
 use crate::cis2::{ContractTokenAmount, ContractTokenId, MintCountTokenID};
@@ -16,7 +18,13 @@ pub struct MintedEvent {
   pub token_uri: MetadataUrl,
 }
 
-#[derive(Debug, Deserial, PartialEq, Eq, Serial, SchemaType)]
+/// Version of the contract, logged in the `Deploy` event so the indexer can
+/// select the events and entrypoints to expect.
+pub const CONTRACT_VERSION: u16 = 1;
+/// Version of deploy events logged before the version was added.
+pub const LEGACY_CONTRACT_VERSION: u16 = 0;
+
+#[derive(Debug, PartialEq, Eq, Serial, SchemaType)]
 pub struct DeployEvent {
   pub name: String,
   pub symbol: String,
@@ -25,6 +33,51 @@ pub struct DeployEvent {
   pub mint_start: u64,
   pub mint_deadline: u64,
   pub max_total_supply: u32,
+  pub contract_version: u16,
+}
+
+impl Deserial for DeployEvent {
+  /// Deploy events logged before the version was added end after
+  /// `max_total_supply` and are read as `LEGACY_CONTRACT_VERSION`.
+  fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+    let name = String::deserial(source)?;
+    let symbol = String::deserial(source)?;
+    let contract_uri = MetadataUrl::deserial(source)?;
+    let minter = AccountAddress::deserial(source)?;
+    let mint_start = u64::deserial(source)?;
+    let mint_deadline = u64::deserial(source)?;
+    let max_total_supply = u32::deserial(source)?;
+    let mut version = [0u8; 2];
+    let contract_version = match source.read(&mut version)? {
+      0 => LEGACY_CONTRACT_VERSION,
+      1 => u16::from_le_bytes([version[0], source.read_u8()?]),
+      _ => u16::from_le_bytes(version),
+    };
+    Ok(DeployEvent {
+      name,
+      symbol,
+      contract_uri,
+      minter,
+      mint_start,
+      mint_deadline,
+      max_total_supply,
+      contract_version,
+    })
+  }
+}
+
+/// The CCD balance of the contract was withdrawn to `receiver`.
+#[derive(Debug, Deserial, PartialEq, Eq, Serial, SchemaType)]
+pub struct WithdrawEvent {
+  pub receiver: AccountAddress,
+  pub amount: Amount,
+}
+
+/// The ownership of the contract was transferred to `new_owner`.
+#[derive(Debug, Deserial, PartialEq, Eq, Serial, SchemaType)]
+pub struct OwnershipTransferredEvent {
+  pub previous_owner: AccountAddress,
+  pub new_owner: AccountAddress,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -32,12 +85,18 @@ pub enum ContractEvent {
   Mint(MintEvent),
   TokenMetadata(TokenMetadataEvent),
   Transfer(TransferEvent),
+  Burn(BurnEvent),
   Minted(MintedEvent),
   Deploy(DeployEvent),
+  Withdraw(WithdrawEvent),
+  OwnershipTransferred(OwnershipTransferredEvent),
 }
 
-const MINTED_EVENT_TAG: u8 = u8::MIN;
-const DEPLOY_EVENT_TAG: u8 = u8::MIN + 1;
+pub const MINTED_EVENT_TAG: u8 = u8::MIN;
+#[allow(clippy::identity_op)]
+pub const DEPLOY_EVENT_TAG: u8 = u8::MIN + 1;
+pub const WITHDRAW_EVENT_TAG: u8 = 2;
+pub const OWNERSHIP_TRANSFERRED_EVENT_TAG: u8 = 3;
 
 impl Serial for ContractEvent {
   fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
@@ -54,6 +113,10 @@ impl Serial for ContractEvent {
         out.write_u8(concordium_cis2::TOKEN_METADATA_EVENT_TAG)?;
         event.serial(out)
       }
+      ContractEvent::Burn(event) => {
+        out.write_u8(BURN_EVENT_TAG)?;
+        event.serial(out)
+      }
       ContractEvent::Minted(event) => {
         out.write_u8(MINTED_EVENT_TAG)?;
         event.serial(out)
@@ -62,6 +125,14 @@ impl Serial for ContractEvent {
         out.write_u8(DEPLOY_EVENT_TAG)?;
         event.serial(out)
       }
+      ContractEvent::Withdraw(event) => {
+        out.write_u8(WITHDRAW_EVENT_TAG)?;
+        event.serial(out)
+      }
+      ContractEvent::OwnershipTransferred(event) => {
+        out.write_u8(OWNERSHIP_TRANSFERRED_EVENT_TAG)?;
+        event.serial(out)
+      }
     }
   }
 }
@@ -85,6 +156,10 @@ impl Deserial for ContractEvent {
         let event = TokenMetadataEvent::deserial(source)?;
         Ok(ContractEvent::TokenMetadata(event))
       }
+      BURN_EVENT_TAG => {
+        let event = BurnEvent::deserial(source)?;
+        Ok(ContractEvent::Burn(event))
+      }
       MINTED_EVENT_TAG => {
         let event = MintedEvent::deserial(source)?;
         Ok(ContractEvent::Minted(event))
@@ -93,6 +168,14 @@ impl Deserial for ContractEvent {
         let event = DeployEvent::deserial(source)?;
         Ok(ContractEvent::Deploy(event))
       }
+      WITHDRAW_EVENT_TAG => {
+        let event = WithdrawEvent::deserial(source)?;
+        Ok(ContractEvent::Withdraw(event))
+      }
+      OWNERSHIP_TRANSFERRED_EVENT_TAG => {
+        let event = OwnershipTransferredEvent::deserial(source)?;
+        Ok(ContractEvent::OwnershipTransferred(event))
+      }
       _ => Err(ParseError::default()),
     }
   }
@@ -134,6 +217,17 @@ impl SchemaType for ContractEvent {
         ]),
       ),
     );
+    event_map.insert(
+      BURN_EVENT_TAG,
+      (
+        "Burn".to_string(),
+        schema::Fields::Named(vec![
+          (String::from("token_id"), ContractTokenId::get_type()),
+          (String::from("amount"), ContractTokenAmount::get_type()),
+          (String::from("owner"), Address::get_type()),
+        ]),
+      ),
+    );
     event_map.insert(
       MINTED_EVENT_TAG,
       (
@@ -154,10 +248,31 @@ impl SchemaType for ContractEvent {
           (String::from("name"), String::get_type()),
           (String::from("symbol"), String::get_type()),
           (String::from("contract_uri"), MetadataUrl::get_type()),
-          (String::from("minter"), Address::get_type()),
+          (String::from("minter"), AccountAddress::get_type()),
           (String::from("mint_start"), u64::get_type()),
           (String::from("mint_deadline"), u64::get_type()),
           (String::from("max_total_supply"), u32::get_type()),
+          (String::from("contract_version"), u16::get_type()),
+        ]),
+      ),
+    );
+    event_map.insert(
+      WITHDRAW_EVENT_TAG,
+      (
+        "Withdraw".to_string(),
+        schema::Fields::Named(vec![
+          (String::from("receiver"), AccountAddress::get_type()),
+          (String::from("amount"), Amount::get_type()),
+        ]),
+      ),
+    );
+    event_map.insert(
+      OWNERSHIP_TRANSFERRED_EVENT_TAG,
+      (
+        "OwnershipTransferred".to_string(),
+        schema::Fields::Named(vec![
+          (String::from("previous_owner"), AccountAddress::get_type()),
+          (String::from("new_owner"), AccountAddress::get_type()),
         ]),
       ),
     );
@@ -7,6 +7,30 @@ use crate::{
   state::State,
 };
 
+/// Maximum number of items in a list returned by a view, to keep the return
+/// value small. Views set `truncated` when items were left out.
+pub const MAX_VIEW_ITEMS: usize = 100;
+
+/// Items returned by a view, at most `MAX_VIEW_ITEMS`.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct ViewItems<T> {
+  pub items: Vec<T>,
+  /// Whether items were left out because there are more than
+  /// `MAX_VIEW_ITEMS`.
+  pub truncated: bool,
+}
+
+impl<T> FromIterator<T> for ViewItems<T> {
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut iter = iter.into_iter();
+    let items = iter.by_ref().take(MAX_VIEW_ITEMS).collect();
+    ViewItems {
+      items,
+      truncated: iter.next().is_some(),
+    }
+  }
+}
+
 #[derive(Debug, Serialize, SchemaType)]
 #[concordium(transparent)]
 pub struct MintCountQueryParams<T: IsTokenId> {
@@ -60,15 +84,112 @@ fn contract_get_mint_count_token_id(
   Ok(result)
 }
 
+pub type ContractMintTimestampQueryParams = MintCountQueryParams<ContractTokenId>;
+
+#[derive(Debug, Serialize, SchemaType)]
+#[concordium(transparent)]
+pub struct TokenMintTimestampQueryResponse(#[concordium(size_length = 2)] pub Vec<u64>);
+
+impl From<Vec<u64>> for TokenMintTimestampQueryResponse {
+  fn from(results: Vec<u64>) -> Self {
+    TokenMintTimestampQueryResponse(results)
+  }
+}
+
+/// Get the Unix timestamp in milliseconds at which each of the given tokens
+/// was minted.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - Any of the queried `token_id` does not exist.
+#[receive(
+  contract = "ciphers_nft",
+  name = "mintTimestamp",
+  parameter = "ContractMintTimestampQueryParams",
+  return_value = "TokenMintTimestampQueryResponse",
+  error = "ContractError"
+)]
+fn contract_mint_timestamp(
+  ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ContractResult<TokenMintTimestampQueryResponse> {
+  // Parse the parameter.
+  let params: ContractMintTimestampQueryParams = ctx.parameter_cursor().get()?;
+  // Build the response.
+  let mut response = Vec::with_capacity(params.queries.len());
+  for token_id in params.queries {
+    let timestamp = host
+      .state()
+      .mint_timestamp
+      .get(&token_id)
+      .ok_or(ContractError::InvalidTokenId)?;
+
+    response.push(*timestamp);
+  }
+  Ok(TokenMintTimestampQueryResponse::from(response))
+}
+
+pub type ContractFirstOwnerQueryParams = MintCountQueryParams<ContractTokenId>;
+
+#[derive(Debug, Serialize, SchemaType)]
+#[concordium(transparent)]
+pub struct FirstOwnerQueryResponse(#[concordium(size_length = 2)] pub Vec<Address>);
+
+/// Get the address each of the given tokens was minted to, regardless of the
+/// transfers since.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - Any of the queried `token_id` does not exist.
+#[receive(
+  contract = "ciphers_nft",
+  name = "firstOwnerOf",
+  parameter = "ContractFirstOwnerQueryParams",
+  return_value = "FirstOwnerQueryResponse",
+  error = "ContractError"
+)]
+fn contract_first_owner_of(
+  ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ContractResult<FirstOwnerQueryResponse> {
+  let params: ContractFirstOwnerQueryParams = ctx.parameter_cursor().get()?;
+  let mut response = Vec::with_capacity(params.queries.len());
+  for token_id in params.queries {
+    let first_owner = host
+      .state()
+      .first_owner
+      .get(&token_id)
+      .ok_or(ContractError::InvalidTokenId)?;
+
+    response.push(*first_owner);
+  }
+  Ok(FirstOwnerQueryResponse(response))
+}
+
 #[derive(Serialize, SchemaType, Debug)]
 pub struct ViewSettings {
   pub name: String,
   pub symbol: String,
   pub contract_uri: MetadataUrl,
   pub minter: AccountAddress,
+  /// Every account allowed to mint, including `minter`.
+  pub minters: Vec<AccountAddress>,
+  pub minter_locked: bool,
   pub mint_start: u64,
   pub mint_deadline: u64,
   pub max_total_supply: u32,
+  pub mint_price: Amount,
+  pub deadline_grace_ms: u64,
+  pub minting_paused: bool,
+  pub minting_locked: bool,
+  pub paused: bool,
+  pub royalty_basis_points: u16,
+  pub royalty_recipient: AccountAddress,
+  pub mint_cooldown_ms: u64,
+  pub max_mints_per_account: u32,
+  pub resale_lock_ms: u64,
+  /// Highest mint count revealed, see `revealBatch`.
+  pub revealed_up_to: u32,
 }
 
 #[receive(
@@ -87,9 +208,101 @@ fn contract_view_settings(
     symbol: state.symbol.clone(),
     contract_uri: state.contract_uri.clone(),
     minter: state.minter,
+    minters: state.minters.iter().map(|minter| *minter).collect(),
+    minter_locked: state.minter_locked,
+    mint_start: state.mint_start,
+    mint_deadline: state.mint_deadline,
+    max_total_supply: state.max_total_supply,
+    mint_price: state.mint_price,
+    deadline_grace_ms: state.deadline_grace_ms,
+    minting_paused: state.minting_paused,
+    minting_locked: state.minting_locked,
+    paused: state.paused,
+    royalty_basis_points: state.royalty_basis_points,
+    royalty_recipient: state.royalty_recipient,
+    mint_cooldown_ms: state.mint_cooldown_ms,
+    max_mints_per_account: state.max_mints_per_account,
+    resale_lock_ms: state.resale_lock_ms,
+    revealed_up_to: state.revealed_up_to,
+  })
+}
+
+/// The administrative configuration of the contract.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct AdminConfig {
+  pub owner: AccountAddress,
+  pub minter: AccountAddress,
+  pub minter_locked: bool,
+  pub minting_paused: bool,
+  pub minting_locked: bool,
+  pub paused: bool,
+  pub mint_start: u64,
+  pub mint_deadline: u64,
+  pub deadline_grace_ms: u64,
+  pub max_total_supply: u32,
+  pub mint_price: Amount,
+  pub mint_cooldown_ms: u64,
+  pub max_mints_per_account: u32,
+  pub mint_proof_difficulty: u8,
+  pub refund_overpayment: bool,
+  pub treasury: Option<AccountAddress>,
+  pub treasury_bps: u16,
+  pub royalty_basis_points: u16,
+  pub royalty_recipient: AccountAddress,
+  pub require_unique_uri: bool,
+  pub emit_events: bool,
+  pub operators_enabled: bool,
+  pub max_operators_per_owner: u16,
+  pub trading_after_mint: bool,
+  pub resale_lock_ms: u64,
+  pub reject_operator_self_transfers: bool,
+  pub enforce_receiver_whitelist: bool,
+  pub burn_requires_admin: bool,
+  pub claim_deadline: u64,
+  pub admin_timelock_ms: u64,
+}
+
+/// View the whole administrative configuration in one call, for an admin
+/// dashboard.
+#[receive(
+  contract = "ciphers_nft",
+  name = "adminConfig",
+  return_value = "AdminConfig"
+)]
+fn contract_admin_config(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<AdminConfig> {
+  let state = host.state();
+
+  Ok(AdminConfig {
+    owner: state.owner,
+    minter: state.minter,
+    minter_locked: state.minter_locked,
+    minting_paused: state.minting_paused,
+    minting_locked: state.minting_locked,
+    paused: state.paused,
     mint_start: state.mint_start,
     mint_deadline: state.mint_deadline,
+    deadline_grace_ms: state.deadline_grace_ms,
     max_total_supply: state.max_total_supply,
+    mint_price: state.mint_price,
+    mint_cooldown_ms: state.mint_cooldown_ms,
+    max_mints_per_account: state.max_mints_per_account,
+    mint_proof_difficulty: state.mint_proof_difficulty,
+    refund_overpayment: state.refund_overpayment,
+    treasury: state.treasury,
+    treasury_bps: state.treasury_bps,
+    royalty_basis_points: state.royalty_basis_points,
+    royalty_recipient: state.royalty_recipient,
+    require_unique_uri: state.require_unique_uri,
+    emit_events: state.emit_events,
+    operators_enabled: state.operators_enabled,
+    max_operators_per_owner: state.max_operators_per_owner,
+    trading_after_mint: state.trading_after_mint,
+    resale_lock_ms: state.resale_lock_ms,
+    reject_operator_self_transfers: state.reject_operator_self_transfers,
+    enforce_receiver_whitelist: state.enforce_receiver_whitelist,
+    burn_requires_admin: state.burn_requires_admin,
+    claim_deadline: state.claim_deadline,
+    admin_timelock_ms: state.admin_timelock_ms,
   })
 }
 
@@ -97,6 +310,8 @@ fn contract_view_settings(
 pub struct ViewAddress {
   pub owned_tokens: Vec<ContractTokenId>,
   pub operators: Vec<Address>,
+  /// Whether tokens or operators were left out, see `MAX_VIEW_ITEMS`.
+  pub truncated: bool,
 }
 
 #[derive(Debug, Serialize, SchemaType)]
@@ -118,11 +333,367 @@ fn contract_view_address(ctx: &ReceiveContext, host: &Host<State>) -> ReceiveRes
     .get(&address)
     .ok_or(CustomContractError::InvalidAddress)?;
 
-  let owned_tokens = a_state.owned_tokens.iter().map(|x| *x).collect();
-  let operators = a_state.operators.iter().map(|x| *x).collect();
+  let owned_tokens: ViewItems<_> = a_state.owned_tokens.iter().map(|x| *x).collect();
+  let operators: ViewItems<_> = a_state.operators.iter().map(|(x, _)| *x).collect();
 
   Ok(ViewAddress {
-    owned_tokens,
+    owned_tokens: owned_tokens.items,
+    operators: operators.items,
+    truncated: owned_tokens.truncated || operators.truncated,
+  })
+}
+
+/// The tokens owned by the given address, without its operators, truncated
+/// to `MAX_VIEW_ITEMS`. Unknown addresses own no tokens.
+#[receive(
+  contract = "ciphers_nft",
+  name = "tokensOf",
+  parameter = "Address",
+  return_value = "ViewItems<ContractTokenId>",
+  error = "ContractError"
+)]
+fn contract_tokens_of(
+  ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ContractResult<ViewItems<ContractTokenId>> {
+  let address: Address = ctx.parameter_cursor().get()?;
+  let tokens = match host.state().address_state.get(&address) {
+    Some(a_state) => a_state.owned_tokens.iter().map(|x| *x).collect(),
+    None => ViewItems {
+      items: Vec::new(),
+      truncated: false,
+    },
+  };
+  Ok(tokens)
+}
+
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct RolesOf {
+  pub is_owner: bool,
+  pub is_minter: bool,
+  /// Whether the address is an operator of at least one address.
+  pub is_admin: bool,
+}
+
+#[receive(
+  contract = "ciphers_nft",
+  name = "rolesOf",
+  parameter = "Address",
+  return_value = "RolesOf",
+  error = "ContractError"
+)]
+fn contract_roles_of(ctx: &ReceiveContext, host: &Host<State>) -> ContractResult<RolesOf> {
+  let address: Address = ctx.parameter_cursor().get()?;
+  let state = host.state();
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+
+  let is_admin = state.address_state.iter().any(|(_, a_state)| {
+    a_state
+      .operators
+      .get(&address)
+      .is_some_and(|expiry| expiry.timestamp_millis() >= block_time)
+  });
+
+  Ok(RolesOf {
+    is_owner: address.matches_account(&state.owner),
+    is_minter: state.is_minter(&address),
+    is_admin,
+  })
+}
+
+/// The number of tokens currently in circulation, which is the number of
+/// tokens minted minus the number of tokens burned.
+#[receive(contract = "ciphers_nft", name = "totalSupply", return_value = "u32")]
+fn contract_total_supply(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<u32> {
+  let state = host.state();
+  Ok(state.counter - state.burned_counter)
+}
+
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct AccountStats {
+  /// Number of tokens ever minted to the address, counting every copy of an
+  /// edition.
+  pub minted: u32,
+  /// Number of tokens currently owned by the address, counting every copy of
+  /// an edition.
+  pub held: u32,
+}
+
+/// The number of tokens ever minted to the given address and the number of
+/// tokens it currently owns, both counting copies like `totalSupply`.
+#[receive(
+  contract = "ciphers_nft",
+  name = "accountStats",
+  parameter = "Address",
+  return_value = "AccountStats",
+  error = "ContractError"
+)]
+fn contract_account_stats(
+  ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ContractResult<AccountStats> {
+  let address: Address = ctx.parameter_cursor().get()?;
+  let state = host.state();
+
+  let minted = state
+    .minted_per_account
+    .get(&address)
+    .map_or(0, |minted| *minted);
+  let held = state
+    .address_state
+    .get(&address)
+    .map_or(Some(0), |a_state| {
+      a_state
+        .owned_tokens
+        .iter()
+        .try_fold(0u32, |held, token_id| {
+          held.checked_add(state.balance_of(&token_id, &address) as u32)
+        })
+    })
+    .ok_or(CustomContractError::AmountOverflow)?;
+  Ok(AccountStats { minted, held })
+}
+
+/// The number of tokens burned.
+#[receive(contract = "ciphers_nft", name = "totalBurned", return_value = "u32")]
+fn contract_total_burned(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<u32> {
+  Ok(host.state().burned_counter)
+}
+
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct OwnedTokenDetails {
+  pub token_id: ContractTokenId,
+  pub token_uri: String,
+  pub mint_count: MintCountTokenID,
+}
+
+/// The tokens owned by an address with their URI and mint count.
+pub type ViewAddressDetailed = ViewItems<OwnedTokenDetails>;
+
+/// View the tokens owned by the given address, or by the sender if no address
+/// is given, together with their URI and mint count.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The address has never owned a token or had an operator.
+#[receive(
+  contract = "ciphers_nft",
+  name = "viewAddressDetailed",
+  parameter = "Option<Address>",
+  return_value = "ViewAddressDetailed",
+  error = "ContractError"
+)]
+fn contract_view_address_detailed(
+  ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ContractResult<ViewAddressDetailed> {
+  let address: Option<Address> = ctx.parameter_cursor().get()?;
+  let address = address.unwrap_or_else(|| ctx.sender());
+  let state = host.state();
+  let a_state = state
+    .address_state
+    .get(&address)
+    .ok_or(CustomContractError::InvalidAddress)?;
+
+  let mut tokens = Vec::new();
+  for token_id in a_state.owned_tokens.iter().take(MAX_VIEW_ITEMS + 1) {
+    let token_uri = state
+      .token_uris
+      .get(&token_id)
+      .ok_or(ContractError::InvalidTokenId)?;
+    let mint_count = state
+      .mint_count
+      .get(&token_id)
+      .ok_or(ContractError::InvalidTokenId)?;
+    tokens.push(OwnedTokenDetails {
+      token_id: *token_id,
+      token_uri: token_uri.clone(),
+      mint_count: *mint_count,
+    });
+  }
+  Ok(tokens.into_iter().collect())
+}
+
+/// The tokens without a metadata URI, either missing or empty. Every token
+/// gets a URI when minted, so a non-empty result signals a bug or a token
+/// minted with an empty URI, such as a pending reveal.
+#[receive(
+  contract = "ciphers_nft",
+  name = "tokensMissingUri",
+  return_value = "ViewItems<ContractTokenId>"
+)]
+fn contract_tokens_missing_uri(
+  _ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ReceiveResult<ViewItems<ContractTokenId>> {
+  let state = host.state();
+  let missing = state
+    .all_tokens
+    .iter()
+    .filter(|token_id| match state.token_uris.get(token_id) {
+      Some(token_uri) => token_uri.is_empty(),
+      None => true,
+    })
+    .map(|token_id| *token_id)
+    .collect();
+  Ok(missing)
+}
+
+/// The tokens owned by each contract address. Both the contracts and the
+/// tokens of each contract are truncated to `MAX_VIEW_ITEMS`.
+pub type ContractHoldings = ViewItems<(ContractAddress, ViewItems<ContractTokenId>)>;
+
+/// View the tokens owned by contract addresses, such as tokens escrowed in a
+/// marketplace. Contracts not owning any tokens are skipped.
+#[receive(
+  contract = "ciphers_nft",
+  name = "contractHoldings",
+  return_value = "ContractHoldings"
+)]
+fn contract_contract_holdings(
+  _ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ReceiveResult<ContractHoldings> {
+  let mut holdings = Vec::new();
+  for (address, a_state) in host.state().address_state.iter() {
+    let Address::Contract(contract) = *address else {
+      continue;
+    };
+    let owned_tokens: ViewItems<_> = a_state.owned_tokens.iter().map(|x| *x).collect();
+    if !owned_tokens.items.is_empty() {
+      holdings.push((contract, owned_tokens));
+      if holdings.len() > MAX_VIEW_ITEMS {
+        break;
+      }
+    }
+  }
+  Ok(holdings.into_iter().collect())
+}
+
+/// Number of elements in the state, as an estimate of the cost of `view`.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct StateSize {
+  pub addresses: u32,
+  pub tokens: u32,
+  /// Number of operators summed over all addresses.
+  pub operators: u32,
+  /// Number of implementors summed over all standards.
+  pub implementors: u32,
+}
+
+#[receive(
+  contract = "ciphers_nft",
+  name = "stateSize",
+  return_value = "StateSize"
+)]
+fn contract_state_size(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<StateSize> {
+  let state = host.state();
+  let mut addresses = 0;
+  let mut operators = 0;
+  for (_, a_state) in state.address_state.iter() {
+    addresses += 1;
+    operators += u32::from(a_state.operator_count);
+  }
+  let implementors = state
+    .implementors
+    .iter()
+    .map(|(_, implementors)| implementors.len() as u32)
+    .sum();
+
+  Ok(StateSize {
+    addresses,
+    tokens: state.counter - state.burned_counter,
     operators,
+    implementors,
   })
 }
+
+/// Names of the receive entrypoints of the contract. Keep in sync when adding
+/// or removing an entrypoint.
+pub const ENTRYPOINTS: &[&str] = &[
+  // CIS-2
+  "transfer",
+  "updateOperator",
+  "operatorOf",
+  "balanceOf",
+  "tokenMetadata",
+  "supports",
+  "setImplementors",
+  "transferWithMode",
+  "updateOperatorWithExpiry",
+  "sweepExpiredOperators",
+  "transferAndCall",
+  "permit",
+  "nonceOf",
+  // Minting and burning
+  "mint",
+  "mintTo",
+  "mintWithDeposit",
+  "canMint",
+  "publicMint",
+  "burn",
+  "assignClaims",
+  "claim",
+  "reclaimUnclaimed",
+  // Administration
+  "transferOwnership",
+  "setMinter",
+  "lockMinter",
+  "addMinter",
+  "removeMinter",
+  "setAllowlisted",
+  "setAllowedReceiver",
+  "pauseMinting",
+  "resumeMinting",
+  "lockMinting",
+  "setPaused",
+  "withdraw",
+  "setTokenRoyalty",
+  "setReservedUri",
+  "setMaxSupply",
+  "revealBatch",
+  "queueAdminAction",
+  "executeAdminAction",
+  "freeze",
+  "unfreeze",
+  "lockToken",
+  // Vault
+  "vaultDeposit",
+  "vaultRedeem",
+  "vaultTransfer",
+  "vaultBalanceOf",
+  // Queries
+  "view",
+  "viewLight",
+  "viewPaginated",
+  "viewSettings",
+  "viewAddress",
+  "tokensOf",
+  "viewAddressDetailed",
+  "adminConfig",
+  "entrypoints",
+  "getMintCountTokenID",
+  "mintTimestamp",
+  "firstOwnerOf",
+  "rolesOf",
+  "accountStats",
+  "totalSupply",
+  "totalBurned",
+  "tokensMissingUri",
+  "contractHoldings",
+  "stateSize",
+  "royaltyOf",
+  "tokenRoyalty",
+  "restrictedTokens",
+];
+
+/// The names of the receive entrypoints of the contract, so tools can
+/// discover them without a schema.
+#[receive(
+  contract = "ciphers_nft",
+  name = "entrypoints",
+  return_value = "Vec<String>"
+)]
+fn contract_entrypoints(_ctx: &ReceiveContext, _host: &Host<State>) -> ReceiveResult<Vec<String>> {
+  Ok(ENTRYPOINTS.iter().map(|name| name.to_string()).collect())
+}
@@ -1,7 +1,11 @@
 use concordium_std::*;
 
 use crate::{
-  events::{ContractEvent, DeployEvent},
+  cis2::SetImplementorsParams,
+  error::{ContractError, CustomContractError},
+  events::{ContractEvent, DeployEvent, CONTRACT_VERSION},
+  mint::MAX_TREASURY_BASIS_POINTS,
+  royalty::MAX_ROYALTY_BASIS_POINTS,
   state::State,
 };
 
@@ -14,6 +18,73 @@ pub struct InitParams {
   pub mint_start: u64,    // Unix milliseconds
   pub mint_deadline: u64, // Unix milliseconds
   pub max_total_supply: u32,
+  pub mint_price: Amount,
+  /// Log the custom `Deploy` and `Minted` events. The CIS2 events are always
+  /// logged.
+  pub emit_events: bool,
+  /// Milliseconds after `mint_deadline` during which mints are still
+  /// accepted, to absorb block time jitter.
+  pub deadline_grace_ms: u64,
+  /// Default royalty in basis points out of 10000, paid to
+  /// `royalty_recipient`.
+  pub royalty_basis_points: u16,
+  pub royalty_recipient: AccountAddress,
+  /// Reject minting a token with a URI already used by another token.
+  pub require_unique_uri: bool,
+  /// Let owners enable operators. When disabled, only owners can transfer
+  /// their tokens.
+  pub operators_enabled: bool,
+  /// Maximum number of operators per owner, 0 for unlimited.
+  pub max_operators_per_owner: u16,
+  /// Start with the minter locked, see `lockMinter`.
+  pub minter_locked: bool,
+  /// Receiver of `treasury_bps` of every `publicMint` payment, the rest is
+  /// paid to the minter. Without a treasury the payments stay in the contract.
+  pub treasury: Option<AccountAddress>,
+  pub treasury_bps: u16,
+  /// Minimum delay in milliseconds between queuing and executing an admin
+  /// action. When set, the minters can only be changed through the timelock.
+  pub admin_timelock_ms: u64,
+  /// Refund the amount paid above the price of a `publicMint`.
+  pub refund_overpayment: bool,
+  /// Block transfers until all tokens are minted or the mint deadline is
+  /// reached.
+  pub trading_after_mint: bool,
+  /// Number of leading zero bits required of a `publicMint` proof, 0 to not
+  /// require a proof.
+  pub mint_proof_difficulty: u8,
+  /// Minimum delay in milliseconds between two `publicMint` calls of an
+  /// address, 0 to disable. Allowlisted addresses are exempt.
+  pub mint_cooldown_ms: u64,
+  /// Maximum number of tokens an address can mint through `publicMint`, 0
+  /// for unlimited. Allowlisted addresses are exempt.
+  pub max_mints_per_account: u32,
+  /// Only let the contract owner burn tokens, as an operator of the token
+  /// owner.
+  pub burn_requires_admin: bool,
+  /// Unix milliseconds after which unclaimed tokens can no longer be claimed
+  /// and can be reclaimed by the owner, 0 for claims to never expire.
+  pub claim_deadline: u64,
+  /// Milliseconds after its mint during which a token cannot be transferred,
+  /// 0 to disable.
+  pub resale_lock_ms: u64,
+  /// Reject transfers by an operator to the owner of the token. Otherwise
+  /// such transfers, like any transfer to the owner, leave the state unchanged
+  /// and only log the `Transfer` event.
+  pub reject_operator_self_transfers: bool,
+  /// Only let contracts added with `setAllowedReceiver` receive tokens.
+  /// Accounts can always receive tokens.
+  pub enforce_receiver_whitelist: bool,
+  /// URI returned by `tokenMetadata` for the tokens not yet revealed with
+  /// `revealBatch`, `None` to reveal every token at mint.
+  pub placeholder_uri: Option<String>,
+  /// Milliseconds within which a `permit` message must expire, during which
+  /// its hash is kept to reject replays. 0 to require sequential nonces
+  /// instead.
+  pub permit_replay_window_ms: u64,
+  /// Implementors of standards registered at init, as if `setImplementors`
+  /// was called for each of them.
+  pub initial_implementors: Vec<SetImplementorsParams>,
 }
 
 /// Initialize contract instance with no token types initially.
@@ -29,17 +100,28 @@ fn contract_init(
   logger: &mut Logger,
 ) -> InitResult<State> {
   let params: InitParams = ctx.parameter_cursor().get()?;
+  ensure!(
+    params.royalty_basis_points <= MAX_ROYALTY_BASIS_POINTS,
+    ContractError::from(CustomContractError::InvalidRoyalty).into()
+  );
+  ensure!(
+    params.treasury_bps <= MAX_TREASURY_BASIS_POINTS,
+    ContractError::from(CustomContractError::InvalidFeeSplit).into()
+  );
 
-  logger.log(&ContractEvent::Deploy(DeployEvent {
-    name: params.name.clone(),
-    symbol: params.symbol.clone(),
-    contract_uri: params.contract_uri.clone(),
-    minter: params.minter,
-    mint_start: params.mint_start,
-    mint_deadline: params.mint_deadline,
-    max_total_supply: params.max_total_supply,
-  }))?;
+  if params.emit_events {
+    logger.log(&ContractEvent::Deploy(DeployEvent {
+      name: params.name.clone(),
+      symbol: params.symbol.clone(),
+      contract_uri: params.contract_uri.clone(),
+      minter: params.minter,
+      mint_start: params.mint_start,
+      mint_deadline: params.mint_deadline,
+      max_total_supply: params.max_total_supply,
+      contract_version: CONTRACT_VERSION,
+    }))?;
+  }
 
-  // Construct the initial contract state.
-  Ok(State::init(state_builder, params))
+  // Construct the initial contract state, owned by the account creating it.
+  Ok(State::init(state_builder, ctx.init_origin(), params))
 }
@@ -1,10 +1,18 @@
 #![cfg_attr(not(feature = "std"), no_std)]
+pub mod burn;
 pub mod cis2;
+pub mod claim;
 pub mod contract_view; // testing only
 pub mod error;
 pub mod events;
 pub mod getters;
 pub mod init;
 pub mod mint;
+pub mod permit;
+pub mod restrictions;
+pub mod royalty;
 pub mod setters;
 pub mod state;
+pub mod timelock;
+pub mod transfer_and_call;
+pub mod vault;
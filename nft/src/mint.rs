@@ -4,36 +4,88 @@ use concordium_std::*;
 use crate::{
   cis2::{ContractTokenAmount, ContractTokenId},
   error::{ContractError, ContractResult, CustomContractError},
-  events::{ContractEvent, MintedEvent},
+  events::{ContractEvent, MintedEvent, WithdrawEvent},
+  royalty::{TokenRoyalty, MAX_ROYALTY_BASIS_POINTS},
   state::State,
 };
 
+/// Maximum value of `treasury_bps`, which pays the whole payment to the
+/// treasury.
+pub const MAX_TREASURY_BASIS_POINTS: u16 = 10000;
+
 /// The parameter for the contract function `mint` which mints a number of
 /// tokens to a given address.
 #[derive(Serial, Deserial, SchemaType)]
 pub struct MintParams {
   /// Owner of the newly minted tokens.
-  #[concordium(size_length = 1)] // max size of 256
+  #[concordium(size_length = 1)] // max size of 255
   pub owners: Vec<Address>,
   /// A collection of tokens to mint.
-  #[concordium(size_length = 1)] // max size of 256
+  #[concordium(size_length = 1)] // max size of 255
   pub tokens: Vec<ContractTokenId>,
   /// The metadata URL for the token.
-  #[concordium(size_length = 1)] // max size of 256
+  #[concordium(size_length = 1)] // max size of 255
   pub token_uris: Vec<String>,
+  /// Royalties overriding the contract default for each token, in the order
+  /// of `tokens`. Empty to use the contract default for every token. Copies
+  /// of an existing edition cannot be given a royalty.
+  #[concordium(size_length = 1)] // max size of 255
+  pub royalties: Vec<TokenRoyalty>,
+  /// SHA-256 hashes of the token metadata, in the order of `tokens`. Empty
+  /// to mint every token without a hash. Copies of an existing edition
+  /// cannot be given a hash.
+  #[concordium(size_length = 1)] // max size of 255
+  pub token_hashes: Vec<Option<[u8; 32]>>,
+}
+
+/// Length in milliseconds of the block time buckets a mint proof is valid for.
+pub const MINT_PROOF_BUCKET_MS: u64 = 60_000;
+
+/// The parameter for the contract function `publicMint`.
+#[derive(Serial, Deserial, SchemaType)]
+pub struct PublicMintParams {
+  pub mint: MintParams,
+  /// Nonce proving the work required by `mint_proof_difficulty`, ignored
+  /// when no proof is required.
+  pub nonce: u64,
+}
+
+/// The message hashed for a mint proof: the sender, the nonce and the block
+/// time bucket.
+pub fn mint_proof_message(sender: &Address, nonce: u64, block_time: u64) -> Vec<u8> {
+  let mut message = to_bytes(sender);
+  message.extend_from_slice(&nonce.to_le_bytes());
+  message.extend_from_slice(&(block_time / MINT_PROOF_BUCKET_MS).to_le_bytes());
+  message
+}
+
+/// Number of leading zero bits of a hash.
+pub fn leading_zero_bits(hash: &[u8]) -> u32 {
+  let mut bits = 0;
+  for byte in hash {
+    bits += byte.leading_zeros();
+    if *byte != 0 {
+      break;
+    }
+  }
+  bits
 }
 
 /// Mint new tokens with a given address as the owner of these tokens.
-/// Can only be called by the contract owner.
+/// Can only be called by the minters.
 /// Logs a `Mint` and a `TokenMetadata` event for each token.
+/// Tokens are minted and assigned mint counts in the order of the parameter.
 /// The url for the token metadata is the token ID encoded in hex, appended on
 /// the `TOKEN_METADATA_BASE_URL`.
 ///
 /// It rejects if:
-/// - The sender is not the contract instance owner.
+/// - The sender is not a minter.
 /// - Fails to parse parameter.
 /// - Any of the tokens fails to be minted, which could be if:
-///     - The minted token ID already exists.
+///     - The minted token ID already exists, unless it is an edition with
+///       copies left, see `setMaxSupply`.
+///     - A copy of an existing edition is given a royalty or a hash, which
+///       would change them for every holder.
 ///     - Fails to log Mint event
 ///     - Fails to log TokenMetadata event
 ///
@@ -53,33 +105,400 @@ fn contract_mint(
   logger: &mut Logger,
 ) -> ContractResult<()> {
   let (state, builder) = host.state_and_builder();
-  let sender = ctx.sender();
-  let minter = state.minter;
-  ensure!(sender.matches_account(&minter), ContractError::Unauthorized);
+  ensure!(state.is_minter(&ctx.sender()), ContractError::Unauthorized);
+
+  // Parse the parameter.
+  let params: MintParams = ctx.parameter_cursor().get()?;
+  mint_tokens(ctx, state, builder, logger, params)
+}
+
+/// The parameter for the contract function `mintTo` which mints a single
+/// token.
+#[derive(Serial, Deserial, SchemaType)]
+pub struct MintToParams {
+  /// Owner of the newly minted token.
+  pub owner: Address,
+  /// The token to mint.
+  pub token_id: ContractTokenId,
+  /// The metadata URL for the token.
+  pub token_uri: String,
+}
+
+impl From<MintToParams> for MintParams {
+  fn from(params: MintToParams) -> Self {
+    MintParams {
+      owners: vec![params.owner],
+      tokens: vec![params.token_id],
+      token_uris: vec![params.token_uri],
+      royalties: Vec::new(),
+      token_hashes: Vec::new(),
+    }
+  }
+}
+
+/// Mint a single token, see `mint`.
+/// Can only be called by the minters.
+///
+/// It rejects if:
+/// - The sender is not a minter.
+/// - Fails to parse parameter.
+/// - The token fails to be minted, see `mint`.
+#[receive(
+  contract = "ciphers_nft",
+  name = "mintTo",
+  parameter = "MintToParams",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_mint_to(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  let (state, builder) = host.state_and_builder();
+  ensure!(state.is_minter(&ctx.sender()), ContractError::Unauthorized);
+
+  let params: MintToParams = ctx.parameter_cursor().get()?;
+  mint_tokens(ctx, state, builder, logger, params.into())
+}
+
+/// Mint a single token, see `mint`, escrowing the attached CCD as the deposit
+/// of the token. Burning the token releases the deposit to the burner, which
+/// makes it redeemable like a coupon.
+/// Can only be called by the minters.
+///
+/// It rejects if:
+/// - The sender is not a minter.
+/// - Fails to parse parameter.
+/// - The token already exists, so copies of editions cannot carry a deposit.
+/// - The token fails to be minted, see `mint`.
+#[receive(
+  contract = "ciphers_nft",
+  name = "mintWithDeposit",
+  parameter = "MintToParams",
+  error = "ContractError",
+  enable_logger,
+  payable,
+  mutable
+)]
+fn contract_mint_with_deposit(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  amount: Amount,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  let (state, builder) = host.state_and_builder();
+  ensure!(state.is_minter(&ctx.sender()), ContractError::Unauthorized);
+
+  let params: MintToParams = ctx.parameter_cursor().get()?;
+  let token_id = params.token_id;
+  ensure!(
+    !state.contains_token(&token_id),
+    CustomContractError::TokenIdAlreadyExists.into()
+  );
+  mint_tokens(ctx, state, builder, logger, params.into())?;
+  if amount > Amount::zero() {
+    state.token_deposit.insert(token_id, amount);
+    state.escrowed_deposits += amount;
+  }
+  Ok(())
+}
+
+/// Mint new tokens by paying the `mint_price` for every token in the batch.
+/// Can be called by any address while the mint window is active.
+/// With a `mint_proof_difficulty` set, the sha256 hash of the
+/// `mint_proof_message` must start with that many zero bits.
+/// Logs the same events as `mint`.
+/// Unless the sender is allowlisted, it must respect the `mint_cooldown_ms`
+/// since its previous `publicMint` and the `max_mints_per_account`.
+/// With `refund_overpayment` set, the amount paid above the price is refunded
+/// to the sender if it is an account. With a treasury configured, the
+/// retained payment is split between the treasury and the minter.
+///
+/// It rejects if:
+/// - Fails to parse parameter.
+/// - The nonce is not a valid mint proof.
+/// - The attached amount is less than `mint_price` times the number of tokens,
+///   or that price does not fit an `Amount`.
+/// - The sender is not allowlisted and minted within the cooldown or would
+///   exceed the per-account cap.
+/// - Any of the tokens fails to be minted, see `mint`.
+/// - Fails to refund the sender or to pay the treasury or the minter.
+#[receive(
+  contract = "ciphers_nft",
+  name = "publicMint",
+  parameter = "PublicMintParams",
+  error = "ContractError",
+  enable_logger,
+  payable,
+  mutable,
+  crypto_primitives
+)]
+fn contract_public_mint(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  amount: Amount,
+  logger: &mut Logger,
+  crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+  let (state, builder) = host.state_and_builder();
+
+  // Parse the parameter.
+  let PublicMintParams {
+    mint: params,
+    nonce,
+  } = ctx.parameter_cursor().get()?;
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+  if state.mint_proof_difficulty > 0 {
+    let message = mint_proof_message(&ctx.sender(), nonce, block_time);
+    let hash = crypto_primitives.hash_sha2_256(&message);
+    ensure!(
+      leading_zero_bits(&hash.0) >= u32::from(state.mint_proof_difficulty),
+      CustomContractError::InvalidMintProof.into()
+    );
+  }
+  let price = state
+    .mint_price
+    .micro_ccd
+    .checked_mul(params.tokens.len() as u64)
+    .map(Amount::from_micro_ccd)
+    .ok_or(CustomContractError::InsufficientPayment)?;
+  ensure!(
+    amount >= price,
+    CustomContractError::InsufficientPayment.into()
+  );
+  state.record_public_mint(&ctx.sender(), params.tokens.len() as u32, block_time)?;
+  mint_tokens(ctx, state, builder, logger, params)?;
+
+  let state = host.state();
+  let mut retained = amount;
+  if state.refund_overpayment && amount > price {
+    if let Address::Account(sender) = ctx.sender() {
+      host
+        .invoke_transfer(&sender, amount - price)
+        .map_err(CustomContractError::from)?;
+      retained = price;
+    }
+  }
+
+  let state = host.state();
+  if let Some(treasury) = state.treasury {
+    let minter = state.minter;
+    let treasury_share = Amount::from_micro_ccd(
+      (u128::from(retained.micro_ccd) * u128::from(state.treasury_bps)
+        / u128::from(MAX_TREASURY_BASIS_POINTS)) as u64,
+    );
+    host
+      .invoke_transfer(&treasury, treasury_share)
+      .map_err(CustomContractError::from)?;
+    host
+      .invoke_transfer(&minter, retained - treasury_share)
+      .map_err(CustomContractError::from)?;
+  }
+  Ok(())
+}
+
+/// The parameter for the contract function `canMint`.
+#[derive(Serial, Deserial, SchemaType)]
+pub struct CanMintParams {
+  /// The address that would send the mint.
+  pub sender: Address,
+  /// The token to mint.
+  pub token_id: ContractTokenId,
+  /// Owner of the minted token. No precondition depends on the owner yet.
+  pub owner: Address,
+}
+
+/// Whether a mint would pass the preconditions, or the first one it fails.
+#[derive(Serial, Deserial, SchemaType, PartialEq, Eq, Debug)]
+pub enum MintEligibility {
+  Ok,
+  Paused,
+  MintingPaused,
+  NotStarted,
+  DeadlinePassed,
+  TokenExists,
+  SoldOut,
+  Cooldown,
+  AccountCapReached,
+  MintingLocked,
+}
+
+/// Check whether `sender` could mint a token now, without changing the state.
+/// Minters are checked against `mint`, other addresses against `publicMint`,
+/// including the cooldown and the per-account cap unless allowlisted. The
+/// payment, the mint proof and the URI are not checked.
+///
+/// It rejects if:
+/// - Fails to parse parameter.
+#[receive(
+  contract = "ciphers_nft",
+  name = "canMint",
+  parameter = "CanMintParams",
+  return_value = "MintEligibility",
+  error = "ContractError"
+)]
+fn contract_can_mint(ctx: &ReceiveContext, host: &Host<State>) -> ContractResult<MintEligibility> {
+  let params: CanMintParams = ctx.parameter_cursor().get()?;
+  let state = host.state();
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+  let supply = state.supply_of(&params.token_id);
+
+  let eligibility = if state.paused {
+    MintEligibility::Paused
+  } else if state.minting_paused {
+    MintEligibility::MintingPaused
+  } else if state.minting_locked {
+    MintEligibility::MintingLocked
+  } else if block_time < state.mint_start {
+    MintEligibility::NotStarted
+  } else if block_time >= state.mint_deadline.saturating_add(state.deadline_grace_ms) {
+    MintEligibility::DeadlinePassed
+  } else if supply > 0 && state.max_supply.get(&params.token_id).is_none() {
+    MintEligibility::TokenExists
+  } else if state.counter >= state.max_total_supply
+    || supply >= state.max_supply_of(&params.token_id)
+  {
+    MintEligibility::SoldOut
+  } else if state.is_minter(&params.sender) || state.allowlist.contains(&params.sender) {
+    MintEligibility::Ok
+  } else {
+    match state.check_public_mint(&params.sender, 1, block_time) {
+      Ok(_) => MintEligibility::Ok,
+      Err(Cis2Error::Custom(CustomContractError::MintCooldown)) => MintEligibility::Cooldown,
+      Err(_) => MintEligibility::AccountCapReached,
+    }
+  };
+  Ok(eligibility)
+}
+
+/// The parameter for the contract function `withdraw`.
+#[derive(Serial, Deserial, SchemaType)]
+pub struct WithdrawParams {
+  /// The account receiving the balance of the contract.
+  pub receiver: AccountAddress,
+}
+
+/// Transfer the CCD balance of the contract, such as the proceeds of
+/// `publicMint`, to the receiver and log a `Withdraw` event. The deposits of
+/// tokens minted with `mintWithDeposit` are kept. Does nothing when there is
+/// nothing to withdraw.
+/// Can only be called by the contract owner.
+///
+/// It rejects if:
+/// - The sender is not the contract instance owner.
+/// - Fails to parse parameter.
+/// - Fails to transfer the balance to the receiver.
+#[receive(
+  contract = "ciphers_nft",
+  name = "withdraw",
+  parameter = "WithdrawParams",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_withdraw(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: WithdrawParams = ctx.parameter_cursor().get()?;
+  let amount = host.self_balance() - host.state().escrowed_deposits;
+  if amount == Amount::zero() {
+    return Ok(());
+  }
+  host
+    .invoke_transfer(&params.receiver, amount)
+    .map_err(CustomContractError::from)?;
+  logger.log(&ContractEvent::Withdraw(WithdrawEvent {
+    receiver: params.receiver,
+    amount,
+  }))?;
+  Ok(())
+}
+
+/// Mint every token in `params` after checking the mint window, and log the
+/// `Mint` and `Minted` events for each of them.
+/// The tokens are minted in the order of `params.tokens`, so the first token
+/// gets the lowest mint count and its events are logged first. The arrays are
+/// checked to have the same length before minting, so none of them is
+/// truncated. `royalties` and `token_hashes` may also be empty.
+pub(crate) fn mint_tokens(
+  ctx: &ReceiveContext,
+  state: &mut State,
+  builder: &mut StateBuilder,
+  logger: &mut Logger,
+  params: MintParams,
+) -> ContractResult<()> {
+  ensure!(!state.paused, CustomContractError::ContractPaused.into());
+  ensure!(
+    !state.minting_paused,
+    CustomContractError::MintingPaused.into()
+  );
+  ensure!(
+    !state.minting_locked,
+    CustomContractError::MintingLocked.into()
+  );
   let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
   ensure!(
     block_time >= state.mint_start,
     CustomContractError::MintingNotStarted.into()
   );
   ensure!(
-    block_time < state.mint_deadline,
+    block_time < state.mint_deadline.saturating_add(state.deadline_grace_ms),
     CustomContractError::MintDeadlineReached.into()
   );
 
-  // Parse the parameter.
-  let params: MintParams = ctx.parameter_cursor().get()?;
   ensure!(
     params.owners.len() == params.tokens.len() && params.owners.len() == params.token_uris.len(),
     CustomContractError::ArraysNotSameLength.into()
   );
-  for ((&token_id, owner), token_uri) in params
+  ensure!(
+    params.royalties.is_empty() || params.royalties.len() == params.tokens.len(),
+    CustomContractError::ArraysNotSameLength.into()
+  );
+  ensure!(
+    params.token_hashes.is_empty() || params.token_hashes.len() == params.tokens.len(),
+    CustomContractError::ArraysNotSameLength.into()
+  );
+  ensure!(
+    params
+      .royalties
+      .iter()
+      .all(|royalty| royalty.basis_points <= MAX_ROYALTY_BASIS_POINTS),
+    CustomContractError::InvalidRoyalty.into()
+  );
+  for (index, ((&token_id, owner), token_uri)) in params
     .tokens
     .iter()
     .zip(params.owners)
     .zip(params.token_uris)
+    .enumerate()
   {
+    let royalty = params.royalties.get(index);
+    let hash = params.token_hashes.get(index).copied().flatten();
+    // The royalty and hash of an edition are set by its first copy.
+    ensure!(
+      (royalty.is_none() && hash.is_none()) || !state.contains_token(&token_id),
+      CustomContractError::TokenIdAlreadyExists.into()
+    );
+
     // Mint the token in the state.
-    let mint_count = state.mint(token_id, &owner, &token_uri, builder)?;
+    let mint_count = state.mint(token_id, &owner, &token_uri, block_time, builder)?;
+    if let Some(royalty) = royalty {
+      state
+        .token_royalty
+        .insert(token_id, (royalty.basis_points, royalty.recipient));
+    }
+    if let Some(hash) = hash {
+      state.token_hashes.insert(token_id, hash);
+    }
 
     // Event for minted NFT.
     logger.log(&ContractEvent::Mint(MintEvent {
@@ -94,20 +513,30 @@ fn contract_mint(
     //   token_id,
     //   metadata_url: MetadataUrl {
     //     url: token_uri.clone(),
-    //     hash: None,
+    //     hash,
     //   },
     // }))?;
 
-    // Event for minted NFT.
-    logger.log(&ContractEvent::Minted(MintedEvent {
-      token_id,
-      mint_count,
-      timestamp: block_time,
-      token_uri: MetadataUrl {
-        url: token_uri,
-        hash: None,
-      },
-    }))?;
+    // Event for minted NFT, skipped to save energy when events are disabled.
+    // An unrevealed token logs the placeholder instead of its URI.
+    if state.emit_events {
+      let token_uri = match state.unrevealed_uri(mint_count) {
+        Some(placeholder_uri) => MetadataUrl {
+          url: placeholder_uri.clone(),
+          hash: None,
+        },
+        None => MetadataUrl {
+          url: token_uri,
+          hash,
+        },
+      };
+      logger.log(&ContractEvent::Minted(MintedEvent {
+        token_id,
+        mint_count,
+        timestamp: block_time,
+        token_uri,
+      }))?;
+    }
   }
 
   Ok(())
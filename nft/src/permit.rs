@@ -0,0 +1,229 @@
+//! Sponsored transfers in the style of the CIS3 `permit` function.
+//!
+//! A relayer submits a message signed by an account, and the contract executes
+//! the message as if it was sent by that account. Only `transfer` messages
+//! are supported.
+//!
+//! Replays are prevented in one of two ways, chosen at init:
+//! - Without a replay window, every message must carry the next nonce of the
+//!   signer, see `nonceOf`.
+//! - With a replay window, the nonce is not checked. Instead the message must
+//!   expire within the window, and the hashes of the accepted messages are
+//!   kept until they expire, so several permits of one signer can be
+//!   submitted in parallel. The hashes are grouped in buckets of the window
+//!   length by expiry time, and whole buckets are pruned once expired, which
+//!   bounds the stored hashes to those submitted in the last two windows.
+use concordium_cis2::*;
+use concordium_std::*;
+
+use crate::{
+  cis2::{execute_transfer, TransferMode, TransferParameter, MAX_TRANSFER_BATCH},
+  error::{ContractError, ContractResult, CustomContractError},
+  state::State,
+};
+
+/// The message signed by the signer of a permit.
+#[derive(Debug, Serialize, SchemaType, Clone)]
+pub struct PermitMessage {
+  /// The contract the message is meant for.
+  pub contract_address: ContractAddress,
+  /// The next nonce of the signer, ignored with a replay window.
+  pub nonce: u64,
+  /// Time after which the message can no longer be executed.
+  pub timestamp: Timestamp,
+  /// The entrypoint to execute, only `transfer` is supported.
+  pub entry_point: OwnedEntrypointName,
+  /// The serialized parameter of the entrypoint.
+  #[concordium(size_length = 2)]
+  pub payload: Vec<u8>,
+}
+
+/// The parameter for the contract function `permit`.
+#[derive(Debug, Serialize, SchemaType)]
+pub struct PermitParam {
+  pub signature: AccountSignatures,
+  pub signer: AccountAddress,
+  pub message: PermitMessage,
+}
+
+/// The parameter for the contract function `nonceOf`.
+#[derive(Serialize, SchemaType)]
+#[concordium(transparent)]
+pub struct NonceOfQueryParams {
+  #[concordium(size_length = 2)]
+  pub queries: Vec<AccountAddress>,
+}
+
+/// The response of the contract function `nonceOf`, in the order of the
+/// queries.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+#[concordium(transparent)]
+pub struct NonceOfQueryResponse(#[concordium(size_length = 2)] pub Vec<u64>);
+
+/// The hash signed by the signer of a permit: the signer address, eight zero
+/// bytes and the serialized message, as signed by the wallets.
+pub fn permit_message_hash(
+  crypto_primitives: &impl HasCryptoPrimitives,
+  signer: &AccountAddress,
+  message: &PermitMessage,
+) -> [u8; 32] {
+  let mut bytes = signer.0.to_vec();
+  bytes.extend_from_slice(&[0u8; 8]);
+  message.serial(&mut bytes).unwrap_abort();
+  crypto_primitives.hash_sha2_256(&bytes).0
+}
+
+impl State {
+  /// Check and consume the nonce of `signer`.
+  fn use_permit_nonce(&mut self, signer: AccountAddress, nonce: u64) -> ContractResult<()> {
+    let mut entry = self.nonces.entry(signer).or_insert(0);
+    ensure!(*entry == nonce, CustomContractError::NonceMismatch.into());
+    *entry += 1;
+    Ok(())
+  }
+
+  /// Record the hash of a permit expiring at `expiry`, rejecting it if it was
+  /// already seen. Expired buckets are pruned first.
+  fn use_permit_hash(&mut self, hash: [u8; 32], expiry: u64, now: u64) -> ContractResult<()> {
+    let window = self.permit_replay_window_ms;
+    ensure!(
+      expiry <= now.saturating_add(window),
+      CustomContractError::PermitOutsideWindow.into()
+    );
+
+    // A bucket before the one of `now` only holds permits expired before
+    // `now`, which can no longer be executed.
+    let current_bucket = now / window;
+    let expired: Vec<u64> = self
+      .seen_permit_buckets
+      .iter()
+      .map(|(bucket, _)| *bucket)
+      .filter(|bucket| *bucket < current_bucket)
+      .collect();
+    for bucket in expired {
+      if let Some(hashes) = self.seen_permit_buckets.remove_and_get(&bucket) {
+        for hash in hashes.iter() {
+          self.seen_permits.remove(hash);
+        }
+      }
+    }
+
+    ensure!(
+      self.seen_permits.insert(hash),
+      CustomContractError::PermitReplayed.into()
+    );
+    self
+      .seen_permit_buckets
+      .entry(expiry / window)
+      .or_default()
+      .push(hash);
+    Ok(())
+  }
+}
+
+/// Execute a message signed by `signer` as if it was sent by `signer`.
+/// Logs the `Transfer` events of the executed transfers.
+///
+/// It rejects if:
+/// - It fails to parse the parameter or the payload.
+/// - The message is meant for another contract.
+/// - The message is expired.
+/// - The signature is not valid for the signer.
+/// - Without a replay window, the nonce is not the next nonce of the signer.
+/// - With a replay window, the message expires after the window or was
+///   already executed.
+/// - The entrypoint is not `transfer`.
+/// - Any of the transfers fails, see `transfer`.
+#[receive(
+  contract = "ciphers_nft",
+  name = "permit",
+  parameter = "PermitParam",
+  error = "ContractError",
+  crypto_primitives,
+  enable_logger,
+  mutable
+)]
+fn contract_permit(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+  crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+  let param: PermitParam = ctx.parameter_cursor().get()?;
+  let message = param.message;
+  let now = ctx.metadata().block_time();
+
+  ensure!(
+    message.contract_address == ctx.self_address(),
+    CustomContractError::WrongContract.into()
+  );
+  ensure!(
+    message.timestamp >= now,
+    CustomContractError::PermitExpired.into()
+  );
+
+  let hash = permit_message_hash(crypto_primitives, &param.signer, &message);
+  let valid = host
+    .check_account_signature(param.signer, &param.signature, &hash)
+    .map_err(|_| ContractError::from(CustomContractError::WrongSignature))?;
+  ensure!(valid, CustomContractError::WrongSignature.into());
+
+  let state = host.state_mut();
+  if state.permit_replay_window_ms == 0 {
+    state.use_permit_nonce(param.signer, message.nonce)?;
+  } else {
+    state.use_permit_hash(
+      hash,
+      message.timestamp.timestamp_millis(),
+      now.timestamp_millis(),
+    )?;
+  }
+
+  ensure!(
+    message.entry_point.as_entrypoint_name() == EntrypointName::new_unchecked("transfer"),
+    CustomContractError::WrongEntryPoint.into()
+  );
+  let TransferParams(transfers): TransferParameter = from_bytes(&message.payload)?;
+  ensure!(
+    transfers.len() <= MAX_TRANSFER_BATCH,
+    CustomContractError::BatchTooLarge.into()
+  );
+  let block_time = now.timestamp_millis();
+  for transfer in transfers {
+    execute_transfer(
+      host,
+      logger,
+      Address::Account(param.signer),
+      block_time,
+      transfer,
+      TransferMode::Atomic,
+    )?;
+  }
+  Ok(())
+}
+
+/// Get the next nonce of each of the queried accounts, used by `permit`
+/// without a replay window.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+  contract = "ciphers_nft",
+  name = "nonceOf",
+  parameter = "NonceOfQueryParams",
+  return_value = "NonceOfQueryResponse",
+  error = "ContractError"
+)]
+fn contract_nonce_of(
+  ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ContractResult<NonceOfQueryResponse> {
+  let params: NonceOfQueryParams = ctx.parameter_cursor().get()?;
+  let state = host.state();
+  let nonces = params
+    .queries
+    .iter()
+    .map(|account| state.nonces.get(account).map_or(0, |nonce| *nonce))
+    .collect();
+  Ok(NonceOfQueryResponse(nonces))
+}
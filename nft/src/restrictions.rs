@@ -0,0 +1,149 @@
+use concordium_std::*;
+
+use crate::{
+  cis2::ContractTokenId,
+  error::{ContractError, ContractResult, CustomContractError},
+  getters::ViewItems,
+  state::State,
+};
+
+/// Check that every token in `tokens` exists.
+fn ensure_tokens_exist(state: &State, tokens: &[ContractTokenId]) -> ContractResult<()> {
+  for token_id in tokens {
+    ensure!(
+      state.contains_token(token_id),
+      ContractError::InvalidTokenId
+    );
+  }
+  Ok(())
+}
+
+/// Freeze tokens, preventing them from being transferred until unfrozen.
+/// Can only be called by the contract owner.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - Any of the tokens does not exist.
+#[receive(
+  contract = "ciphers_nft",
+  name = "freeze",
+  parameter = "Vec<ContractTokenId>",
+  error = "ContractError",
+  mutable
+)]
+fn contract_freeze(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let tokens: Vec<ContractTokenId> = ctx.parameter_cursor().get()?;
+  ensure_tokens_exist(host.state(), &tokens)?;
+  for token_id in tokens {
+    host.state_mut().frozen.insert(token_id);
+  }
+  Ok(())
+}
+
+/// Unfreeze tokens frozen with `freeze`. Tokens that are not frozen are
+/// ignored.
+/// Can only be called by the contract owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "unfreeze",
+  parameter = "Vec<ContractTokenId>",
+  error = "ContractError",
+  mutable
+)]
+fn contract_unfreeze(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let tokens: Vec<ContractTokenId> = ctx.parameter_cursor().get()?;
+  for token_id in tokens {
+    host.state_mut().frozen.remove(&token_id);
+  }
+  Ok(())
+}
+
+#[derive(Debug, Serialize, SchemaType)]
+pub struct LockTokenParams {
+  pub token_id: ContractTokenId,
+  /// The token cannot be transferred before this time. A time in the past
+  /// removes the lock.
+  pub until: Timestamp,
+}
+
+/// Lock a token, preventing it from being transferred until a given time.
+/// Replaces any earlier lock of the token.
+/// Can only be called by the contract owner.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The token does not exist.
+#[receive(
+  contract = "ciphers_nft",
+  name = "lockToken",
+  parameter = "LockTokenParams",
+  error = "ContractError",
+  mutable
+)]
+fn contract_lock_token(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: LockTokenParams = ctx.parameter_cursor().get()?;
+  let state = host.state_mut();
+  ensure!(
+    state.contains_token(&params.token_id),
+    ContractError::InvalidTokenId
+  );
+  if params.until <= ctx.metadata().block_time() {
+    state.locked_until.remove(&params.token_id);
+  } else {
+    state.locked_until.insert(params.token_id, params.until);
+  }
+  Ok(())
+}
+
+#[derive(Debug, Serialize, SchemaType, PartialEq, Eq)]
+pub struct RestrictedTokens {
+  /// The frozen tokens.
+  pub frozen: Vec<ContractTokenId>,
+  /// The locked tokens and the time their lock expires. Expired locks are
+  /// not included.
+  pub locked: Vec<(ContractTokenId, Timestamp)>,
+  /// Whether tokens were left out, see `MAX_VIEW_ITEMS`.
+  pub truncated: bool,
+}
+
+/// View the tokens that currently cannot be transferred because they are
+/// frozen or locked.
+#[receive(
+  contract = "ciphers_nft",
+  name = "restrictedTokens",
+  return_value = "RestrictedTokens"
+)]
+fn contract_restricted_tokens(
+  ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ReceiveResult<RestrictedTokens> {
+  let state = host.state();
+  let block_time = ctx.metadata().block_time();
+  let frozen: ViewItems<_> = state.frozen.iter().map(|token_id| *token_id).collect();
+  let locked: ViewItems<_> = state
+    .locked_until
+    .iter()
+    .filter(|(_, until)| **until > block_time)
+    .map(|(token_id, until)| (*token_id, *until))
+    .collect();
+  Ok(RestrictedTokens {
+    frozen: frozen.items,
+    locked: locked.items,
+    truncated: frozen.truncated || locked.truncated,
+  })
+}
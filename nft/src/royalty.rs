@@ -0,0 +1,149 @@
+use concordium_std::*;
+
+use crate::{
+  cis2::ContractTokenId,
+  error::{ContractError, ContractResult, CustomContractError},
+  state::State,
+};
+
+/// Royalties are expressed in basis points out of 10000.
+pub const MAX_ROYALTY_BASIS_POINTS: u16 = 10000;
+
+/// The parameter type for the contract function `royaltyOf`.
+#[derive(Debug, Serialize, SchemaType)]
+pub struct RoyaltyOfQueryParams {
+  pub token_id: ContractTokenId,
+  /// The price the token is sold for.
+  pub sale_price: Amount,
+}
+
+#[derive(Debug, Serialize, SchemaType, PartialEq, Eq)]
+pub struct RoyaltyOfResponse {
+  /// The account receiving the royalty.
+  pub receiver: AccountAddress,
+  /// The royalty owed on the sale price.
+  pub royalty_amount: Amount,
+}
+
+/// Get the royalty owed to the creator when a token is sold for a given
+/// price. Uses the royalty set for the token with `setTokenRoyalty`, or the
+/// contract default otherwise.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The `token_id` does not exist.
+#[receive(
+  contract = "ciphers_nft",
+  name = "royaltyOf",
+  parameter = "RoyaltyOfQueryParams",
+  return_value = "RoyaltyOfResponse",
+  error = "ContractError"
+)]
+fn contract_royalty_of(
+  ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ContractResult<RoyaltyOfResponse> {
+  let params: RoyaltyOfQueryParams = ctx.parameter_cursor().get()?;
+  let state = host.state();
+  ensure!(
+    state.contains_token(&params.token_id),
+    ContractError::InvalidTokenId
+  );
+
+  let (basis_points, receiver) = state.royalty(&params.token_id);
+  let royalty_amount =
+    params.sale_price.micro_ccd() as u128 * basis_points as u128 / MAX_ROYALTY_BASIS_POINTS as u128;
+
+  Ok(RoyaltyOfResponse {
+    receiver,
+    royalty_amount: Amount::from_micro_ccd(royalty_amount as u64),
+  })
+}
+
+/// Royalty of a single token.
+#[derive(Debug, Serialize, SchemaType, PartialEq, Eq)]
+pub struct TokenRoyalty {
+  pub recipient: AccountAddress,
+  /// Royalty in basis points out of 10000.
+  pub basis_points: u16,
+}
+
+/// The response of the contract function `tokenRoyalty`.
+pub type RoyaltyResponse = TokenRoyalty;
+
+/// Get the royalty of a token: the royalty set at mint or with
+/// `setTokenRoyalty`, or the contract default otherwise.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The token does not exist.
+#[receive(
+  contract = "ciphers_nft",
+  name = "tokenRoyalty",
+  parameter = "ContractTokenId",
+  return_value = "RoyaltyResponse",
+  error = "ContractError"
+)]
+fn contract_token_royalty(
+  ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ContractResult<RoyaltyResponse> {
+  let token_id: ContractTokenId = ctx.parameter_cursor().get()?;
+  let state = host.state();
+  ensure!(
+    state.contains_token(&token_id),
+    ContractError::InvalidTokenId
+  );
+
+  let (basis_points, recipient) = state.royalty(&token_id);
+  Ok(RoyaltyResponse {
+    recipient,
+    basis_points,
+  })
+}
+
+/// The parameter type for the contract function `setTokenRoyalty`.
+#[derive(Debug, Serialize, SchemaType)]
+pub struct SetTokenRoyaltyParams {
+  pub token_id: ContractTokenId,
+  /// Royalty in basis points out of 10000.
+  pub basis_points: u16,
+  pub recipient: AccountAddress,
+}
+
+/// Set a royalty for a single token, overriding the contract default.
+///
+/// It rejects if:
+/// - Sender is not the owner of the contract instance.
+/// - It fails to parse the parameter.
+/// - The `token_id` does not exist.
+/// - The basis points exceed 10000.
+#[receive(
+  contract = "ciphers_nft",
+  name = "setTokenRoyalty",
+  parameter = "SetTokenRoyaltyParams",
+  error = "ContractError",
+  mutable
+)]
+fn contract_set_token_royalty(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: SetTokenRoyaltyParams = ctx.parameter_cursor().get()?;
+  ensure!(
+    params.basis_points <= MAX_ROYALTY_BASIS_POINTS,
+    CustomContractError::InvalidRoyalty.into()
+  );
+  let state = host.state_mut();
+  ensure!(
+    state.contains_token(&params.token_id),
+    ContractError::InvalidTokenId
+  );
+
+  state
+    .token_royalty
+    .insert(params.token_id, (params.basis_points, params.recipient));
+  Ok(())
+}
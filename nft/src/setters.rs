@@ -1,7 +1,9 @@
 use concordium_std::*;
 
 use crate::{
-  error::{ContractError, ContractResult},
+  cis2::ContractTokenId,
+  error::{ContractError, ContractResult, CustomContractError},
+  events::{ContractEvent, OwnershipTransferredEvent},
   state::State,
 };
 
@@ -10,6 +12,10 @@ pub struct SetMinter {
   pub minter: AccountAddress,
 }
 
+/// Set the primary minter, replacing it in the minters. Setting the current
+/// minter again is a no-op that succeeds, even when the minter is locked.
+/// Can only be called by the contract owner, and only when there is no admin
+/// timelock, otherwise use `queueAdminAction`.
 #[receive(
   contract = "ciphers_nft",
   name = "setMinter",
@@ -19,11 +25,364 @@ pub struct SetMinter {
 )]
 fn contract_set_minter(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
   ensure!(
-    ctx.sender().matches_account(&ctx.owner()),
-    ContractError::Unauthorized
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
   );
 
   let params: SetMinter = ctx.parameter_cursor().get()?;
-  host.state_mut().set_minter(params.minter);
+  if params.minter == host.state().minter {
+    return Ok(());
+  }
+  ensure!(
+    host.state().admin_timelock_ms == 0,
+    CustomContractError::TimelockRequired.into()
+  );
+  host.state_mut().set_minter(params.minter)
+}
+
+/// Permanently prevent the minters from being changed through `setMinter`,
+/// `addMinter` and `removeMinter`.
+/// Can only be called by the contract owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "lockMinter",
+  error = "ContractError",
+  mutable
+)]
+fn contract_lock_minter(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  host.state_mut().lock_minter();
+  Ok(())
+}
+
+/// Allow an additional account to mint, next to the primary minter.
+/// Can only be called by the contract owner while the minter is not locked,
+/// and only when there is no admin timelock, otherwise use `queueAdminAction`.
+#[receive(
+  contract = "ciphers_nft",
+  name = "addMinter",
+  parameter = "AccountAddress",
+  error = "ContractError",
+  mutable
+)]
+fn contract_add_minter(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  ensure!(
+    host.state().admin_timelock_ms == 0,
+    CustomContractError::TimelockRequired.into()
+  );
+  let minter: AccountAddress = ctx.parameter_cursor().get()?;
+  host.state_mut().set_minter_allowed(minter, true)
+}
+
+/// Stop an account from minting. Removing the primary minter only revokes its
+/// right to mint, it still receives the `publicMint` payments.
+/// Can only be called by the contract owner while the minter is not locked,
+/// and only when there is no admin timelock, otherwise use `queueAdminAction`.
+#[receive(
+  contract = "ciphers_nft",
+  name = "removeMinter",
+  parameter = "AccountAddress",
+  error = "ContractError",
+  mutable
+)]
+fn contract_remove_minter(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  ensure!(
+    host.state().admin_timelock_ms == 0,
+    CustomContractError::TimelockRequired.into()
+  );
+  let minter: AccountAddress = ctx.parameter_cursor().get()?;
+  host.state_mut().set_minter_allowed(minter, false)
+}
+
+#[derive(Debug, Serialize, SchemaType)]
+pub struct SetAllowlisted {
+  pub address: Address,
+  pub allowed: bool,
+}
+
+/// Add or remove an address from the allowlist. Allowlisted addresses bypass
+/// the `publicMint` cooldown and per-account cap.
+/// Can only be called by the contract owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "setAllowlisted",
+  parameter = "SetAllowlisted",
+  error = "ContractError",
+  mutable
+)]
+fn contract_set_allowlisted(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: SetAllowlisted = ctx.parameter_cursor().get()?;
+  host
+    .state_mut()
+    .set_allowlisted(params.address, params.allowed);
+  Ok(())
+}
+
+#[derive(Debug, Serialize, SchemaType)]
+pub struct SetAllowedReceiver {
+  pub address: ContractAddress,
+  pub allowed: bool,
+}
+
+/// Add or remove a contract from the receiver whitelist, which restricts the
+/// contracts that can receive tokens when `enforce_receiver_whitelist` is set.
+/// Can only be called by the contract owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "setAllowedReceiver",
+  parameter = "SetAllowedReceiver",
+  error = "ContractError",
+  mutable
+)]
+fn contract_set_allowed_receiver(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: SetAllowedReceiver = ctx.parameter_cursor().get()?;
+  host
+    .state_mut()
+    .set_allowed_receiver(params.address, params.allowed);
+  Ok(())
+}
+
+#[derive(Debug, Serialize, SchemaType)]
+pub struct SetReservedUri {
+  pub token_id: ContractTokenId,
+  /// The URI returned for the token ID, `None` to release the ID.
+  pub uri: Option<String>,
+}
+
+/// Reserve a token ID for a collection-level asset, such as a banner or a
+/// logo, so `tokenMetadata` returns the given URI for it, or release it.
+/// Can only be called by the contract owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "setReservedUri",
+  parameter = "SetReservedUri",
+  error = "ContractError",
+  mutable
+)]
+fn contract_set_reserved_uri(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: SetReservedUri = ctx.parameter_cursor().get()?;
+  let state = host.state_mut();
+  match params.uri {
+    Some(uri) => {
+      state.reserved_uris.insert(params.token_id, uri);
+    }
+    None => {
+      state.reserved_uris.remove(&params.token_id);
+    }
+  }
+  Ok(())
+}
+
+#[derive(Debug, Serialize, SchemaType)]
+pub struct SetMaxSupply {
+  pub token_id: ContractTokenId,
+  /// The number of copies of the token that can be minted, 1 for a unique
+  /// token.
+  pub max_supply: u64,
+}
+
+/// Set the number of copies that can be minted of a token ID, turning it into
+/// an edition whose copies are minted with `mint` like new tokens. A max
+/// supply of 1 makes the token unique again. Once a copy is minted, the max
+/// supply can only change through the timelock with `AdminAction::SetMaxSupply`.
+/// Can only be called by the contract owner.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - A copy of the token is minted already.
+/// - The max supply is 0.
+#[receive(
+  contract = "ciphers_nft",
+  name = "setMaxSupply",
+  parameter = "SetMaxSupply",
+  error = "ContractError",
+  mutable
+)]
+fn contract_set_max_supply(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: SetMaxSupply = ctx.parameter_cursor().get()?;
+  let state = host.state_mut();
+  ensure!(
+    state.supply_of(&params.token_id) == 0,
+    CustomContractError::MaxSupplyLocked.into()
+  );
+  state.set_max_supply(params.token_id, params.max_supply)
+}
+
+#[derive(Debug, Serialize, SchemaType)]
+pub struct TransferOwnershipParams {
+  pub new_owner: AccountAddress,
+}
+
+/// Transfer the ownership of the contract, and with it every owner-only
+/// entrypoint, to a new account. Logs an `OwnershipTransferred` event.
+/// Can only be called by the current owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "transferOwnership",
+  parameter = "TransferOwnershipParams",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_transfer_ownership(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  let previous_owner = host.state().owner;
+  ensure!(
+    ctx.sender().matches_account(&previous_owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: TransferOwnershipParams = ctx.parameter_cursor().get()?;
+  host.state_mut().owner = params.new_owner;
+  logger.log(&ContractEvent::OwnershipTransferred(
+    OwnershipTransferredEvent {
+      previous_owner,
+      new_owner: params.new_owner,
+    },
+  ))?;
+  Ok(())
+}
+
+/// Reveal the next tokens in mint order: `tokenMetadata` returns the URI of
+/// the tokens with a mint count up to the new `revealed_up_to` instead of the
+/// placeholder. Tokens that are not minted yet are not revealed. Returns the
+/// new `revealed_up_to`.
+/// Can only be called by the contract owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "revealBatch",
+  parameter = "u32",
+  return_value = "u32",
+  error = "ContractError",
+  mutable
+)]
+fn contract_reveal_batch(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<u32> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let count: u32 = ctx.parameter_cursor().get()?;
+  Ok(host.state_mut().reveal_batch(count))
+}
+
+#[derive(Debug, Serialize, SchemaType)]
+pub struct SetPaused {
+  pub paused: bool,
+}
+
+/// Pause or resume both minting and transfers, for example during an
+/// incident. Unlike `pauseMinting`, this also stops transfers.
+/// Can only be called by the contract owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "setPaused",
+  parameter = "SetPaused",
+  error = "ContractError",
+  mutable
+)]
+fn contract_set_paused(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: SetPaused = ctx.parameter_cursor().get()?;
+  host.state_mut().set_paused(params.paused);
+  Ok(())
+}
+
+/// Pause minting through `mint` and `publicMint`. Transfers are not affected.
+/// Can only be called by the contract owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "pauseMinting",
+  error = "ContractError",
+  mutable
+)]
+fn contract_pause_minting(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  host.state_mut().set_minting_paused(true);
+  Ok(())
+}
+
+/// Resume minting after `pauseMinting`.
+/// Can only be called by the contract owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "resumeMinting",
+  error = "ContractError",
+  mutable
+)]
+fn contract_resume_minting(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  host.state_mut().set_minting_paused(false);
+  Ok(())
+}
+
+/// Disable minting through every mint entrypoint for good, so the supply is
+/// final. This cannot be undone, unlike `pauseMinting`.
+/// Can only be called by the contract owner.
+#[receive(
+  contract = "ciphers_nft",
+  name = "lockMinting",
+  error = "ContractError",
+  mutable
+)]
+fn contract_lock_minting(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  host.state_mut().lock_minting();
   Ok(())
 }
@@ -5,27 +5,45 @@ use crate::{
   cis2::{ContractTokenAmount, ContractTokenId, MintCountTokenID},
   error::{ContractError, ContractResult, CustomContractError},
   init::InitParams,
+  royalty::MAX_ROYALTY_BASIS_POINTS,
+  timelock::{AdminAction, PendingAdminAction},
 };
 
+/// Expiry of operators added without one.
+pub const NO_EXPIRY: Timestamp = Timestamp::from_timestamp_millis(u64::MAX);
+
 /// The state for each address.
 #[derive(Serial, DeserialWithState, Deletable)]
 #[concordium(state_parameter = "S")]
 pub struct AddressState<S = StateApi> {
-  /// The tokens owned by this address.
+  /// The tokens of which this address owns at least one copy.
   pub owned_tokens: StateSet<ContractTokenId, S>,
-  /// The address which are currently enabled as operators for this address.
-  pub operators: StateSet<Address, S>,
+  /// The address which are currently enabled as operators for this address,
+  /// and the time their rights expire.
+  pub operators: StateMap<Address, Timestamp, S>,
+  /// The number of operators in `operators`.
+  pub operator_count: u16,
 }
 
 impl AddressState {
   fn empty(state_builder: &mut StateBuilder) -> Self {
     AddressState {
       owned_tokens: state_builder.new_set(),
-      operators: state_builder.new_set(),
+      operators: state_builder.new_map(),
+      operator_count: 0,
     }
   }
 }
 
+/// Add `amount` copies to a balance, which must still fit a
+/// `ContractTokenAmount`.
+fn add_to_balance(balance: u64, amount: u64) -> ContractResult<u64> {
+  balance
+    .checked_add(amount)
+    .filter(|balance| *balance <= u64::from(u8::MAX))
+    .ok_or_else(|| CustomContractError::AmountOverflow.into())
+}
+
 /// The contract state.
 // Note: The specification does not specify how to structure the contract state
 // and this could be structured in a more space efficient way depending on the use case.
@@ -36,8 +54,24 @@ pub struct State<S = StateApi> {
   pub address_state: StateMap<Address, AddressState<S>, S>,
   /// All of the token IDs
   pub all_tokens: StateSet<ContractTokenId, S>,
+  /// Maximum number of copies of the tokens minted as editions, tokens
+  /// without an entry are unique
+  pub max_supply: StateMap<ContractTokenId, u64, S>,
+  /// Number of copies in circulation of each edition in `max_supply`, a
+  /// unique token has one copy while it exists
+  pub token_supply: StateMap<ContractTokenId, u64, S>,
+  /// Number of copies of each token owned by each address
+  pub balances: StateMap<(ContractTokenId, Address), u64, S>,
   /// Map with the tokenUris
   pub token_uris: StateMap<ContractTokenId, String, S>,
+  /// URI returned for the tokens that are not revealed yet, `None` to reveal
+  /// every token at mint
+  pub placeholder_uri: Option<String>,
+  /// Highest mint count revealed with `revealBatch`
+  pub revealed_up_to: u32,
+  /// URIs of reserved token IDs for collection-level assets, returned by
+  /// `tokenMetadata` without the token being minted
+  pub reserved_uris: StateMap<ContractTokenId, String, S>,
   /// Map with contract addresses providing implementations of additional
   /// standards.
   pub implementors: StateMap<StandardIdentifierOwned, Vec<ContractAddress>, S>,
@@ -48,52 +82,250 @@ pub struct State<S = StateApi> {
   pub symbol: String,
   /// Contract URI for metadata of the contract
   pub contract_uri: MetadataUrl,
-  /// address of the minter
+  /// Owner of the contract, allowed to change its settings. Initially the
+  /// account that created the instance, see `transferOwnership`
+  pub owner: AccountAddress,
+  /// address of the primary minter, which receives the `publicMint`
+  /// payments not paid to the treasury
   pub minter: AccountAddress,
+  /// Accounts allowed to mint, including the primary minter
+  pub minters: StateSet<AccountAddress, S>,
+  /// Whether the minter can no longer be changed
+  pub minter_locked: bool,
   /// Counter of the mints
   pub counter: MintCountTokenID,
+  /// Counter of the burns
+  pub burned_counter: u32,
   /// Counter of the mint
   pub mint_count: StateMap<ContractTokenId, MintCountTokenID, S>,
+  /// Number of tokens ever minted to each address, including tokens since
+  /// transferred away or burned
+  pub minted_per_account: StateMap<Address, u32, S>,
+  /// Unix timestamp in milliseconds at which each token was minted
+  pub mint_timestamp: StateMap<ContractTokenId, u64, S>,
+  /// The address each token was minted to, not changed by transfers
+  pub first_owner: StateMap<ContractTokenId, Address, S>,
+  /// Royalties overriding the contract default for individual tokens
+  pub token_royalty: StateMap<ContractTokenId, (u16, AccountAddress), S>,
+  /// SHA-256 hashes of the metadata of the tokens minted with one
+  pub token_hashes: StateMap<ContractTokenId, [u8; 32], S>,
+  /// CCD deposited at `mintWithDeposit`, released to the burner of the token
+  pub token_deposit: StateMap<ContractTokenId, Amount, S>,
+  /// Sum of `token_deposit`, which `withdraw` leaves in the contract
+  pub escrowed_deposits: Amount,
+  /// Token URIs already minted, only tracked when `require_unique_uri` is set
+  pub used_uris: StateSet<String, S>,
+  /// Tokens and their URIs assigned to an account, waiting to be claimed
+  pub claims: StateMap<AccountAddress, Vec<(ContractTokenId, String)>, S>,
+  /// Unix timestamp in milliseconds after which claims expire, 0 for never
+  pub claim_deadline: u64,
   /// Unix timestamp to start minting
   pub mint_start: u64,
   /// Minting deadline in Unix timestamp
   pub mint_deadline: u64,
   /// Max total supply
   pub max_total_supply: u32,
+  /// Price of a single token minted through `publicMint`
+  pub mint_price: Amount,
+  /// Whether the custom `Minted` event is logged on mint
+  pub emit_events: bool,
+  /// Grace period in milliseconds after the minting deadline
+  pub deadline_grace_ms: u64,
+  /// Whether minting is paused, transfers are not affected
+  pub minting_paused: bool,
+  /// Whether minting is disabled for good, see `lockMinting`
+  pub minting_locked: bool,
+  /// Whether both minting and transfers are paused
+  pub paused: bool,
+  /// Default royalty in basis points for tokens without an override
+  pub royalty_basis_points: u16,
+  /// Receiver of the default royalty
+  pub royalty_recipient: AccountAddress,
+  /// Whether every token must have a distinct URI
+  pub require_unique_uri: bool,
+  /// Whether owners can enable operators
+  pub operators_enabled: bool,
+  /// Maximum number of operators per owner, 0 for unlimited
+  pub max_operators_per_owner: u16,
+  /// Receiver of the treasury share of `publicMint` payments
+  pub treasury: Option<AccountAddress>,
+  /// Share of `publicMint` payments paid to the treasury
+  pub treasury_bps: u16,
+  /// Whether `publicMint` refunds the amount paid above the price
+  pub refund_overpayment: bool,
+  /// Whether transfers are blocked until the collection is minted out or the
+  /// mint deadline is reached
+  pub trading_after_mint: bool,
+  /// Number of leading zero bits required of a `publicMint` proof
+  pub mint_proof_difficulty: u8,
+  /// Minimum delay in milliseconds between two `publicMint` calls of an
+  /// address, 0 to disable
+  pub mint_cooldown_ms: u64,
+  /// Maximum number of tokens an address can mint through `publicMint`, 0
+  /// for unlimited
+  pub max_mints_per_account: u32,
+  /// Number of tokens minted through `publicMint` and the time of the last
+  /// mint for each address
+  pub account_mints: StateMap<Address, (u32, u64), S>,
+  /// Addresses exempt from the mint cooldown and the per-account cap
+  pub allowlist: StateSet<Address, S>,
+  /// Vault tokens of each address, see `vault`
+  pub vault_balances: StateMap<Address, u64, S>,
+  /// Whether burns must also be sent by the contract owner
+  pub burn_requires_admin: bool,
+  /// Tokens that cannot be transferred until unfrozen
+  pub frozen: StateSet<ContractTokenId, S>,
+  /// Time until which individual tokens cannot be transferred
+  pub locked_until: StateMap<ContractTokenId, Timestamp, S>,
+  /// Milliseconds after its mint during which a token cannot be transferred
+  pub resale_lock_ms: u64,
+  /// Whether operators are rejected when transferring a token to its owner
+  pub reject_operator_self_transfers: bool,
+  /// Contracts allowed to receive tokens when the whitelist is enforced
+  pub allowed_receivers: StateSet<ContractAddress, S>,
+  /// Whether only contracts in `allowed_receivers` can receive tokens
+  pub enforce_receiver_whitelist: bool,
+  /// Minimum delay in milliseconds of queued admin actions, 0 to disable
+  pub admin_timelock_ms: u64,
+  /// Admin actions waiting to be executed
+  pub admin_actions: StateMap<u32, PendingAdminAction, S>,
+  /// ID of the next queued admin action
+  pub next_admin_action_id: u32,
+  /// Next `permit` nonce of each signer
+  pub nonces: StateMap<AccountAddress, u64, S>,
+  /// Replay window of `permit` in milliseconds, 0 to use nonces instead
+  pub permit_replay_window_ms: u64,
+  /// Hashes of the permits executed within the replay window
+  pub seen_permits: StateSet<[u8; 32], S>,
+  /// Hashes in `seen_permits` by expiry time divided by the replay window
+  pub seen_permit_buckets: StateMap<u64, Vec<[u8; 32]>, S>,
 }
 
 impl State {
-  /// Creates a new state with no tokens.
-  pub fn init(state_builder: &mut StateBuilder, init_params: InitParams) -> Self {
-    State {
+  /// Creates a new state owned by `owner`, with no tokens and the initial
+  /// implementors.
+  pub fn init(
+    state_builder: &mut StateBuilder,
+    owner: AccountAddress,
+    init_params: InitParams,
+  ) -> Self {
+    let mut state = State {
       name: init_params.name,
       symbol: init_params.symbol,
       contract_uri: init_params.contract_uri,
+      owner,
       address_state: state_builder.new_map(),
       all_tokens: state_builder.new_set(),
+      max_supply: state_builder.new_map(),
+      token_supply: state_builder.new_map(),
+      balances: state_builder.new_map(),
       token_uris: state_builder.new_map(),
+      reserved_uris: state_builder.new_map(),
+      placeholder_uri: init_params.placeholder_uri,
+      revealed_up_to: 0,
       implementors: state_builder.new_map(),
       mint_count: state_builder.new_map(),
+      minted_per_account: state_builder.new_map(),
+      mint_timestamp: state_builder.new_map(),
+      first_owner: state_builder.new_map(),
+      token_royalty: state_builder.new_map(),
+      token_hashes: state_builder.new_map(),
+      token_deposit: state_builder.new_map(),
+      escrowed_deposits: Amount::zero(),
+      used_uris: state_builder.new_set(),
+      claims: state_builder.new_map(),
+      claim_deadline: init_params.claim_deadline,
       counter: 0,
+      burned_counter: 0,
+      minter_locked: init_params.minter_locked,
       minter: init_params.minter,
+      minters: state_builder.new_set(),
       mint_start: init_params.mint_start,
       mint_deadline: init_params.mint_deadline,
       max_total_supply: init_params.max_total_supply,
+      mint_price: init_params.mint_price,
+      emit_events: init_params.emit_events,
+      deadline_grace_ms: init_params.deadline_grace_ms,
+      minting_paused: false,
+      minting_locked: false,
+      paused: false,
+      royalty_basis_points: init_params.royalty_basis_points,
+      royalty_recipient: init_params.royalty_recipient,
+      require_unique_uri: init_params.require_unique_uri,
+      operators_enabled: init_params.operators_enabled,
+      max_operators_per_owner: init_params.max_operators_per_owner,
+      treasury: init_params.treasury,
+      treasury_bps: init_params.treasury_bps,
+      refund_overpayment: init_params.refund_overpayment,
+      trading_after_mint: init_params.trading_after_mint,
+      mint_proof_difficulty: init_params.mint_proof_difficulty,
+      mint_cooldown_ms: init_params.mint_cooldown_ms,
+      max_mints_per_account: init_params.max_mints_per_account,
+      account_mints: state_builder.new_map(),
+      allowlist: state_builder.new_set(),
+      vault_balances: state_builder.new_map(),
+      burn_requires_admin: init_params.burn_requires_admin,
+      frozen: state_builder.new_set(),
+      locked_until: state_builder.new_map(),
+      resale_lock_ms: init_params.resale_lock_ms,
+      reject_operator_self_transfers: init_params.reject_operator_self_transfers,
+      allowed_receivers: state_builder.new_set(),
+      enforce_receiver_whitelist: init_params.enforce_receiver_whitelist,
+      admin_timelock_ms: init_params.admin_timelock_ms,
+      admin_actions: state_builder.new_map(),
+      next_admin_action_id: 0,
+      nonces: state_builder.new_map(),
+      permit_replay_window_ms: init_params.permit_replay_window_ms,
+      seen_permits: state_builder.new_set(),
+      seen_permit_buckets: state_builder.new_map(),
+    };
+    state.minters.insert(state.minter);
+    for implementors in init_params.initial_implementors {
+      state.set_implementors(implementors.id, implementors.implementors);
     }
+    state
   }
 
-  /// Mint a new token with a given address as the owner
+  /// Mint a copy of a token with a given address as the owner. The first
+  /// copy creates the token with `token_uri`, further copies can only be
+  /// minted of editions, see `max_supply`, and keep the URI, mint count and
+  /// first owner of the first copy.
+  /// Every copy counts towards `max_total_supply`.
   pub fn mint(
     &mut self,
     token: ContractTokenId,
     owner: &Address,
-    token_uri: &String,
+    token_uri: &str,
+    timestamp: u64,
     state_builder: &mut StateBuilder,
   ) -> ContractResult<u32> {
-    ensure!(
-      self.all_tokens.insert(token) && self.token_uris.insert(token, token_uri.clone()).is_none(),
-      CustomContractError::TokenIdAlreadyExists.into()
-    );
+    let max_supply = self.max_supply_of(&token);
+    let supply = if max_supply > 1 {
+      self.supply_of(&token)
+    } else {
+      0
+    };
+    if supply == 0 {
+      ensure!(
+        self.all_tokens.insert(token)
+          && self
+            .token_uris
+            .insert(token, token_uri.to_string())
+            .is_none(),
+        CustomContractError::TokenIdAlreadyExists.into()
+      );
+      if self.require_unique_uri {
+        ensure!(
+          self.used_uris.insert(token_uri.to_string()),
+          CustomContractError::DuplicateUri.into()
+        );
+      }
+    } else {
+      ensure!(
+        supply < max_supply,
+        CustomContractError::MaxTotalSupplyReached.into()
+      );
+    }
 
     self.counter += 1;
     let count = self.counter;
@@ -103,7 +335,23 @@ impl State {
       CustomContractError::MaxTotalSupplyReached.into()
     );
 
-    self.mint_count.insert(token, count);
+    if supply == 0 {
+      self.mint_count.insert(token, count);
+      self.mint_timestamp.insert(token, timestamp);
+      self.first_owner.insert(token, *owner);
+    }
+    if max_supply > 1 {
+      self.token_supply.insert(token, supply + 1);
+    }
+    *self.minted_per_account.entry(*owner).or_insert(0) += 1;
+    let balance = if supply == 0 {
+      0
+    } else {
+      self.balance_of(&token, owner)
+    };
+    self
+      .balances
+      .insert((token, *owner), add_to_balance(balance, 1)?);
 
     let mut owner_state = self
       .address_state
@@ -115,6 +363,234 @@ impl State {
     Ok(count)
   }
 
+  /// Maximum number of copies of a token, 1 unless it is an edition.
+  pub fn max_supply_of(&self, token_id: &ContractTokenId) -> u64 {
+    self
+      .max_supply
+      .get(token_id)
+      .map_or(1, |max_supply| *max_supply)
+  }
+
+  /// Number of copies of a token in circulation.
+  pub fn supply_of(&self, token_id: &ContractTokenId) -> u64 {
+    match self.token_supply.get(token_id) {
+      Some(supply) => *supply,
+      None => u64::from(self.contains_token(token_id)),
+    }
+  }
+
+  /// Set the maximum number of copies of a token, keeping `token_supply` in
+  /// sync. Results in an error if it is 0 or below the copies in circulation.
+  /// The copies held by a single address must still fit a
+  /// `ContractTokenAmount`.
+  pub fn set_max_supply(
+    &mut self,
+    token_id: ContractTokenId,
+    max_supply: u64,
+  ) -> ContractResult<()> {
+    let supply = self.supply_of(&token_id);
+    ensure!(
+      max_supply > 0 && max_supply >= supply,
+      CustomContractError::InvalidMaxSupply.into()
+    );
+    if max_supply == 1 {
+      self.max_supply.remove(&token_id);
+      self.token_supply.remove(&token_id);
+    } else {
+      self.max_supply.insert(token_id, max_supply);
+      if supply > 0 {
+        self.token_supply.insert(token_id, supply);
+      }
+    }
+    Ok(())
+  }
+
+  /// Number of copies of a token owned by an address.
+  pub fn balance_of(&self, token_id: &ContractTokenId, address: &Address) -> u64 {
+    self
+      .balances
+      .get(&(*token_id, *address))
+      .map_or(0, |balance| *balance)
+  }
+
+  /// Set the balance of an address, keeping `owned_tokens` in sync.
+  fn set_balance(
+    &mut self,
+    token_id: &ContractTokenId,
+    address: &Address,
+    balance: u64,
+    state_builder: &mut StateBuilder,
+  ) {
+    if balance == 0 {
+      self.balances.remove(&(*token_id, *address));
+      if let Some(mut address_state) = self.address_state.get_mut(address) {
+        address_state.owned_tokens.remove(token_id);
+      }
+    } else {
+      self.balances.insert((*token_id, *address), balance);
+      self
+        .address_state
+        .entry(*address)
+        .or_insert_with(|| AddressState::empty(state_builder))
+        .owned_tokens
+        .insert(*token_id);
+    }
+  }
+
+  /// Record a `publicMint` of `tokens` tokens by `account`.
+  /// Results in an error if the account minted less than `mint_cooldown_ms`
+  /// ago or would exceed `max_mints_per_account`. Allowlisted addresses are
+  /// not checked nor recorded.
+  pub fn record_public_mint(
+    &mut self,
+    account: &Address,
+    tokens: u32,
+    block_time: u64,
+  ) -> ContractResult<()> {
+    if self.allowlist.contains(account) {
+      return Ok(());
+    }
+    let minted = self.check_public_mint(account, tokens, block_time)?;
+    self.account_mints.insert(*account, (minted, block_time));
+    Ok(())
+  }
+
+  /// Check the `mint_cooldown_ms` and `max_mints_per_account` of a
+  /// `publicMint` of `tokens` tokens by `account`, without recording it.
+  /// Returns the number of tokens the account minted including these.
+  pub fn check_public_mint(
+    &self,
+    account: &Address,
+    tokens: u32,
+    block_time: u64,
+  ) -> ContractResult<u32> {
+    let (minted, last_mint) = self.account_mints.get(account).map_or((0, None), |entry| {
+      let (minted, last_mint) = *entry;
+      (minted, Some(last_mint))
+    });
+    if let Some(last_mint) = last_mint {
+      ensure!(
+        block_time >= last_mint.saturating_add(self.mint_cooldown_ms),
+        CustomContractError::MintCooldown.into()
+      );
+    }
+    let minted = minted.saturating_add(tokens);
+    ensure!(
+      self.max_mints_per_account == 0 || minted <= self.max_mints_per_account,
+      CustomContractError::AccountMintCapReached.into()
+    );
+    Ok(minted)
+  }
+
+  /// Check that `to` can receive tokens: accounts always can, contracts only
+  /// if they are whitelisted or the whitelist is not enforced.
+  pub fn check_receiver(&self, to: &Address) -> ContractResult<()> {
+    if let Address::Contract(address) = to {
+      ensure!(
+        !self.enforce_receiver_whitelist || self.allowed_receivers.contains(address),
+        CustomContractError::ReceiverNotAllowed.into()
+      );
+    }
+    Ok(())
+  }
+
+  /// Add or remove a contract from the receiver whitelist.
+  pub fn set_allowed_receiver(&mut self, address: ContractAddress, allowed: bool) {
+    if allowed {
+      self.allowed_receivers.insert(address);
+    } else {
+      self.allowed_receivers.remove(&address);
+    }
+  }
+
+  /// Add or remove an address from the allowlist.
+  pub fn set_allowlisted(&mut self, address: Address, allowed: bool) {
+    if allowed {
+      self.allowlist.insert(address);
+    } else {
+      self.allowlist.remove(&address);
+    }
+  }
+
+  /// Whether the assigned claims expired at `block_time`.
+  pub fn claims_expired(&self, block_time: u64) -> bool {
+    self.claim_deadline != 0 && block_time >= self.claim_deadline
+  }
+
+  /// Remove all the claims, returning the tokens and URIs that were assigned.
+  pub fn take_claims(&mut self) -> Vec<(ContractTokenId, String)> {
+    let accounts: Vec<AccountAddress> = self.claims.iter().map(|(account, _)| *account).collect();
+    let mut claims = Vec::new();
+    for account in accounts {
+      if let Some(tokens) = self.claims.remove_and_get(&account) {
+        claims.extend(tokens);
+      }
+    }
+    claims
+  }
+
+  /// Burn a copy of a token owned by the given address. Burning the last
+  /// copy removes the token with its freeze and lock, returning its deposit,
+  /// if any.
+  /// The mint `counter` is not decremented, so the mint count of the other
+  /// tokens stays stable and burned tokens still count towards
+  /// `max_total_supply`.
+  pub fn burn(
+    &mut self,
+    token_id: &ContractTokenId,
+    owner: &Address,
+  ) -> ContractResult<Option<Amount>> {
+    ensure!(self.contains_token(token_id), ContractError::InvalidTokenId);
+    let balance = self.balance_of(token_id, owner);
+    ensure!(balance > 0, ContractError::InsufficientFunds);
+    if balance == 1 {
+      self.balances.remove(&(*token_id, *owner));
+      if let Some(mut owner_state) = self.address_state.get_mut(owner) {
+        owner_state.owned_tokens.remove(token_id);
+      }
+    } else {
+      self.balances.insert((*token_id, *owner), balance - 1);
+    }
+    self.burned_counter += 1;
+
+    let supply = self.supply_of(token_id) - 1;
+    if supply > 0 {
+      self.token_supply.insert(*token_id, supply);
+      return Ok(None);
+    }
+    self.token_supply.remove(token_id);
+    self.all_tokens.remove(token_id);
+    self.token_uris.remove(token_id);
+    self.mint_count.remove(token_id);
+    self.mint_timestamp.remove(token_id);
+    self.first_owner.remove(token_id);
+    self.token_royalty.remove(token_id);
+    self.token_hashes.remove(token_id);
+    self.frozen.remove(token_id);
+    self.locked_until.remove(token_id);
+    let deposit = self.token_deposit.remove_and_get(token_id);
+    if let Some(deposit) = deposit {
+      self.escrowed_deposits -= deposit;
+    }
+    Ok(deposit)
+  }
+
+  /// The placeholder URI if the token with the given mint count is not
+  /// revealed yet, `None` if its own URI can be shown.
+  pub fn unrevealed_uri(&self, mint_count: u32) -> Option<&String> {
+    self
+      .placeholder_uri
+      .as_ref()
+      .filter(|_| mint_count > self.revealed_up_to)
+  }
+
+  /// Reveal the next `count` tokens in mint order, without revealing tokens
+  /// that are not minted yet. Returns the new `revealed_up_to`.
+  pub fn reveal_batch(&mut self, count: u32) -> u32 {
+    self.revealed_up_to = self.revealed_up_to.saturating_add(count).min(self.counter);
+    self.revealed_up_to
+  }
+
   /// Check that the token ID currently exists in this contract.
   #[inline(always)]
   pub fn contains_token(&self, token_id: &ContractTokenId) -> bool {
@@ -122,87 +598,144 @@ impl State {
   }
 
   /// Get the current balance of a given token ID for a given address.
-  /// Results in an error if the token ID does not exist in the state.
-  /// Since this contract only contains NFTs, the balance will always be
-  /// either 1 or 0.
+  /// Results in an error if the token ID does not exist in the state, or if
+  /// the balance does not fit a `ContractTokenAmount`.
   pub fn balance(
     &self,
     token_id: &ContractTokenId,
     address: &Address,
   ) -> ContractResult<ContractTokenAmount> {
     ensure!(self.contains_token(token_id), ContractError::InvalidTokenId);
-    let balance = self
-      .address_state
-      .get(address)
-      .map(|address_state| u8::from(address_state.owned_tokens.contains(token_id)))
-      .unwrap_or(0);
+    let balance = self.balance_of(token_id, address);
+    let balance = u8::try_from(balance)
+      .map_err(|_| ContractError::from(CustomContractError::AmountOverflow))?;
     Ok(balance.into())
   }
 
-  /// Check if a given address is an operator of a given owner address.
-  pub fn is_operator(&self, address: &Address, owner: &Address) -> bool {
+  /// Check if a given address is an operator of a given owner address at
+  /// `block_time`. Always false when operators are disabled or the operator
+  /// expired.
+  pub fn is_operator(&self, address: &Address, owner: &Address, block_time: u64) -> bool {
+    self.operators_enabled
+      && self
+        .address_state
+        .get(owner)
+        .and_then(|address_state| {
+          address_state
+            .operators
+            .get(address)
+            .map(|expiry| expiry.timestamp_millis() >= block_time)
+        })
+        .unwrap_or(false)
+  }
+
+  /// Whether transfers are allowed at `block_time`. With `trading_after_mint`
+  /// set, trading opens once all tokens are minted or the mint deadline is
+  /// reached.
+  pub fn trading_open(&self, block_time: u64) -> bool {
+    !self.trading_after_mint
+      || self.counter >= self.max_total_supply
+      || block_time >= self.mint_deadline
+  }
+
+  /// The time until which the token is locked, if the lock has not expired
+  /// at `block_time`.
+  pub fn locked_until(&self, token_id: &ContractTokenId, block_time: u64) -> Option<Timestamp> {
     self
-      .address_state
-      .get(owner)
-      .map(|address_state| address_state.operators.contains(address))
-      .unwrap_or(false)
+      .locked_until
+      .get(token_id)
+      .map(|until| *until)
+      .filter(|until| until.timestamp_millis() > block_time)
+  }
+
+  /// Whether the token is still in its resale lock at `block_time`.
+  pub fn resale_locked(&self, token_id: &ContractTokenId, block_time: u64) -> bool {
+    self.resale_lock_ms != 0
+      && self
+        .mint_timestamp
+        .get(token_id)
+        .is_some_and(|minted| block_time < minted.saturating_add(self.resale_lock_ms))
   }
 
   /// Update the state with a transfer of some token.
-  /// Results in an error if the token ID does not exist in the state or if
-  /// the from address have insufficient tokens to do the transfer.
+  /// Results in an error if the contract is paused, if the token ID does not
+  /// exist in the state, if
+  /// trading is not open yet, if the token is frozen, locked or in its resale
+  /// lock or if the from address have insufficient tokens to do the transfer.
   pub fn transfer(
     &mut self,
     token_id: &ContractTokenId,
     amount: ContractTokenAmount,
     from: &Address,
     to: &Address,
+    block_time: u64,
     state_builder: &mut StateBuilder,
   ) -> ContractResult<()> {
+    ensure!(!self.paused, CustomContractError::ContractPaused.into());
     ensure!(self.contains_token(token_id), ContractError::InvalidTokenId);
+    ensure!(
+      self.trading_open(block_time),
+      CustomContractError::TradingNotOpen.into()
+    );
+    ensure!(
+      !self.frozen.contains(token_id),
+      CustomContractError::TokenFrozen.into()
+    );
+    ensure!(
+      self.locked_until(token_id, block_time).is_none(),
+      CustomContractError::TokenLocked.into()
+    );
+    ensure!(
+      !self.resale_locked(token_id, block_time),
+      CustomContractError::ResaleLocked.into()
+    );
     // A zero transfer does not modify the state.
     if amount == 0.into() {
       return Ok(());
     }
-    // Since this contract only contains NFTs, no one will have an amount greater
-    // than 1. And since the amount cannot be the zero at this point, the
-    // address must have insufficient funds for any amount other than 1.
-    ensure_eq!(amount, 1.into(), ContractError::InsufficientFunds);
+    let amount = u64::from(amount.0);
+    let from_balance = self.balance_of(token_id, from);
+    ensure!(from_balance >= amount, ContractError::InsufficientFunds);
 
-    {
-      let mut from_address_state = self
-        .address_state
-        .get_mut(from)
-        .ok_or(ContractError::InsufficientFunds)?;
-      // Find and remove the token from the owner, if nothing is removed, we know the
-      // address did not own the token..
-      let from_had_the_token = from_address_state.owned_tokens.remove(token_id);
-      ensure!(from_had_the_token, ContractError::InsufficientFunds);
+    // A transfer to the owner does not modify the state.
+    if from == to {
+      return Ok(());
     }
 
-    // Add the token to the new owner.
-    let mut to_address_state = self
-      .address_state
-      .entry(*to)
-      .or_insert_with(|| AddressState::empty(state_builder));
-    to_address_state.owned_tokens.insert(*token_id);
+    let to_balance = add_to_balance(self.balance_of(token_id, to), amount)?;
+    self.set_balance(token_id, from, from_balance - amount, state_builder);
+    self.set_balance(token_id, to, to_balance, state_builder);
     Ok(())
   }
 
-  /// Update the state adding a new operator for a given address.
+  /// Update the state adding a new operator for a given address, whose
+  /// rights expire after `expiry`.
   /// Succeeds even if the `operator` is already an operator for the
-  /// `address`.
+  /// `address`, replacing its expiry. Rejects if the `address` already has the
+  /// maximum number of operators.
   pub fn add_operator(
     &mut self,
     owner: &Address,
     operator: &Address,
+    expiry: Timestamp,
     state_builder: &mut StateBuilder,
-  ) {
+  ) -> ContractResult<()> {
+    let max_operators = self.max_operators_per_owner;
     let mut owner_state: OccupiedEntry<'_, Address, AddressState, ExternStateApi> = self
       .address_state
       .entry(*owner)
       .or_insert_with(|| AddressState::empty(state_builder));
-    owner_state.operators.insert(*operator);
+    if let Some(mut current) = owner_state.operators.get_mut(operator) {
+      *current = expiry;
+      return Ok(());
+    }
+    ensure!(
+      max_operators == 0 || owner_state.operator_count < max_operators,
+      CustomContractError::OperatorCapExceeded.into()
+    );
+    owner_state.operators.insert(*operator, expiry);
+    owner_state.operator_count += 1;
+    Ok(())
   }
 
   /// Update the state removing an operator for a given address.
@@ -212,10 +745,31 @@ impl State {
       .address_state
       .entry(*owner)
       .and_modify(|address_state| {
-        address_state.operators.remove(operator);
+        if address_state.operators.remove_and_get(operator).is_some() {
+          address_state.operator_count -= 1;
+        }
       });
   }
 
+  /// Remove the operators of `owner` that expired at `block_time`. Returns
+  /// the number of operators removed.
+  pub fn sweep_expired_operators(&mut self, owner: &Address, block_time: u64) -> u32 {
+    let Some(mut address_state) = self.address_state.get_mut(owner) else {
+      return 0;
+    };
+    let expired: Vec<Address> = address_state
+      .operators
+      .iter()
+      .filter(|(_, expiry)| expiry.timestamp_millis() < block_time)
+      .map(|(operator, _)| *operator)
+      .collect();
+    for operator in &expired {
+      address_state.operators.remove(operator);
+      address_state.operator_count -= 1;
+    }
+    expired.len() as u32
+  }
+
   /// Check if state contains any implementors for a given standard.
   pub fn have_implementors(&self, std_id: &StandardIdentifierOwned) -> SupportResult {
     if let Some(addresses) = self.implementors.get(std_id) {
@@ -234,7 +788,115 @@ impl State {
     self.implementors.insert(std_id, implementors);
   }
 
-  pub fn set_minter(&mut self, minter: AccountAddress) {
+  /// Replace the primary minter, both as payee and in `minters`.
+  pub fn set_minter(&mut self, minter: AccountAddress) -> ContractResult<()> {
+    ensure!(
+      !self.minter_locked,
+      CustomContractError::MinterLocked.into()
+    );
+    self.minters.remove(&self.minter);
+    self.minters.insert(minter);
     self.minter = minter;
+    Ok(())
+  }
+
+  /// Add or remove an account allowed to mint.
+  pub fn set_minter_allowed(
+    &mut self,
+    minter: AccountAddress,
+    allowed: bool,
+  ) -> ContractResult<()> {
+    ensure!(
+      !self.minter_locked,
+      CustomContractError::MinterLocked.into()
+    );
+    if allowed {
+      self.minters.insert(minter);
+    } else {
+      self.minters.remove(&minter);
+    }
+    Ok(())
+  }
+
+  /// Whether the address is allowed to mint.
+  pub fn is_minter(&self, address: &Address) -> bool {
+    match address {
+      Address::Account(account) => self.minters.contains(account),
+      Address::Contract(_) => false,
+    }
+  }
+
+  /// Prevent the minters from being changed. This cannot be undone.
+  pub fn lock_minter(&mut self) {
+    self.minter_locked = true;
+  }
+
+  /// Get the royalty of a token, falling back to the contract default when
+  /// the token has no override.
+  pub fn royalty(&self, token_id: &ContractTokenId) -> (u16, AccountAddress) {
+    self
+      .token_royalty
+      .get(token_id)
+      .map(|royalty| *royalty)
+      .unwrap_or((self.royalty_basis_points, self.royalty_recipient))
+  }
+
+  pub fn set_minting_paused(&mut self, paused: bool) {
+    self.minting_paused = paused;
+  }
+
+  /// Disable minting, making the supply final. This cannot be undone.
+  pub fn lock_minting(&mut self) {
+    self.minting_locked = true;
+  }
+
+  pub fn set_paused(&mut self, paused: bool) {
+    self.paused = paused;
+  }
+
+  /// Queue an admin action, returning its ID.
+  pub fn queue_admin_action(&mut self, action: AdminAction, execute_after: u64) -> u32 {
+    let id = self.next_admin_action_id;
+    self.next_admin_action_id += 1;
+    self.admin_actions.insert(
+      id,
+      PendingAdminAction {
+        action,
+        execute_after,
+      },
+    );
+    id
+  }
+
+  /// Apply the change of an admin action.
+  pub fn apply_admin_action(&mut self, action: AdminAction) -> ContractResult<()> {
+    match action {
+      AdminAction::SetMinter(minter) => self.set_minter(minter)?,
+      AdminAction::SetMaxTotalSupply(max_total_supply) => {
+        ensure!(
+          max_total_supply >= self.counter,
+          CustomContractError::InvalidMaxSupply.into()
+        );
+        self.max_total_supply = max_total_supply;
+      }
+      AdminAction::SetRoyalty {
+        basis_points,
+        recipient,
+      } => {
+        ensure!(
+          basis_points <= MAX_ROYALTY_BASIS_POINTS,
+          CustomContractError::InvalidRoyalty.into()
+        );
+        self.royalty_basis_points = basis_points;
+        self.royalty_recipient = recipient;
+      }
+      AdminAction::AddMinter(minter) => self.set_minter_allowed(minter, true)?,
+      AdminAction::RemoveMinter(minter) => self.set_minter_allowed(minter, false)?,
+      AdminAction::SetMaxSupply {
+        token_id,
+        max_supply,
+      } => self.set_max_supply(token_id, max_supply)?,
+    }
+    Ok(())
   }
 }
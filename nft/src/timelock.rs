@@ -0,0 +1,120 @@
+use concordium_std::*;
+
+use crate::{
+  cis2::ContractTokenId,
+  error::{ContractError, ContractResult, CustomContractError},
+  state::State,
+};
+
+/// A change of the contract settings that goes through the timelock.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq, Eq)]
+pub enum AdminAction {
+  /// Change the minter, see `setMinter`.
+  SetMinter(AccountAddress),
+  /// Change the maximum number of tokens that can be minted.
+  SetMaxTotalSupply(u32),
+  /// Change the default royalty of the contract.
+  SetRoyalty {
+    basis_points: u16,
+    recipient: AccountAddress,
+  },
+  /// Allow an account to mint, see `addMinter`.
+  AddMinter(AccountAddress),
+  /// Stop an account from minting, see `removeMinter`.
+  RemoveMinter(AccountAddress),
+  /// Change the max supply of a token already minted, see `setMaxSupply`.
+  SetMaxSupply {
+    token_id: ContractTokenId,
+    max_supply: u64,
+  },
+}
+
+/// An admin action waiting to be executed.
+#[derive(Serialize, SchemaType, Clone, Debug)]
+pub struct PendingAdminAction {
+  pub action: AdminAction,
+  /// Unix timestamp in milliseconds from which the action can be executed.
+  pub execute_after: u64,
+}
+
+/// The parameter for the contract function `queueAdminAction`.
+#[derive(Serialize, SchemaType)]
+pub struct QueueAdminActionParams {
+  pub action: AdminAction,
+  /// Unix timestamp in milliseconds from which the action can be executed.
+  pub execute_after: u64,
+}
+
+/// Queue an admin action to be executed with `executeAdminAction` once
+/// `execute_after` is reached. Returns the ID of the queued action.
+/// Can only be called by the contract owner.
+///
+/// It rejects if:
+/// - The sender is not the contract instance owner.
+/// - Fails to parse parameter.
+/// - `execute_after` is earlier than the current time plus the admin timelock.
+#[receive(
+  contract = "ciphers_nft",
+  name = "queueAdminAction",
+  parameter = "QueueAdminActionParams",
+  return_value = "u32",
+  error = "ContractError",
+  mutable
+)]
+fn contract_queue_admin_action(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+) -> ContractResult<u32> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let params: QueueAdminActionParams = ctx.parameter_cursor().get()?;
+  let state = host.state_mut();
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+  ensure!(
+    params.execute_after >= block_time.saturating_add(state.admin_timelock_ms),
+    CustomContractError::TimelockTooShort.into()
+  );
+  Ok(state.queue_admin_action(params.action, params.execute_after))
+}
+
+/// Execute a queued admin action and remove it from the queue.
+/// Can only be called by the contract owner.
+///
+/// It rejects if:
+/// - The sender is not the contract instance owner.
+/// - Fails to parse parameter.
+/// - No action is queued with the given ID.
+/// - The `execute_after` of the action is not reached.
+/// - The action itself fails, such as changing a locked minter.
+#[receive(
+  contract = "ciphers_nft",
+  name = "executeAdminAction",
+  parameter = "u32",
+  error = "ContractError",
+  mutable
+)]
+fn contract_execute_admin_action(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+) -> ContractResult<()> {
+  ensure!(
+    ctx.sender().matches_account(&host.state().owner),
+    ContractError::from(CustomContractError::NotOwner)
+  );
+
+  let id: u32 = ctx.parameter_cursor().get()?;
+  let state = host.state_mut();
+  let pending = state
+    .admin_actions
+    .remove_and_get(&id)
+    .ok_or(CustomContractError::UnknownAdminAction)?;
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+  ensure!(
+    block_time >= pending.execute_after,
+    CustomContractError::TimelockNotExpired.into()
+  );
+  state.apply_admin_action(pending.action)
+}
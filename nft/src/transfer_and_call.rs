@@ -0,0 +1,105 @@
+use concordium_cis2::*;
+use concordium_std::*;
+
+use crate::{
+  cis2::{ContractTokenAmount, ContractTokenId},
+  error::{ContractError, ContractResult, CustomContractError},
+  state::State,
+};
+
+/// The parameter for the contract function `transferAndCall`.
+#[derive(Serialize, SchemaType)]
+pub struct TransferAndCallParams {
+  /// The token to transfer.
+  pub token_id: ContractTokenId,
+  /// The current owner of the token.
+  pub from: Address,
+  /// The contract receiving the token.
+  pub to: ContractAddress,
+  /// The entrypoint of `to` invoked after the transfer. Cannot be
+  /// `onReceivingCis2`, which is only invoked by `transfer`.
+  pub entrypoint: OwnedEntrypointName,
+  /// Additional data passed on to the entrypoint.
+  pub data: AdditionalData,
+}
+
+/// Name of the CIS2 receive hook, reserved for `transfer`.
+const ON_RECEIVING_CIS2: &str = "onReceivingCis2";
+
+/// Transfer a token to a contract and invoke an entrypoint of that contract,
+/// in the same transaction.
+/// Like the CIS2 receive hook, the entrypoint is invoked with
+/// `OnReceivingCis2Params` built by this contract: the token ID, an amount of
+/// 1, `from` and then `data`. The caller only chooses the entrypoint and the
+/// data, so the callee can trust the token, amount and previous owner it
+/// receives.
+/// Logs a `Transfer` event.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The entrypoint is `onReceivingCis2`.
+/// - The token does not exist.
+/// - The sender is not the owner of the token or an operator of the owner.
+/// - The token is not owned by `from`.
+/// - The receiver is not in the enforced receiver whitelist.
+/// - Fails to log event.
+/// - The invoked entrypoint rejects.
+#[receive(
+  contract = "ciphers_nft",
+  name = "transferAndCall",
+  parameter = "TransferAndCallParams",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_transfer_and_call(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  let params: TransferAndCallParams = ctx.parameter_cursor().get()?;
+  ensure!(
+    params.entrypoint.as_entrypoint_name() != EntrypointName::new_unchecked(ON_RECEIVING_CIS2),
+    CustomContractError::ReservedEntrypoint.into()
+  );
+  let sender = ctx.sender();
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+  let to = Address::Contract(params.to);
+  let amount = ContractTokenAmount::from(1);
+
+  let (state, builder) = host.state_and_builder();
+  ensure!(
+    params.from == sender || state.is_operator(&sender, &params.from, block_time),
+    ContractError::Unauthorized
+  );
+  state.check_receiver(&to)?;
+  state.transfer(
+    &params.token_id,
+    amount,
+    &params.from,
+    &to,
+    block_time,
+    builder,
+  )?;
+
+  logger.log(&Cis2Event::Transfer(TransferEvent {
+    token_id: params.token_id,
+    amount,
+    from: params.from,
+    to,
+  }))?;
+
+  let parameter = OnReceivingCis2Params {
+    token_id: params.token_id,
+    amount,
+    from: params.from,
+    data: params.data,
+  };
+  host.invoke_contract(
+    &params.to,
+    &parameter,
+    params.entrypoint.as_entrypoint_name(),
+    Amount::zero(),
+  )?;
+  Ok(())
+}
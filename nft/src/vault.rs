@@ -0,0 +1,161 @@
+//! A vault wrapping tokens into a fungible vault token.
+//!
+//! Depositing a token transfers it to this contract and credits the sender
+//! one vault token. Vault tokens are tracked in a simple ledger and can be
+//! transferred between addresses. Redeeming burns one vault token of the
+//! sender and transfers the chosen deposited token back to it.
+use concordium_cis2::*;
+use concordium_std::*;
+
+use crate::{
+  cis2::{ContractTokenAmount, ContractTokenId},
+  error::{ContractError, ContractResult},
+  state::State,
+};
+
+/// Vault balance of an address.
+pub type VaultAmount = u64;
+
+/// The parameter for the contract function `vaultTransfer`.
+#[derive(Serialize, SchemaType)]
+pub struct VaultTransferParams {
+  pub to: Address,
+  pub amount: VaultAmount,
+}
+
+/// Remove `amount` vault tokens from the balance of `owner`.
+/// Results in an error if the balance is insufficient.
+fn debit_vault(state: &mut State, owner: &Address, amount: VaultAmount) -> ContractResult<()> {
+  let mut balance = state
+    .vault_balances
+    .get_mut(owner)
+    .ok_or(ContractError::InsufficientFunds)?;
+  ensure!(*balance >= amount, ContractError::InsufficientFunds);
+  *balance -= amount;
+  Ok(())
+}
+
+/// Transfer a token between the vault and an address in the state and log the
+/// `Transfer` event.
+fn vault_transfer_token(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+  token_id: ContractTokenId,
+  from: Address,
+  to: Address,
+) -> ContractResult<()> {
+  let block_time: u64 = ctx.metadata().block_time().timestamp_millis();
+  let amount = ContractTokenAmount::from(1);
+  let (state, builder) = host.state_and_builder();
+  state.transfer(&token_id, amount, &from, &to, block_time, builder)?;
+  logger.log(&Cis2Event::Transfer(TransferEvent {
+    token_id,
+    amount,
+    from,
+    to,
+  }))?;
+  Ok(())
+}
+
+/// Deposit a token owned by the sender into the vault, crediting the sender
+/// one vault token.
+/// Logs a `Transfer` event.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The token does not exist or is not owned by the sender.
+/// - Fails to log event.
+#[receive(
+  contract = "ciphers_nft",
+  name = "vaultDeposit",
+  parameter = "ContractTokenId",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_vault_deposit(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  let token_id: ContractTokenId = ctx.parameter_cursor().get()?;
+  let sender = ctx.sender();
+  let vault = Address::Contract(ctx.self_address());
+  vault_transfer_token(ctx, host, logger, token_id, sender, vault)?;
+
+  *host.state_mut().vault_balances.entry(sender).or_insert(0) += 1;
+  Ok(())
+}
+
+/// Burn one vault token of the sender and transfer the given token from the
+/// vault to the sender.
+/// Logs a `Transfer` event.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender has no vault tokens.
+/// - The token is not in the vault.
+/// - Fails to log event.
+#[receive(
+  contract = "ciphers_nft",
+  name = "vaultRedeem",
+  parameter = "ContractTokenId",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn contract_vault_redeem(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> ContractResult<()> {
+  let token_id: ContractTokenId = ctx.parameter_cursor().get()?;
+  let sender = ctx.sender();
+  debit_vault(host.state_mut(), &sender, 1)?;
+
+  let vault = Address::Contract(ctx.self_address());
+  vault_transfer_token(ctx, host, logger, token_id, vault, sender)
+}
+
+/// Transfer vault tokens from the sender to another address.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender has less than `amount` vault tokens.
+#[receive(
+  contract = "ciphers_nft",
+  name = "vaultTransfer",
+  parameter = "VaultTransferParams",
+  error = "ContractError",
+  mutable
+)]
+fn contract_vault_transfer(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+  let params: VaultTransferParams = ctx.parameter_cursor().get()?;
+  let state = host.state_mut();
+  debit_vault(state, &ctx.sender(), params.amount)?;
+  *state.vault_balances.entry(params.to).or_insert(0) += params.amount;
+  Ok(())
+}
+
+/// The vault token balance of the given address.
+#[receive(
+  contract = "ciphers_nft",
+  name = "vaultBalanceOf",
+  parameter = "Address",
+  return_value = "VaultAmount",
+  error = "ContractError"
+)]
+fn contract_vault_balance_of(
+  ctx: &ReceiveContext,
+  host: &Host<State>,
+) -> ContractResult<VaultAmount> {
+  let address: Address = ctx.parameter_cursor().get()?;
+  Ok(
+    host
+      .state()
+      .vault_balances
+      .get(&address)
+      .map_or(0, |balance| *balance),
+  )
+}
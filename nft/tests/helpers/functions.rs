@@ -1,13 +1,16 @@
 //! Tests for the `ciphers_nft` contract.
 use ciphers_nft::{
+  cis2::ContractTokenId,
   contract_view::*,
-  events::{ContractEvent, DeployEvent},
+  events::{ContractEvent, DeployEvent, CONTRACT_VERSION},
   getters::*,
   init::InitParams,
   mint::*,
+  timelock::{AdminAction, QueueAdminActionParams},
 };
 use concordium_cis2::*;
 use concordium_smart_contract_testing::*;
+use concordium_std::{Deserial, Serial};
 
 use super::init::*;
 
@@ -22,7 +25,7 @@ pub fn mint_to_address(
   let invoker = invoker.unwrap_or(MINTER);
   let sender = sender.unwrap_or(MINTER_ADDR);
 
-  let update_result = chain.contract_update(
+  chain.contract_update(
     SIGNER,
     invoker,
     sender,
@@ -33,13 +36,229 @@ pub fn mint_to_address(
       address: contract_address,
       message: OwnedParameter::from_serial(&mint_params).expect("Mint params"),
     },
+  )
+}
+
+// Helper function that mints through the payable public mint.
+#[allow(unused)]
+pub fn public_mint(
+  chain: &mut Chain,
+  contract_address: ContractAddress,
+  mint_params: MintParams,
+  invoker: AccountAddress,
+  amount: Amount,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+  let params = PublicMintParams {
+    mint: mint_params,
+    nonce: 0,
+  };
+  public_mint_with_proof(chain, contract_address, params, invoker, amount)
+}
+
+// Helper function that calls `publicMint` with a mint proof.
+pub fn public_mint_with_proof(
+  chain: &mut Chain,
+  contract_address: ContractAddress,
+  params: PublicMintParams,
+  invoker: AccountAddress,
+  amount: Amount,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+  chain.contract_update(
+    SIGNER,
+    invoker,
+    Address::Account(invoker),
+    Energy::from(10000),
+    UpdateContractPayload {
+      amount,
+      receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.publicMint".to_string()),
+      address: contract_address,
+      message: OwnedParameter::from_serial(&params).expect("Mint params"),
+    },
+  )
+}
+
+// Helper function that calls an entrypoint of the contract.
+#[allow(unused)]
+pub fn update_contract<P: Serial>(
+  chain: &mut Chain,
+  contract_address: ContractAddress,
+  invoker: AccountAddress,
+  entrypoint: &str,
+  params: &P,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+  chain.contract_update(
+    SIGNER,
+    invoker,
+    Address::Account(invoker),
+    Energy::from(10000),
+    UpdateContractPayload {
+      amount: Amount::zero(),
+      receive_name: OwnedReceiveName::new_unchecked(format!("ciphers_nft.{}", entrypoint)),
+      address: contract_address,
+      message: OwnedParameter::from_serial(params).expect("Params"),
+    },
+  )
+}
+
+// Helper function that queues an admin action executable right away and
+// executes it, as the owner of a contract without an admin timelock.
+#[allow(unused)]
+pub fn queue_and_execute(
+  chain: &mut Chain,
+  contract_address: ContractAddress,
+  action: AdminAction,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+  let params = QueueAdminActionParams {
+    action,
+    execute_after: chain.block_time().timestamp_millis(),
+  };
+  let id: u32 = update_contract(chain, contract_address, OWNER, "queueAdminAction", &params)
+    .expect("Queue admin action failed")
+    .parse_return_value()
+    .expect("Admin action ID");
+  update_contract(chain, contract_address, OWNER, "executeAdminAction", &id)
+}
+
+// Helper function that invokes a view entrypoint and parses its return value.
+#[allow(unused)]
+pub fn invoke_view<P: Serial, R: Deserial>(
+  chain: &Chain,
+  contract_address: ContractAddress,
+  entrypoint: &str,
+  params: &P,
+) -> R {
+  chain
+    .contract_invoke(
+      OWNER,
+      OWNER_ADDR,
+      Energy::from(10000),
+      UpdateContractPayload {
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked(format!("ciphers_nft.{}", entrypoint)),
+        address: contract_address,
+        message: OwnedParameter::from_serial(params).expect("Params"),
+      },
+    )
+    .expect("Invoke view")
+    .parse_return_value()
+    .expect("Return value")
+}
+
+// Helper function that transfers a token on behalf of `from`.
+#[allow(unused)]
+pub fn transfer_token(
+  chain: &mut Chain,
+  contract_address: ContractAddress,
+  invoker: AccountAddress,
+  from: Address,
+  to: Receiver,
+  token_id: ContractTokenId,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+  let transfer_params = TransferParams::from(vec![concordium_cis2::Transfer {
+    from,
+    to,
+    token_id,
+    amount: TokenAmountU8(1),
+    data: AdditionalData::empty(),
+  }]);
+  update_contract(
+    chain,
+    contract_address,
+    invoker,
+    "transfer",
+    &transfer_params,
+  )
+}
+
+/// Assert that a call used at most `max` energy, to catch energy regressions.
+/// The energy used is printed with `--nocapture`.
+#[allow(unused)]
+pub fn assert_energy_below(call: &str, energy_used: Energy, max: u64) {
+  println!("{} used {} energy", call, energy_used.energy);
+  assert!(
+    energy_used.energy <= max,
+    "{} used {} energy, more than {}",
+    call,
+    energy_used.energy,
+    max
   );
+}
 
-  update_result
+/// Init parameters used by `initialize_chain_and_contract`.
+pub fn default_init_params() -> InitParams {
+  InitParams {
+    name: NAME.to_string(),
+    symbol: SYMBOL.to_string(),
+    contract_uri: get_contract_metadata(),
+    minter: MINTER,
+    mint_start: MINT_START,
+    mint_deadline: MINT_DEADLINE,
+    max_total_supply: MAX_TOTAL_SUPPLY,
+    mint_price: MINT_PRICE,
+    emit_events: true,
+    deadline_grace_ms: 0,
+    royalty_basis_points: ROYALTY_BASIS_POINTS,
+    royalty_recipient: OWNER,
+    require_unique_uri: false,
+    operators_enabled: true,
+    max_operators_per_owner: 0,
+    minter_locked: false,
+    treasury: None,
+    treasury_bps: 0,
+    admin_timelock_ms: 0,
+    refund_overpayment: true,
+    trading_after_mint: false,
+    mint_proof_difficulty: 0,
+    mint_cooldown_ms: 0,
+    max_mints_per_account: 0,
+    burn_requires_admin: false,
+    claim_deadline: 0,
+    resale_lock_ms: 0,
+    reject_operator_self_transfers: false,
+    enforce_receiver_whitelist: false,
+    placeholder_uri: None,
+    permit_replay_window_ms: 0,
+    initial_implementors: Vec::new(),
+  }
 }
 
 /// Setup chain and contract.
 pub fn initialize_chain_and_contract(timestamp: u64) -> (Chain, ContractAddress) {
+  initialize_chain_and_contract_with_params(timestamp, default_init_params())
+}
+
+/// Setup chain and contract with the given init parameters.
+pub fn initialize_chain_and_contract_with_params(
+  timestamp: u64,
+  params: InitParams,
+) -> (Chain, ContractAddress) {
+  let mut chain = initialize_chain(timestamp);
+  let init = initialize_contract(&mut chain, &params);
+
+  for event in init.events {
+    let contract_event = event.parse::<ContractEvent>().expect("Deserialize event");
+    // println!("Event: {:?}", contract_event);
+
+    assert_eq!(
+      contract_event,
+      ContractEvent::Deploy(DeployEvent {
+        name: params.name.clone(),
+        symbol: params.symbol.clone(),
+        contract_uri: params.contract_uri.clone(),
+        minter: params.minter,
+        mint_start: params.mint_start,
+        mint_deadline: params.mint_deadline,
+        max_total_supply: params.max_total_supply,
+        contract_version: CONTRACT_VERSION,
+      })
+    );
+  }
+
+  (chain, init.contract_address)
+}
+
+/// Setup chain with the test accounts.
+pub fn initialize_chain(timestamp: u64) -> Chain {
   let mut chain = Chain::builder()
     .block_time(Timestamp::from_timestamp_millis(timestamp))
     .build()
@@ -53,56 +272,74 @@ pub fn initialize_chain_and_contract(timestamp: u64) -> (Chain, ContractAddress)
   chain.create_account(Account::new(USER3, ACC_INITIAL_BALANCE));
   chain.create_account(Account::new(NEW_MINTER, ACC_INITIAL_BALANCE));
 
+  chain
+}
+
+/// Deploy the module and initialize the contract with the given init
+/// parameters.
+pub fn initialize_contract(chain: &mut Chain, params: &InitParams) -> ContractInitSuccess {
+  try_initialize_contract(chain, params).expect("Initialize contract")
+}
+
+/// Deploy the module and try to initialize the contract with the given init
+/// parameters.
+pub fn try_initialize_contract(
+  chain: &mut Chain,
+  params: &InitParams,
+) -> Result<ContractInitSuccess, ContractInitError> {
   // Load and deploy the module.
   let module = module_load_v1("ciphers_nft.wasm.v1").expect("Module exists");
   let deployment = chain
     .module_deploy_v1(SIGNER, OWNER, module)
     .expect("Deploy valid module");
 
-  let params = InitParams {
-    name: NAME.to_string(),
-    symbol: SYMBOL.to_string(),
-    contract_uri: get_contract_metadata(),
-    minter: MINTER,
-    mint_start: MINT_START,
-    mint_deadline: MINT_DEADLINE,
-    max_total_supply: MAX_TOTAL_SUPPLY,
-  };
-
   // Initialize the auction contract.
-  let init = chain
-    .contract_init(
-      SIGNER,
-      OWNER,
-      Energy::from(10000),
-      InitContractPayload {
-        amount: Amount::zero(),
-        mod_ref: deployment.module_reference,
-        init_name: OwnedContractName::new_unchecked("init_ciphers_nft".to_string()),
-        param: OwnedParameter::from_serial(&params).expect("Init params"),
-      },
-    )
-    .expect("Initialize contract");
+  chain.contract_init(
+    SIGNER,
+    OWNER,
+    Energy::from(10000),
+    InitContractPayload {
+      amount: Amount::zero(),
+      mod_ref: deployment.module_reference,
+      init_name: OwnedContractName::new_unchecked("init_ciphers_nft".to_string()),
+      param: OwnedParameter::from_serial(params).expect("Init params"),
+    },
+  )
+}
 
-  for event in init.events {
-    let contract_event = event.parse::<ContractEvent>().expect("Deserialize event");
-    // println!("Event: {:?}", contract_event);
+/// Deploy and initialize the `receiver` contract, which records the calls it
+/// receives.
+#[allow(unused)]
+pub fn initialize_receiver(chain: &mut Chain) -> ContractAddress {
+  initialize_receivers(chain, 1)[0]
+}
 
-    assert_eq!(
-      contract_event,
-      ContractEvent::Deploy(DeployEvent {
-        name: NAME.to_string(),
-        symbol: SYMBOL.to_string(),
-        contract_uri: get_contract_metadata(),
-        minter: MINTER,
-        mint_start: MINT_START,
-        mint_deadline: MINT_DEADLINE,
-        max_total_supply: MAX_TOTAL_SUPPLY,
-      })
-    );
-  }
+/// Deploy the `receiver` module once and initialize `count` instances of it.
+#[allow(unused)]
+pub fn initialize_receivers(chain: &mut Chain, count: usize) -> Vec<ContractAddress> {
+  let module = module_load_v1("../receiver/receiver.wasm.v1").expect("Module exists");
+  let deployment = chain
+    .module_deploy_v1(SIGNER, OWNER, module)
+    .expect("Deploy valid module");
 
-  (chain, init.contract_address)
+  (0..count)
+    .map(|_| {
+      chain
+        .contract_init(
+          SIGNER,
+          OWNER,
+          Energy::from(10000),
+          InitContractPayload {
+            amount: Amount::zero(),
+            mod_ref: deployment.module_reference,
+            init_name: OwnedContractName::new_unchecked("init_receiver".to_string()),
+            param: OwnedParameter::empty(),
+          },
+        )
+        .expect("Initialize receiver")
+        .contract_address
+    })
+    .collect()
 }
 
 pub fn get_view_state(chain: &Chain, contract_address: ContractAddress) -> ViewState {
@@ -123,6 +360,7 @@ pub fn get_view_state(chain: &Chain, contract_address: ContractAddress) -> ViewS
   invoke.parse_return_value().expect("ViewState return value")
 }
 
+#[allow(unused)]
 pub fn get_view_address(
   chain: &Chain,
   contract_address: ContractAddress,
@@ -168,14 +406,19 @@ pub fn get_view_settings(chain: &Chain, contract_address: ContractAddress) -> Vi
 }
 
 #[allow(unused)]
-pub fn c_mint_params(token: u32) -> MintParams {
-  MintParams {
-    owners: vec![USER_ADDR],
-    tokens: vec![TokenIdU32(token)],
-    token_uris: vec!["ipfs://test".to_string()],
+pub fn c_mint_to_params(token: u32) -> MintToParams {
+  MintToParams {
+    owner: USER_ADDR,
+    token_id: TokenIdU32(token),
+    token_uri: "ipfs://test".to_string(),
   }
 }
 
+#[allow(unused)]
+pub fn c_mint_params(token: u32) -> MintParams {
+  c_mint_to_params(token).into()
+}
+
 pub fn get_contract_metadata() -> MetadataUrl {
   MetadataUrl {
     url: "ipfs://contractURI".to_string(),
@@ -4,11 +4,25 @@ mod helpers;
 use helpers::functions::*;
 use helpers::init::*;
 
-use ciphers_nft::error::ContractError;
-use ciphers_nft::{contract_view::*, mint::*};
+use ciphers_nft::error::{ContractError, CustomContractError};
+use ciphers_nft::init::InitParams;
+use ciphers_nft::{
+  cis2::{
+    ContractTokenAmount, ContractTokenId, SetImplementorsParams, TransferMode, TransferReport,
+    TransferWithModeParams, UpdateOperatorWithExpiry, UpdateOperatorWithExpiryParams,
+    MAX_TRANSFER_BATCH,
+  },
+  contract_view::*,
+  mint::*,
+  permit::{PermitMessage, PermitParam},
+  setters::SetAllowedReceiver,
+  transfer_and_call::TransferAndCallParams,
+};
+use concordium_base::id::types::AccountKeys;
 use concordium_cis2::*;
 use concordium_smart_contract_testing::*;
 use concordium_std::concordium_test;
+use sha2::{Digest, Sha256};
 
 /// Test regular transfer where sender is the owner.
 #[concordium_test]
@@ -19,6 +33,8 @@ fn test_account_transfer() {
     owners: vec![USER_ADDR, USER_ADDR],
     tokens: vec![TOKEN_0, TOKEN_1],
     token_uris: vec!["ipfs://test".to_string(), "ipfs://test".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
   };
 
   mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
@@ -46,6 +62,7 @@ fn test_account_transfer() {
       },
     )
     .expect("Transfer tokens");
+  assert_energy_below("transfer", update.energy_used, MAX_TRANSFER_ENERGY);
 
   // Check that User2 now has `TOKEN_0` and that User still has `TOKEN_1`.
   let rv: ViewState = get_view_state(&chain, contract_address);
@@ -95,6 +112,8 @@ fn test_operator_can_transfer() {
     owners: vec![USER_ADDR, USER_ADDR],
     tokens: vec![TOKEN_0, TOKEN_1],
     token_uris: vec!["ipfs://test".to_string(), "ipfs://test".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
   };
 
   mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
@@ -190,6 +209,8 @@ fn test_unauthorized_sender() {
     owners: vec![USER_ADDR, USER_ADDR],
     tokens: vec![TOKEN_0, TOKEN_1],
     token_uris: vec!["ipfs://test".to_string(), "ipfs://test".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
   };
 
   mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
@@ -226,3 +247,893 @@ fn test_unauthorized_sender() {
     .expect("ContractError return value");
   assert_eq!(rv, ContractError::Unauthorized);
 }
+
+/// Test that an admin-gated call by another account rejects with `NotOwner`,
+/// while a transfer by an unauthorized account rejects with `Unauthorized`.
+#[concordium_test]
+fn test_not_owner_vs_unauthorized() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(100);
+  mint_to_address(&mut chain, contract_address, c_mint_params(0), None, None).expect("Mint failed");
+
+  let params = SetImplementorsParams {
+    id: StandardIdentifierOwned::new_unchecked("CIS-3".to_string()),
+    implementors: vec![],
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "setImplementors",
+    &params,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::NotOwner));
+
+  let update = transfer_token(
+    &mut chain,
+    contract_address,
+    USER2,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(0),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::Unauthorized);
+}
+
+/// Test that an owner cannot add more operators than the configured maximum,
+/// and that removing an operator frees room for a new one.
+#[concordium_test]
+fn test_operator_cap() {
+  let params = InitParams {
+    max_operators_per_owner: 2,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) = initialize_chain_and_contract_with_params(100, params);
+
+  let update_operator = |chain: &mut Chain, update: OperatorUpdate, operator: Address| {
+    let params = UpdateOperatorParams(vec![UpdateOperator { update, operator }]);
+    update_contract(chain, contract_address, USER, "updateOperator", &params)
+  };
+
+  update_operator(&mut chain, OperatorUpdate::Add, USER2_ADDR).expect("Add operator");
+  update_operator(&mut chain, OperatorUpdate::Add, USER3_ADDR).expect("Add operator");
+  // Adding an existing operator does not count against the cap.
+  update_operator(&mut chain, OperatorUpdate::Add, USER2_ADDR).expect("Add operator");
+
+  let update =
+    update_operator(&mut chain, OperatorUpdate::Add, MINTER_ADDR).expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(
+    rv,
+    Cis2Error::Custom(CustomContractError::OperatorCapExceeded)
+  );
+
+  update_operator(&mut chain, OperatorUpdate::Remove, USER2_ADDR).expect("Remove operator");
+  update_operator(&mut chain, OperatorUpdate::Add, MINTER_ADDR).expect("Add operator");
+
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(
+    view_state.state,
+    vec![(
+      USER_ADDR,
+      ViewAddressState {
+        owned_tokens: Vec::new(),
+        operators: vec![MINTER_ADDR, USER3_ADDR],
+      }
+    )]
+  );
+}
+
+/// Test that with operators disabled no operator can be added and only the
+/// owner can transfer.
+#[concordium_test]
+fn test_operators_disabled() {
+  let params = InitParams {
+    operators_enabled: false,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) = initialize_chain_and_contract_with_params(100, params);
+  mint_to_address(&mut chain, contract_address, c_mint_params(0), None, None).expect("Mint failed");
+
+  let params = UpdateOperatorParams(vec![UpdateOperator {
+    update: OperatorUpdate::Add,
+    operator: USER2_ADDR,
+  }]);
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "updateOperator",
+    &params,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(
+    rv,
+    Cis2Error::Custom(CustomContractError::OperatorsDisabled)
+  );
+
+  let update = transfer_token(
+    &mut chain,
+    contract_address,
+    USER2,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(0),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::Unauthorized);
+
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(0),
+  )
+  .expect("Owner transfer failed");
+}
+
+/// Test that with `trading_after_mint` transfers are rejected until all tokens
+/// are minted.
+#[concordium_test]
+fn test_trading_opens_after_sell_out() {
+  let params = InitParams {
+    max_total_supply: 2,
+    trading_after_mint: true,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) = initialize_chain_and_contract_with_params(100, params);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  let update = transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TOKEN_0,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::TradingNotOpen));
+
+  mint_to_address(&mut chain, contract_address, c_mint_params(42), None, None)
+    .expect("Mint failed");
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TOKEN_0,
+  )
+  .expect("Transfer failed");
+}
+
+/// Test that with `trading_after_mint` transfers are allowed once the mint
+/// deadline is reached, even if not all tokens are minted.
+#[concordium_test]
+fn test_trading_opens_after_deadline() {
+  let params = InitParams {
+    trading_after_mint: true,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) = initialize_chain_and_contract_with_params(100, params);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  chain
+    .tick_block_time(Duration::from_millis(MINT_DEADLINE - 100))
+    .expect("Advance block time");
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TOKEN_0,
+  )
+  .expect("Transfer failed");
+}
+
+/// Test that `transferAndCall` transfers the token to the receiving contract
+/// and invokes the chosen entrypoint with the transfer and the data.
+#[concordium_test]
+fn test_transfer_and_call() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(100);
+  let receiver = initialize_receiver(&mut chain);
+  mint_to_address(&mut chain, contract_address, c_mint_params(0), None, None).expect("Mint failed");
+
+  let params = TransferAndCallParams {
+    token_id: TokenIdU32(0),
+    from: USER_ADDR,
+    to: receiver,
+    entrypoint: OwnedEntrypointName::new_unchecked("callback".to_string()),
+    data: AdditionalData::from(vec![1, 2, 3]),
+  };
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "transferAndCall",
+    &params,
+  )
+  .expect("Transfer and call failed");
+
+  // The receiver records the sender and the parameter of the callback.
+  let (received, last_callback): (u32, Option<(Address, Vec<u8>)>) = chain
+    .contract_invoke(
+      OWNER,
+      OWNER_ADDR,
+      Energy::from(10000),
+      UpdateContractPayload {
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("receiver.view".to_string()),
+        address: receiver,
+        message: OwnedParameter::empty(),
+      },
+    )
+    .expect("Invoke view")
+    .parse_return_value()
+    .expect("Receiver view return value");
+  assert_eq!(received, 0);
+  let expected = OnReceivingCis2Params {
+    token_id: TokenIdU32(0),
+    amount: TokenAmountU8(1),
+    from: USER_ADDR,
+    data: AdditionalData::from(vec![1, 2, 3]),
+  };
+  assert_eq!(
+    last_callback,
+    Some((Address::Contract(contract_address), to_bytes(&expected)))
+  );
+
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(
+    view_state.state,
+    vec![
+      (
+        USER_ADDR,
+        ViewAddressState {
+          owned_tokens: Vec::new(),
+          operators: Vec::new(),
+        }
+      ),
+      (
+        Address::Contract(receiver),
+        ViewAddressState {
+          owned_tokens: vec![TokenIdU32(0)],
+          operators: Vec::new(),
+        }
+      ),
+    ]
+  );
+}
+
+/// Test that the data of `transferAndCall` cannot forge the token, amount or
+/// previous owner the callee receives, and that `onReceivingCis2` cannot be
+/// chosen as the entrypoint.
+#[concordium_test]
+fn test_transfer_and_call_parameter_not_forgeable() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(100);
+  let receiver = initialize_receiver(&mut chain);
+  mint_to_address(&mut chain, contract_address, c_mint_params(0), None, None).expect("Mint failed");
+
+  let params = TransferAndCallParams {
+    token_id: TokenIdU32(0),
+    from: USER_ADDR,
+    to: receiver,
+    entrypoint: OwnedEntrypointName::new_unchecked("onReceivingCis2".to_string()),
+    data: AdditionalData::empty(),
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "transferAndCall",
+    &params,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::ReservedEntrypoint.into());
+
+  // The data claims the transfer of another token by another owner.
+  let forged = to_bytes(&OnReceivingCis2Params {
+    token_id: TokenIdU32(7),
+    amount: TokenAmountU8(1),
+    from: USER2_ADDR,
+    data: AdditionalData::empty(),
+  });
+  let params = TransferAndCallParams {
+    token_id: TokenIdU32(0),
+    from: USER_ADDR,
+    to: receiver,
+    entrypoint: OwnedEntrypointName::new_unchecked("callback".to_string()),
+    data: AdditionalData::from(forged.clone()),
+  };
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "transferAndCall",
+    &params,
+  )
+  .expect("Transfer and call failed");
+
+  let (_received, last_callback): (u32, Option<(Address, Vec<u8>)>) = chain
+    .contract_invoke(
+      OWNER,
+      OWNER_ADDR,
+      Energy::from(10000),
+      UpdateContractPayload {
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("receiver.view".to_string()),
+        address: receiver,
+        message: OwnedParameter::empty(),
+      },
+    )
+    .expect("Invoke view")
+    .parse_return_value()
+    .expect("Receiver view return value");
+  let (_sender, parameter) = last_callback.expect("Callback invoked");
+  let received: OnReceivingCis2Params<ContractTokenId, ContractTokenAmount> =
+    from_bytes(&parameter).expect("OnReceivingCis2Params");
+  assert_eq!(received.token_id, TokenIdU32(0));
+  assert_eq!(received.amount, TokenAmountU8(1));
+  assert_eq!(received.from, USER_ADDR);
+  assert_eq!(received.data.as_ref(), &forged[..]);
+}
+
+/// Test that a batch with a failing transfer is rejected in `Atomic` mode,
+/// while in `BestEffort` mode the other transfers are executed and the
+/// failure is reported.
+#[concordium_test]
+fn test_transfer_with_mode() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(100);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER2_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2), TokenIdU32(3)],
+    token_uris: vec!["ipfs://test".to_string(); 3],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  // USER is not allowed to transfer the token of USER2.
+  let transfers: Vec<concordium_cis2::Transfer<ContractTokenId, ContractTokenAmount>> = [
+    (TokenIdU32(1), USER_ADDR),
+    (TokenIdU32(2), USER2_ADDR),
+    (TokenIdU32(3), USER_ADDR),
+  ]
+  .into_iter()
+  .map(|(token_id, from)| concordium_cis2::Transfer {
+    from,
+    to: Receiver::Account(USER3),
+    token_id,
+    amount: TokenAmountU8(1),
+    data: AdditionalData::empty(),
+  })
+  .collect();
+
+  let params = TransferWithModeParams {
+    mode: TransferMode::Atomic,
+    transfers: transfers.clone(),
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "transferWithMode",
+    &params,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::Unauthorized);
+  let view_state = get_view_state(&chain, contract_address);
+  assert!(view_state
+    .state
+    .iter()
+    .all(|(address, _)| *address != USER3_ADDR));
+
+  let params = TransferWithModeParams {
+    mode: TransferMode::BestEffort,
+    transfers,
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "transferWithMode",
+    &params,
+  )
+  .expect("Transfer failed");
+  let report: TransferReport = update.parse_return_value().expect("TransferReport");
+  assert_eq!(report, [None, Some(ContractError::Unauthorized), None]);
+
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(
+    view_state
+      .state
+      .into_iter()
+      .map(|(address, a_state)| (address, a_state.owned_tokens))
+      .collect::<Vec<_>>(),
+    [
+      (USER_ADDR, vec![]),
+      (USER2_ADDR, vec![TokenIdU32(2)]),
+      (USER3_ADDR, vec![TokenIdU32(1), TokenIdU32(3)]),
+    ]
+  );
+}
+
+/// Test that a batch above `MAX_TRANSFER_BATCH` is rejected before any
+/// transfer is executed.
+#[concordium_test]
+fn test_transfer_batch_too_large() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(100);
+  mint_to_address(&mut chain, contract_address, c_mint_params(1), None, None).expect("Mint failed");
+
+  // The first transfer is valid, the others would fail on the balance.
+  let transfers = vec![
+    concordium_cis2::Transfer {
+      from: USER_ADDR,
+      to: Receiver::Account(USER2),
+      token_id: TokenIdU32(1),
+      amount: TokenAmountU8(1),
+      data: AdditionalData::empty(),
+    };
+    40
+  ];
+  assert!(transfers.len() > MAX_TRANSFER_BATCH);
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "transfer",
+    &TransferParams::from(transfers),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::BatchTooLarge.into());
+}
+
+/// Test that with the receiver whitelist enforced, tokens can be transferred
+/// to a whitelisted contract and to accounts, but not to other contracts.
+#[concordium_test]
+fn test_receiver_whitelist() {
+  let params = InitParams {
+    enforce_receiver_whitelist: true,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) = initialize_chain_and_contract_with_params(100, params);
+  let receivers = initialize_receivers(&mut chain, 2);
+  let (allowed, not_allowed) = (receivers[0], receivers[1]);
+  for token in 1..=3 {
+    mint_to_address(
+      &mut chain,
+      contract_address,
+      c_mint_params(token),
+      None,
+      None,
+    )
+    .expect("Mint failed");
+  }
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setAllowedReceiver",
+    &SetAllowedReceiver {
+      address: allowed,
+      allowed: true,
+    },
+  )
+  .expect("Set allowed receiver failed");
+
+  let hook = || OwnedEntrypointName::new_unchecked("onReceivingCis2".to_string());
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Contract(allowed, hook()),
+    TokenIdU32(1),
+  )
+  .expect("Transfer failed");
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(2),
+  )
+  .expect("Transfer failed");
+
+  let update = transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Contract(not_allowed, hook()),
+    TokenIdU32(3),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::ReceiverNotAllowed.into());
+
+  let params = TransferAndCallParams {
+    token_id: TokenIdU32(3),
+    from: USER_ADDR,
+    to: not_allowed,
+    entrypoint: OwnedEntrypointName::new_unchecked("callback".to_string()),
+    data: AdditionalData::empty(),
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "transferAndCall",
+    &params,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::ReceiverNotAllowed.into());
+
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(
+    view_state
+      .state
+      .into_iter()
+      .map(|(address, a_state)| (address, a_state.owned_tokens))
+      .collect::<Vec<_>>(),
+    [
+      (USER_ADDR, vec![TokenIdU32(3)]),
+      (USER2_ADDR, vec![TokenIdU32(2)]),
+      (Address::Contract(allowed), vec![TokenIdU32(1)]),
+    ]
+  );
+}
+
+/// Test that an operator with an expiry can transfer until the block time
+/// passes the expiry, after which it is rejected and not reported by
+/// `operatorOf`.
+#[concordium_test]
+fn test_operator_expiry() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(100);
+  for token in 0..2 {
+    mint_to_address(
+      &mut chain,
+      contract_address,
+      c_mint_params(token),
+      None,
+      None,
+    )
+    .expect("Mint failed");
+  }
+  let params = UpdateOperatorWithExpiryParams(vec![UpdateOperatorWithExpiry {
+    update: OperatorUpdate::Add,
+    operator: USER2_ADDR,
+    expiry: Some(Timestamp::from_timestamp_millis(200)),
+  }]);
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "updateOperatorWithExpiry",
+    &params,
+  )
+  .expect("Update operators failed");
+
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER2,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(0),
+  )
+  .expect("Transfer failed");
+
+  chain
+    .tick_block_time(Duration::from_millis(101))
+    .expect("Tick block time");
+  let update = transfer_token(
+    &mut chain,
+    contract_address,
+    USER2,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(1),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::Unauthorized);
+
+  let query = OperatorOfQueryParams {
+    queries: vec![OperatorOfQuery {
+      owner: USER_ADDR,
+      address: USER2_ADDR,
+    }],
+  };
+  let rv: OperatorOfQueryResponse = invoke_view(&chain, contract_address, "operatorOf", &query);
+  assert_eq!(rv, OperatorOfQueryResponse(vec![false]));
+}
+
+/// Test that an expired operator cannot transfer and is removed by
+/// `sweepExpiredOperators`, while operators without an expiry are kept.
+#[concordium_test]
+fn test_sweep_expired_operators() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(100);
+  mint_to_address(&mut chain, contract_address, c_mint_params(0), None, None).expect("Mint failed");
+
+  let params = UpdateOperatorWithExpiryParams(vec![
+    UpdateOperatorWithExpiry {
+      update: OperatorUpdate::Add,
+      operator: USER2_ADDR,
+      expiry: Some(Timestamp::from_timestamp_millis(50)),
+    },
+    UpdateOperatorWithExpiry {
+      update: OperatorUpdate::Add,
+      operator: USER3_ADDR,
+      expiry: None,
+    },
+  ]);
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "updateOperatorWithExpiry",
+    &params,
+  )
+  .expect("Update operators failed");
+
+  let update = transfer_token(
+    &mut chain,
+    contract_address,
+    USER2,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(0),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::Unauthorized);
+
+  // Anyone can sweep the expired operators.
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER3,
+    "sweepExpiredOperators",
+    &USER_ADDR,
+  )
+  .expect("Sweep failed");
+  let removed: u32 = update.parse_return_value().expect("Sweep return value");
+  assert_eq!(removed, 1);
+
+  let view_state = get_view_state(&chain, contract_address);
+  let (_, user_state) = view_state
+    .state
+    .iter()
+    .find(|(address, _)| *address == USER_ADDR)
+    .expect("User state");
+  assert_eq!(user_state.operators, [USER3_ADDR]);
+}
+
+/// Test that an operator transferring a token to its owner leaves the state
+/// unchanged and logs the transfer, or rejects with
+/// `reject_operator_self_transfers`.
+#[concordium_test]
+fn test_operator_self_transfer() {
+  for reject in [false, true] {
+    let params = InitParams {
+      reject_operator_self_transfers: reject,
+      ..default_init_params()
+    };
+    let (mut chain, contract_address) = initialize_chain_and_contract_with_params(100, params);
+    mint_to_address(&mut chain, contract_address, c_mint_params(0), None, None)
+      .expect("Mint failed");
+    let operator = UpdateOperatorParams(vec![UpdateOperator {
+      update: OperatorUpdate::Add,
+      operator: USER2_ADDR,
+    }]);
+    update_contract(
+      &mut chain,
+      contract_address,
+      USER,
+      "updateOperator",
+      &operator,
+    )
+    .expect("Update operator failed");
+
+    let result = transfer_token(
+      &mut chain,
+      contract_address,
+      USER2,
+      USER_ADDR,
+      Receiver::Account(USER),
+      TokenIdU32(0),
+    );
+    if reject {
+      let rv: ContractError = result
+        .expect_err("Call didnt fail")
+        .parse_return_value()
+        .expect("ContractError return value");
+      assert_eq!(rv, CustomContractError::OperatorSelfTransfer.into());
+      continue;
+    }
+
+    let update = result.expect("Self transfer failed");
+    let events = update
+      .events()
+      .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+      .collect::<Vec<Cis2Event<ContractTokenId, ContractTokenAmount>>>();
+    assert_eq!(
+      events,
+      [Cis2Event::Transfer(TransferEvent {
+        token_id: TokenIdU32(0),
+        amount: TokenAmountU8(1),
+        from: USER_ADDR,
+        to: USER_ADDR,
+      })]
+    );
+    let view_state = get_view_state(&chain, contract_address);
+    assert_eq!(view_state.state[0].0, USER_ADDR);
+    assert_eq!(view_state.state[0].1.owned_tokens, [TokenIdU32(0)]);
+  }
+}
+
+/// Test that implementors given at init are supported without calling
+/// `setImplementors`.
+#[concordium_test]
+fn test_initial_implementors() {
+  let implementor = ContractAddress::new(42, 0);
+  let params = InitParams {
+    initial_implementors: vec![SetImplementorsParams {
+      id: StandardIdentifierOwned::new_unchecked("CUSTOM_STANDARD".to_string()),
+      implementors: vec![implementor],
+    }],
+    ..default_init_params()
+  };
+  let (chain, contract_address) = initialize_chain_and_contract_with_params(100, params);
+
+  let params = SupportsQueryParams {
+    queries: vec![StandardIdentifierOwned::new_unchecked(
+      "CUSTOM_STANDARD".to_string(),
+    )],
+  };
+  let rv: SupportsQueryResponse = invoke_view(&chain, contract_address, "supports", &params);
+  assert!(matches!(
+    &rv.results[..],
+    [SupportResult::SupportBy(implementors)] if implementors == &[implementor]
+  ));
+}
+
+/// Sign a `permit` message transferring `token_id` from `signer` to `USER2`.
+fn sign_permit(
+  keys: &AccountKeys,
+  signer: AccountAddress,
+  contract_address: ContractAddress,
+  token_id: ContractTokenId,
+  expiry: u64,
+) -> PermitParam {
+  let transfer = concordium_cis2::Transfer {
+    token_id,
+    amount: ContractTokenAmount::from(1),
+    from: Address::Account(signer),
+    to: Receiver::Account(USER2),
+    data: AdditionalData::empty(),
+  };
+  let message = PermitMessage {
+    contract_address,
+    nonce: 0,
+    timestamp: Timestamp::from_timestamp_millis(expiry),
+    entry_point: OwnedEntrypointName::new_unchecked("transfer".to_string()),
+    payload: to_bytes(&TransferParams(vec![transfer])),
+  };
+  let mut signed = signer.0.to_vec();
+  signed.extend_from_slice(&[0u8; 8]);
+  signed.extend(to_bytes(&message));
+  let hash = Sha256::digest(&signed);
+  let signature = AccountSignatures::from(keys.sign_data(&hash));
+  PermitParam {
+    signature: from_bytes(&to_bytes(&signature)).expect("Signatures"),
+    signer,
+    message,
+  }
+}
+
+/// Test that a permit cannot be replayed within the replay window, and that
+/// expired permits can be signed again with a fresh timestamp.
+#[concordium_test]
+fn test_permit_replay_window() {
+  let params = InitParams {
+    permit_replay_window_ms: 1000,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) = initialize_chain_and_contract_with_params(100, params);
+  let keys = AccountKeys::singleton(&mut rand::thread_rng());
+  let signer = AccountAddress([8; 32]);
+  chain.create_account(Account::new_with_keys(
+    signer,
+    AccountBalance::new(ACC_INITIAL_BALANCE, Amount::zero(), Amount::zero()).unwrap(),
+    (&keys).into(),
+  ));
+  for token in 1..=2 {
+    let params = MintToParams {
+      owner: Address::Account(signer),
+      ..c_mint_to_params(token)
+    };
+    mint_to_address(&mut chain, contract_address, params.into(), None, None).expect("Mint failed");
+  }
+
+  let permit = sign_permit(&keys, signer, contract_address, TokenIdU32(1), 600);
+  update_contract(&mut chain, contract_address, USER, "permit", &permit).expect("Permit failed");
+  let update = update_contract(&mut chain, contract_address, USER, "permit", &permit)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::PermitReplayed.into());
+
+  // Permits must expire within the window.
+  let too_far = sign_permit(&keys, signer, contract_address, TokenIdU32(2), 1200);
+  let update = update_contract(&mut chain, contract_address, USER, "permit", &too_far)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::PermitOutsideWindow.into());
+
+  chain
+    .tick_block_time(Duration::from_millis(1000))
+    .expect("Tick block time");
+  let update = update_contract(&mut chain, contract_address, USER, "permit", &permit)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::PermitExpired.into());
+
+  // The same nonce is accepted again with a fresh timestamp.
+  let permit = sign_permit(&keys, signer, contract_address, TokenIdU32(2), 1600);
+  update_contract(&mut chain, contract_address, USER, "permit", &permit).expect("Permit failed");
+  let view_state = get_view_state(&chain, contract_address);
+  let (_, user2_state) = view_state
+    .state
+    .iter()
+    .find(|(address, _)| *address == USER2_ADDR)
+    .expect("USER2 state");
+  assert_eq!(user2_state.owned_tokens, vec![TokenIdU32(1), TokenIdU32(2)]);
+}
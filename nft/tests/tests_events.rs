@@ -0,0 +1,143 @@
+//! Tests for the events of the `ciphers_nft` contract.
+use ciphers_nft::{
+  cis2::{ContractTokenAmount, ContractTokenId},
+  events::*,
+};
+use concordium_cis2::{
+  MetadataUrl, BURN_EVENT_TAG, MINT_EVENT_TAG, TOKEN_METADATA_EVENT_TAG, TRANSFER_EVENT_TAG,
+};
+use concordium_std::{
+  from_bytes,
+  schema::{self, SchemaType},
+  to_bytes, AccountAddress, Address, Amount,
+};
+
+const OWNER: AccountAddress = AccountAddress([1u8; 32]);
+const OWNER_ADDR: Address = Address::Account(OWNER);
+const USER_ADDR: Address = Address::Account(AccountAddress([3u8; 32]));
+
+/// One event of every variant.
+fn sample_events() -> Vec<ContractEvent> {
+  let token_id = ContractTokenId::from(2);
+  let amount = ContractTokenAmount::from(1);
+  let metadata_url = MetadataUrl {
+    url: "ipfs://test".to_string(),
+    hash: None,
+  };
+  vec![
+    ContractEvent::Mint(MintEvent {
+      token_id,
+      amount,
+      owner: USER_ADDR,
+    }),
+    ContractEvent::TokenMetadata(TokenMetadataEvent {
+      token_id,
+      metadata_url: metadata_url.clone(),
+    }),
+    ContractEvent::Transfer(TransferEvent {
+      token_id,
+      amount,
+      from: USER_ADDR,
+      to: OWNER_ADDR,
+    }),
+    ContractEvent::Burn(BurnEvent {
+      token_id,
+      amount,
+      owner: USER_ADDR,
+    }),
+    ContractEvent::Minted(MintedEvent {
+      token_id,
+      mint_count: 1,
+      timestamp: 101,
+      token_uri: metadata_url.clone(),
+    }),
+    ContractEvent::Deploy(DeployEvent {
+      name: "name".to_string(),
+      symbol: "SYM".to_string(),
+      contract_uri: metadata_url,
+      minter: OWNER,
+      mint_start: 100,
+      mint_deadline: 1000,
+      max_total_supply: 10,
+      contract_version: CONTRACT_VERSION,
+    }),
+    ContractEvent::Withdraw(WithdrawEvent {
+      receiver: OWNER,
+      amount: Amount::from_ccd(3),
+    }),
+    ContractEvent::OwnershipTransferred(OwnershipTransferredEvent {
+      previous_owner: OWNER,
+      new_owner: AccountAddress([3u8; 32]),
+    }),
+  ]
+}
+
+/// Test that every event variant survives a serialization round trip.
+#[test]
+fn test_events_round_trip() {
+  for event in sample_events() {
+    let bytes = to_bytes(&event);
+    let parsed: ContractEvent = from_bytes(&bytes).expect("Deserialize event");
+    assert_eq!(parsed, event);
+  }
+}
+
+/// Test that a deploy event logged before the contract version was added
+/// still decodes, with the legacy version.
+#[test]
+fn test_legacy_deploy_event() {
+  let event = sample_events()
+    .into_iter()
+    .find(|event| matches!(event, ContractEvent::Deploy(_)))
+    .expect("Deploy event");
+  let ContractEvent::Deploy(deploy) = &event else {
+    unreachable!();
+  };
+  assert_eq!(deploy.contract_version, CONTRACT_VERSION);
+
+  let mut bytes = to_bytes(&event);
+  bytes.truncate(bytes.len() - 2);
+  let ContractEvent::Deploy(legacy) = from_bytes(&bytes).expect("Deserialize legacy event") else {
+    panic!("Legacy event is not a deploy event");
+  };
+  assert_eq!(legacy.contract_version, LEGACY_CONTRACT_VERSION);
+  assert_eq!(legacy.name, deploy.name);
+  assert_eq!(legacy.max_total_supply, deploy.max_total_supply);
+}
+
+/// Test that the event schema declared by the contract matches the fields of
+/// every event type.
+#[test]
+fn test_event_schema_matches_event_types() {
+  let schema::Type::TaggedEnum(variants) = ContractEvent::get_type() else {
+    panic!("Event schema is not a tagged enum");
+  };
+  let expected = [
+    (MINT_EVENT_TAG, "Mint", MintEvent::get_type()),
+    (
+      TOKEN_METADATA_EVENT_TAG,
+      "TokenMetadata",
+      TokenMetadataEvent::get_type(),
+    ),
+    (TRANSFER_EVENT_TAG, "Transfer", TransferEvent::get_type()),
+    (BURN_EVENT_TAG, "Burn", BurnEvent::get_type()),
+    (MINTED_EVENT_TAG, "Minted", MintedEvent::get_type()),
+    (DEPLOY_EVENT_TAG, "Deploy", DeployEvent::get_type()),
+    (WITHDRAW_EVENT_TAG, "Withdraw", WithdrawEvent::get_type()),
+    (
+      OWNERSHIP_TRANSFERRED_EVENT_TAG,
+      "OwnershipTransferred",
+      OwnershipTransferredEvent::get_type(),
+    ),
+  ];
+
+  assert_eq!(variants.len(), expected.len());
+  for (tag, name, event_type) in expected {
+    let schema::Type::Struct(fields) = event_type else {
+      panic!("{} is not a struct", name);
+    };
+    let (variant_name, variant_fields) = variants.get(&tag).expect("Variant for tag");
+    assert_eq!(variant_name, name);
+    assert_eq!(variant_fields, &fields, "Fields of {}", name);
+  }
+}
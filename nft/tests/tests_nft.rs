@@ -5,17 +5,25 @@ use helpers::functions::*;
 use helpers::init::*;
 
 use ciphers_nft::{
+  burn::*,
   cis2::*,
+  claim::*,
   contract_view::*,
   error::{ContractError, CustomContractError},
-  events::{ContractEvent, MintedEvent},
+  events::{ContractEvent, MintedEvent, OwnershipTransferredEvent, WithdrawEvent},
   getters::*,
+  init::InitParams,
   mint::*,
+  restrictions::*,
+  royalty::*,
   setters::*,
+  timelock::*,
+  vault::*,
 };
 use concordium_cis2::*;
 use concordium_smart_contract_testing::*;
 use concordium_std::concordium_test;
+use sha2::{Digest, Sha256};
 
 /// Test minting succeeds and the tokens are owned by the given address and
 /// the appropriate events are logged.
@@ -27,6 +35,22 @@ fn test_minting() {
 
   let update = mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None)
     .expect("Mint failed");
+  assert_energy_below("mint", update.energy_used, MAX_MINT_ENERGY);
+
+  let view = chain
+    .contract_invoke(
+      OWNER,
+      OWNER_ADDR,
+      Energy::from(10000),
+      UpdateContractPayload {
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.view".to_string()),
+        address: contract_address,
+        message: OwnedParameter::empty(),
+      },
+    )
+    .expect("Invoke view");
+  assert_energy_below("view", view.energy_used, MAX_VIEW_ENERGY);
 
   let rv: ViewState = get_view_state(&chain, contract_address);
   // println!("rv: {:?}", rv);
@@ -93,6 +117,8 @@ fn test_batch_minting() {
       "ipfs://test1".to_string(),
       "ipfs://test2".to_string(),
     ],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
   };
   mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
 
@@ -140,6 +166,216 @@ fn test_batch_minting() {
   assert_eq!(rv.counter, 3);
 }
 
+/// Test that the metadata hashes given at mint are returned by
+/// `tokenMetadata` and logged in the `Minted` events, and that they must match
+/// the number of tokens.
+#[concordium_test]
+fn test_token_hashes() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let hash = [7u8; 32];
+  let mint_params = MintParams {
+    token_hashes: vec![Some(hash)],
+    ..c_mint_params(1)
+  };
+  let update =
+    mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+  let minted_hashes: Vec<Option<[u8; 32]>> = update
+    .events()
+    .flat_map(|(_addr, events)| events)
+    .filter_map(|e| match e.parse().expect("Deserialize event") {
+      ContractEvent::Minted(event) => Some(event.token_uri.hash),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(minted_hashes, [Some(hash)]);
+
+  let mint_params = MintParams {
+    token_hashes: vec![None],
+    ..c_mint_params(2)
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+  let TokenMetadataQueryResponse(urls) = invoke_view(
+    &chain,
+    contract_address,
+    "tokenMetadata",
+    &ContractTokenMetadataQueryParams {
+      queries: vec![TokenIdU32(1), TokenIdU32(2)],
+    },
+  );
+  assert_eq!(
+    urls.iter().map(|url| url.hash).collect::<Vec<_>>(),
+    [Some(hash), None]
+  );
+
+  let mint_params = MintParams {
+    token_hashes: vec![Some(hash), Some(hash)],
+    ..c_mint_params(3)
+  };
+  let update = mint_to_address(&mut chain, contract_address, mint_params, None, None)
+    .expect_err("Mint with too many hashes didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::ArraysNotSameLength.into());
+}
+
+/// Test that a reserved token ID returns its reserved URI from
+/// `tokenMetadata` without being minted, until it is released.
+#[concordium_test]
+fn test_reserved_uri() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  mint_to_address(&mut chain, contract_address, c_mint_params(1), None, None).expect("Mint failed");
+  let reserve = |uri: Option<&str>| SetReservedUri {
+    token_id: TokenIdU32(0),
+    uri: uri.map(str::to_string),
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "setReservedUri",
+    &reserve(Some("ipfs://banner")),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::NotOwner.into());
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setReservedUri",
+    &reserve(Some("ipfs://banner")),
+  )
+  .expect("Set reserved URI failed");
+
+  let TokenMetadataQueryResponse(urls) = invoke_view(
+    &chain,
+    contract_address,
+    "tokenMetadata",
+    &ContractTokenMetadataQueryParams {
+      queries: vec![TokenIdU32(0), TokenIdU32(1)],
+    },
+  );
+  assert_eq!(
+    urls.into_iter().map(|url| url.url).collect::<Vec<_>>(),
+    ["ipfs://banner", "ipfs://test"]
+  );
+
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setReservedUri",
+    &reserve(None),
+  )
+  .expect("Release reserved URI failed");
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "tokenMetadata",
+    &ContractTokenMetadataQueryParams {
+      queries: vec![TokenIdU32(0)],
+    },
+  )
+  .expect_err("Query of released ID didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::InvalidTokenId);
+}
+
+/// Test that `revealBatch` reveals the tokens in mint order, that the other
+/// tokens return the placeholder URI, and that tokens minted later are not
+/// revealed in advance.
+#[concordium_test]
+fn test_reveal_batch() {
+  let params = InitParams {
+    placeholder_uri: Some("ipfs://hidden".to_string()),
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+  let mut mint_count_one_uri = None;
+  for token in 1..=4 {
+    let mint_params = MintParams {
+      token_uris: vec![format!("ipfs://{}", token)],
+      ..c_mint_params(token)
+    };
+    let update =
+      mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+    if token == 1 {
+      mint_count_one_uri = update
+        .events()
+        .flat_map(|(_addr, events)| events)
+        .find_map(|e| match e.parse().expect("Deserialize event") {
+          ContractEvent::Minted(event) => Some(event.token_uri.url),
+          _ => None,
+        });
+    }
+  }
+  assert_eq!(mint_count_one_uri.as_deref(), Some("ipfs://hidden"));
+
+  let token_uris = |chain: &Chain| -> Vec<String> {
+    let TokenMetadataQueryResponse(urls) = invoke_view(
+      chain,
+      contract_address,
+      "tokenMetadata",
+      &ContractTokenMetadataQueryParams {
+        queries: (1..=4).map(TokenIdU32).collect(),
+      },
+    );
+    urls.into_iter().map(|url| url.url).collect()
+  };
+  assert_eq!(token_uris(&chain), ["ipfs://hidden"; 4]);
+
+  let update = update_contract(&mut chain, contract_address, USER, "revealBatch", &1u32)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::NotOwner.into());
+
+  for (count, revealed_up_to, expected) in [
+    (
+      1u32,
+      1u32,
+      [
+        "ipfs://1",
+        "ipfs://hidden",
+        "ipfs://hidden",
+        "ipfs://hidden",
+      ],
+    ),
+    (2, 3, ["ipfs://1", "ipfs://2", "ipfs://3", "ipfs://hidden"]),
+    (10, 4, ["ipfs://1", "ipfs://2", "ipfs://3", "ipfs://4"]),
+  ] {
+    let update = update_contract(&mut chain, contract_address, OWNER, "revealBatch", &count)
+      .expect("Reveal batch failed");
+    let rv: u32 = update.parse_return_value().expect("Revealed up to");
+    assert_eq!(rv, revealed_up_to);
+    assert_eq!(token_uris(&chain), expected);
+  }
+  assert_eq!(
+    get_view_settings(&chain, contract_address).revealed_up_to,
+    4
+  );
+
+  // The batch above was capped at the minted tokens.
+  mint_to_address(&mut chain, contract_address, c_mint_params(5), None, None).expect("Mint failed");
+  let TokenMetadataQueryResponse(urls) = invoke_view(
+    &chain,
+    contract_address,
+    "tokenMetadata",
+    &ContractTokenMetadataQueryParams {
+      queries: vec![TokenIdU32(5)],
+    },
+  );
+  assert_eq!(urls[0].url, "ipfs://hidden");
+}
+
 #[concordium_test]
 fn test_token_metadata_on_mint() {
   let (mut chain, contract_address) = initialize_chain_and_contract(100);
@@ -289,6 +525,8 @@ fn test_mint_should_fail_when_arrays_not_equal() {
       "ipfs://test1".to_string(),
       "ipfs://test2".to_string(),
     ],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
   };
 
   let update = mint_to_address(&mut chain, contract_address, mint_params, None, None)
@@ -388,6 +626,89 @@ fn test_owner_should_be_able_to_set_minter() {
 
   let contract_settings = get_view_settings(&chain, contract_address);
   assert_eq!(contract_settings.minter, new_minter_params.minter);
+  assert_eq!(contract_settings.minters, [new_minter_params.minter]);
+}
+
+/// Test that minters added with `addMinter` can mint next to the primary
+/// minter until they are removed, and that the minters are locked with the
+/// primary minter.
+#[concordium_test]
+fn test_multiple_minters() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let update = update_contract(&mut chain, contract_address, USER, "addMinter", &NEW_MINTER)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::NotOwner.into());
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "addMinter",
+    &NEW_MINTER,
+  )
+  .expect("Add minter failed");
+  let contract_settings = get_view_settings(&chain, contract_address);
+  assert_eq!(contract_settings.minter, MINTER);
+  assert_eq!(contract_settings.minters, [MINTER, NEW_MINTER]);
+
+  mint_to_address(&mut chain, contract_address, c_mint_params(1), None, None).expect("Mint failed");
+  mint_to_address(
+    &mut chain,
+    contract_address,
+    c_mint_params(2),
+    Some(NEW_MINTER),
+    Some(Address::Account(NEW_MINTER)),
+  )
+  .expect("Mint by the added minter failed");
+  let roles: RolesOf = invoke_view(
+    &chain,
+    contract_address,
+    "rolesOf",
+    &Address::Account(NEW_MINTER),
+  );
+  assert!(roles.is_minter);
+
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "removeMinter",
+    &NEW_MINTER,
+  )
+  .expect("Remove minter failed");
+  let update = mint_to_address(
+    &mut chain,
+    contract_address,
+    c_mint_params(3),
+    Some(NEW_MINTER),
+    Some(Address::Account(NEW_MINTER)),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::Unauthorized);
+
+  update_contract(&mut chain, contract_address, OWNER, "lockMinter", &())
+    .expect("Lock minter failed");
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "addMinter",
+    &NEW_MINTER,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::MinterLocked.into());
+  assert_eq!(
+    get_view_settings(&chain, contract_address).minters,
+    [MINTER]
+  );
 }
 
 #[concordium_test]
@@ -403,6 +724,8 @@ fn test_view_address() {
       "ipfs://test1".to_string(),
       "ipfs://test2".to_string(),
     ],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
   };
   mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
 
@@ -413,6 +736,3049 @@ fn test_view_address() {
     ViewAddress {
       owned_tokens: vec![TokenIdU32(2), TokenIdU32(20)],
       operators: Vec::new(),
+      truncated: false,
     },
   );
 }
+
+#[concordium_test]
+fn test_view_contract_balance() {
+  let chain_timestamp = MINT_START + 1;
+  let (chain, contract_address) = initialize_chain_and_contract(chain_timestamp);
+
+  let rv: ViewState = get_view_state(&chain, contract_address);
+  assert_eq!(rv.contract_balance, Amount::zero());
+  assert_eq!(
+    chain.contract_balance(contract_address),
+    Some(rv.contract_balance)
+  );
+}
+
+#[concordium_test]
+fn test_view_contract_balance_after_public_mint() {
+  let chain_timestamp = MINT_START + 1;
+  let (mut chain, contract_address) = initialize_chain_and_contract(chain_timestamp);
+
+  let rv: ViewState = get_view_state(&chain, contract_address);
+  assert_eq!(rv.contract_balance, Amount::zero());
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TOKEN_0, TOKEN_1],
+    token_uris: vec!["ipfs://test".to_string(), "ipfs://test1".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  public_mint(
+    &mut chain,
+    contract_address,
+    mint_params,
+    USER,
+    MINT_PRICE * 2,
+  )
+  .expect("Mint failed");
+
+  let rv: ViewState = get_view_state(&chain, contract_address);
+  assert_eq!(rv.contract_balance, MINT_PRICE * 2);
+  assert_eq!(rv.all_tokens[..], [TOKEN_0, TOKEN_1]);
+}
+
+#[concordium_test]
+fn test_mint_without_custom_events() {
+  let chain_timestamp = MINT_START + 1;
+  let params = InitParams {
+    emit_events: false,
+    ..default_init_params()
+  };
+
+  let mut chain = initialize_chain(chain_timestamp);
+  let init = initialize_contract(&mut chain, &params);
+  // No `Deploy` event is logged.
+  assert!(init.events.is_empty());
+  let contract_address = init.contract_address;
+
+  let update = mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None)
+    .expect("Mint failed");
+
+  // Only the CIS2 `Mint` event is logged.
+  let events: Vec<ContractEvent> = update
+    .events()
+    .flat_map(|(_addr, events)| events)
+    .map(|e| e.parse().expect("Deserialize event"))
+    .collect();
+  assert_eq!(
+    events,
+    [ContractEvent::Mint(MintEvent {
+      token_id: TokenIdU32(2),
+      amount: TokenAmountU8(1),
+      owner: USER_ADDR,
+    })]
+  );
+}
+
+#[concordium_test]
+fn test_mint_within_deadline_grace_period() {
+  let chain_timestamp = MINT_DEADLINE + 50;
+  let params = InitParams {
+    deadline_grace_ms: 100,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(chain_timestamp, params);
+
+  // Past the deadline but within the grace period.
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  // Past the grace period.
+  chain
+    .tick_block_time(Duration::from_millis(100))
+    .expect("Advance block time");
+  let update = mint_to_address(&mut chain, contract_address, c_mint_params(42), None, None)
+    .expect_err("Call didnt fail");
+
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(
+    rv,
+    Cis2Error::Custom(CustomContractError::MintDeadlineReached)
+  );
+}
+
+#[concordium_test]
+fn test_mint_timestamp() {
+  let chain_timestamp = MINT_START + 1;
+  let (mut chain, contract_address) = initialize_chain_and_contract(chain_timestamp);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  chain
+    .tick_block_time(Duration::from_millis(10))
+    .expect("Advance block time");
+  mint_to_address(&mut chain, contract_address, c_mint_params(42), None, None)
+    .expect("Mint failed");
+
+  let token_ids = ContractMintTimestampQueryParams {
+    queries: vec![TOKEN_0, TOKEN_1],
+  };
+  let invoke = chain
+    .contract_invoke(
+      USER,
+      USER_ADDR,
+      Energy::from(10000),
+      UpdateContractPayload {
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.mintTimestamp".to_string()),
+        address: contract_address,
+        message: OwnedParameter::from_serial(&token_ids).expect("tokenIds params"),
+      },
+    )
+    .expect("Invoke view");
+
+  let TokenMintTimestampQueryResponse(timestamps) = invoke
+    .parse_return_value()
+    .expect("Timestamps return value");
+  assert_eq!(timestamps, vec![chain_timestamp, chain_timestamp + 10]);
+}
+
+#[concordium_test]
+fn test_pause_minting_keeps_transfers_active() {
+  let chain_timestamp = MINT_START + 1;
+  let (mut chain, contract_address) = initialize_chain_and_contract(chain_timestamp);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  // Only the owner can pause minting.
+  update_contract(&mut chain, contract_address, USER, "pauseMinting", &())
+    .expect_err("Call didnt fail");
+  update_contract(&mut chain, contract_address, OWNER, "pauseMinting", &()).expect("Pause minting");
+  assert!(get_view_settings(&chain, contract_address).minting_paused);
+
+  let update = mint_to_address(&mut chain, contract_address, c_mint_params(42), None, None)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::MintingPaused));
+
+  // Transfers are still allowed.
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TOKEN_0,
+  )
+  .expect("Transfer tokens");
+
+  update_contract(&mut chain, contract_address, OWNER, "resumeMinting", &())
+    .expect("Resume minting");
+  mint_to_address(&mut chain, contract_address, c_mint_params(42), None, None)
+    .expect("Mint failed");
+}
+
+/// Test that `lockMinting` disables minting for good, before the deadline and
+/// after `resumeMinting`, and that only the owner can call it.
+#[concordium_test]
+fn test_lock_minting() {
+  let chain_timestamp = MINT_START + 1;
+  let (mut chain, contract_address) = initialize_chain_and_contract(chain_timestamp);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  let update = update_contract(&mut chain, contract_address, USER, "lockMinting", &())
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::NotOwner));
+  assert!(!get_view_settings(&chain, contract_address).minting_locked);
+  update_contract(&mut chain, contract_address, OWNER, "lockMinting", &()).expect("Lock minting");
+  assert!(get_view_settings(&chain, contract_address).minting_locked);
+
+  update_contract(&mut chain, contract_address, OWNER, "resumeMinting", &())
+    .expect("Resume minting");
+  chain
+    .tick_block_time(Duration::from_millis(10))
+    .expect("Tick block time");
+  let update = mint_to_address(&mut chain, contract_address, c_mint_params(42), None, None)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::MintingLocked));
+  assert_eq!(get_view_state(&chain, contract_address).counter, 1);
+}
+
+/// Test that copies of an edition can be minted up to its max supply, and
+/// that balances, transfers and burns count the copies.
+#[concordium_test]
+fn test_edition_supply() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let max_supply = SetMaxSupply {
+    token_id: TOKEN_0,
+    max_supply: 3,
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "setMaxSupply",
+    &max_supply,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::NotOwner));
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setMaxSupply",
+    &max_supply,
+  )
+  .expect("Set max supply");
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR, USER2_ADDR],
+    tokens: vec![TOKEN_0; 3],
+    token_uris: vec!["ipfs://edition".to_string(); 3],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+  let update = mint_to_address(
+    &mut chain,
+    contract_address,
+    c_mint_params(TOKEN_0.0),
+    None,
+    None,
+  )
+  .expect_err("Mint above max supply didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(
+    rv,
+    Cis2Error::Custom(CustomContractError::MaxTotalSupplyReached)
+  );
+
+  let balances = |chain: &Chain| -> Vec<ContractTokenAmount> {
+    let params = BalanceOfQueryParams {
+      queries: [USER_ADDR, USER2_ADDR]
+        .into_iter()
+        .map(|address| BalanceOfQuery {
+          token_id: TOKEN_0,
+          address,
+        })
+        .collect(),
+    };
+    let response: BalanceOfQueryResponse<ContractTokenAmount> =
+      invoke_view(chain, contract_address, "balanceOf", &params);
+    response.0
+  };
+  assert_eq!(balances(&chain), [TokenAmountU8(2), TokenAmountU8(1)]);
+
+  let transfer = TransferParams::from(vec![concordium_cis2::Transfer {
+    from: USER_ADDR,
+    to: Receiver::Account(USER2),
+    token_id: TOKEN_0,
+    amount: TokenAmountU8(2),
+    data: AdditionalData::empty(),
+  }]);
+  update_contract(&mut chain, contract_address, USER, "transfer", &transfer)
+    .expect("Transfer copies");
+  assert_eq!(balances(&chain), [TokenAmountU8(0), TokenAmountU8(3)]);
+  let stats: AccountStats = invoke_view(&chain, contract_address, "accountStats", &USER_ADDR);
+  assert_eq!(stats, AccountStats { minted: 2, held: 0 });
+  let stats: AccountStats = invoke_view(&chain, contract_address, "accountStats", &USER2_ADDR);
+  assert_eq!(stats, AccountStats { minted: 1, held: 3 });
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(
+    view_state
+      .state
+      .into_iter()
+      .map(|(address, a_state)| (address, a_state.owned_tokens))
+      .collect::<Vec<_>>(),
+    [(USER_ADDR, vec![]), (USER2_ADDR, vec![TOKEN_0])]
+  );
+
+  // Once minted, the max supply only changes through the timelock.
+  let max_supply = SetMaxSupply {
+    token_id: TOKEN_0,
+    max_supply: 4,
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setMaxSupply",
+    &max_supply,
+  )
+  .expect_err("Max supply of minted token didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::MaxSupplyLocked));
+  let update = queue_and_execute(
+    &mut chain,
+    contract_address,
+    AdminAction::SetMaxSupply {
+      token_id: TOKEN_0,
+      max_supply: 2,
+    },
+  )
+  .expect_err("Max supply below supply didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::InvalidMaxSupply));
+
+  let burn = BurnParams {
+    token_id: TOKEN_0,
+    owner: USER2_ADDR,
+  };
+  update_contract(&mut chain, contract_address, USER2, "burn", &burn).expect("Burn copy");
+  assert_eq!(balances(&chain), [TokenAmountU8(0), TokenAmountU8(2)]);
+
+  // A copy cannot change the royalty or hash of the edition.
+  for (royalties, token_hashes) in [
+    (
+      vec![TokenRoyalty {
+        recipient: USER,
+        basis_points: 500,
+      }],
+      Vec::new(),
+    ),
+    (Vec::new(), vec![Some([1; 32])]),
+  ] {
+    let mint_params = MintParams {
+      owners: vec![USER_ADDR],
+      tokens: vec![TOKEN_0],
+      token_uris: vec!["ipfs://edition".to_string()],
+      royalties,
+      token_hashes,
+    };
+    let update = mint_to_address(&mut chain, contract_address, mint_params, None, None)
+      .expect_err("Mint of copy with royalty or hash didnt fail");
+    let rv: ContractError = update
+      .parse_return_value()
+      .expect("ContractError return value");
+    assert_eq!(
+      rv,
+      Cis2Error::Custom(CustomContractError::TokenIdAlreadyExists)
+    );
+  }
+  assert_eq!(balances(&chain), [TokenAmountU8(0), TokenAmountU8(2)]);
+}
+
+/// Test that more copies of an edition than fit a `ContractTokenAmount` can
+/// be minted, but not held by a single address.
+#[concordium_test]
+fn test_edition_balance_overflow() {
+  let (mut chain, contract_address) = initialize_chain_and_contract_with_params(
+    MINT_START + 1,
+    InitParams {
+      max_total_supply: 300,
+      ..default_init_params()
+    },
+  );
+  let max_supply = SetMaxSupply {
+    token_id: TOKEN_0,
+    max_supply: 300,
+  };
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setMaxSupply",
+    &max_supply,
+  )
+  .expect("Set max supply");
+
+  let copies = usize::from(u8::MAX);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR; copies],
+    tokens: vec![TOKEN_0; copies],
+    token_uris: vec!["ipfs://edition".to_string(); copies],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  chain
+    .contract_update(
+      SIGNER,
+      MINTER,
+      MINTER_ADDR,
+      Energy::from(1_000_000),
+      UpdateContractPayload {
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.mint".to_string()),
+        address: contract_address,
+        message: OwnedParameter::from_serial(&mint_params).expect("Mint params"),
+      },
+    )
+    .expect("Mint failed");
+
+  let update = mint_to_address(
+    &mut chain,
+    contract_address,
+    c_mint_params(TOKEN_0.0),
+    None,
+    None,
+  )
+  .expect_err("Mint past the largest balance didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::AmountOverflow));
+
+  let mint_params = MintParams {
+    owners: vec![USER2_ADDR],
+    tokens: vec![TOKEN_0],
+    token_uris: vec!["ipfs://edition".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+  let transfer = TransferParams::from(vec![concordium_cis2::Transfer {
+    from: USER2_ADDR,
+    to: Receiver::Account(USER),
+    token_id: TOKEN_0,
+    amount: TokenAmountU8(1),
+    data: AdditionalData::empty(),
+  }]);
+  let update = update_contract(&mut chain, contract_address, USER2, "transfer", &transfer)
+    .expect_err("Transfer past the largest balance didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::AmountOverflow));
+
+  let params = BalanceOfQueryParams {
+    queries: vec![BalanceOfQuery {
+      token_id: TOKEN_0,
+      address: USER_ADDR,
+    }],
+  };
+  let response: BalanceOfQueryResponse<ContractTokenAmount> =
+    invoke_view(&chain, contract_address, "balanceOf", &params);
+  assert_eq!(response.0, [TokenAmountU8(u8::MAX)]);
+}
+
+#[concordium_test]
+fn test_token_royalty_overrides_default() {
+  let chain_timestamp = MINT_START + 1;
+  let (mut chain, contract_address) = initialize_chain_and_contract(chain_timestamp);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+  mint_to_address(&mut chain, contract_address, c_mint_params(42), None, None)
+    .expect("Mint failed");
+
+  let params = SetTokenRoyaltyParams {
+    token_id: TOKEN_0,
+    basis_points: 1000,
+    recipient: USER3,
+  };
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "setTokenRoyalty",
+    &params,
+  )
+  .expect_err("Call didnt fail");
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setTokenRoyalty",
+    &params,
+  )
+  .expect("Set token royalty");
+
+  let royalty_of = |token_id| -> RoyaltyOfResponse {
+    chain
+      .contract_invoke(
+        USER,
+        USER_ADDR,
+        Energy::from(10000),
+        UpdateContractPayload {
+          amount: Amount::zero(),
+          receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.royaltyOf".to_string()),
+          address: contract_address,
+          message: OwnedParameter::from_serial(&RoyaltyOfQueryParams {
+            token_id,
+            sale_price: Amount::from_ccd(100),
+          })
+          .expect("RoyaltyOf params"),
+        },
+      )
+      .expect("Invoke royaltyOf")
+      .parse_return_value()
+      .expect("RoyaltyOf return value")
+  };
+
+  // 10% override.
+  assert_eq!(
+    royalty_of(TOKEN_0),
+    RoyaltyOfResponse {
+      receiver: USER3,
+      royalty_amount: Amount::from_ccd(10),
+    }
+  );
+  // 5% contract default.
+  assert_eq!(
+    royalty_of(TOKEN_1),
+    RoyaltyOfResponse {
+      receiver: OWNER,
+      royalty_amount: Amount::from_ccd(5),
+    }
+  );
+}
+
+#[concordium_test]
+fn test_set_token_royalty_should_fail_above_max() {
+  let chain_timestamp = MINT_START + 1;
+  let (mut chain, contract_address) = initialize_chain_and_contract(chain_timestamp);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  let params = SetTokenRoyaltyParams {
+    token_id: TOKEN_0,
+    basis_points: 10001,
+    recipient: USER3,
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setTokenRoyalty",
+    &params,
+  )
+  .expect_err("Call didnt fail");
+
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::InvalidRoyalty));
+}
+
+#[concordium_test]
+fn test_mint_should_fail_on_duplicate_uri_when_unique_required() {
+  let chain_timestamp = MINT_START + 1;
+  let params = InitParams {
+    require_unique_uri: true,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(chain_timestamp, params);
+
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+  // `c_mint_params` always uses the same URI.
+  let update = mint_to_address(&mut chain, contract_address, c_mint_params(42), None, None)
+    .expect_err("Call didnt fail");
+
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::DuplicateUri));
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR],
+    tokens: vec![TOKEN_1],
+    token_uris: vec!["ipfs://test1".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+}
+
+/// Test that an account mints the tokens assigned to it with `claim`, and
+/// cannot claim them a second time.
+#[concordium_test]
+fn test_claim_assigned_tokens() {
+  let chain_timestamp = MINT_START + 1;
+  let (mut chain, contract_address) = initialize_chain_and_contract(chain_timestamp);
+
+  let params = AssignClaimsParams {
+    account: USER,
+    tokens: vec![TOKEN_0, TOKEN_1],
+    token_uris: vec!["ipfs://test0".to_string(), "ipfs://test1".to_string()],
+  };
+  let update = update_contract(&mut chain, contract_address, USER, "assignClaims", &params)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::NotOwner));
+  update_contract(&mut chain, contract_address, OWNER, "assignClaims", &params)
+    .expect("Assign claims failed");
+
+  update_contract(&mut chain, contract_address, USER, "claim", &()).expect("Claim failed");
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(view_state.all_tokens, vec![TOKEN_0, TOKEN_1]);
+  assert_eq!(
+    view_state.state,
+    vec![(
+      USER_ADDR,
+      ViewAddressState {
+        owned_tokens: vec![TOKEN_0, TOKEN_1],
+        operators: Vec::new(),
+      }
+    )]
+  );
+
+  for account in [USER, USER2] {
+    let update = update_contract(&mut chain, contract_address, account, "claim", &())
+      .expect_err("Call didnt fail");
+    let rv: ContractError = update
+      .parse_return_value()
+      .expect("ContractError return value");
+    assert_eq!(rv, Cis2Error::Custom(CustomContractError::NoClaim));
+  }
+}
+
+/// Test that `rolesOf` reports the roles of an address.
+#[concordium_test]
+fn test_roles_of() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+
+  let params = UpdateOperatorParams(vec![UpdateOperator {
+    update: OperatorUpdate::Add,
+    operator: USER2_ADDR,
+  }]);
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "updateOperator",
+    &params,
+  )
+  .expect("Update operator");
+
+  let roles_of = |address: Address| -> RolesOf {
+    chain
+      .contract_invoke(
+        USER,
+        USER_ADDR,
+        Energy::from(10000),
+        UpdateContractPayload {
+          amount: Amount::zero(),
+          receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.rolesOf".to_string()),
+          address: contract_address,
+          message: OwnedParameter::from_serial(&address).expect("Address param"),
+        },
+      )
+      .expect("Invoke rolesOf")
+      .parse_return_value()
+      .expect("RolesOf return value")
+  };
+
+  assert_eq!(
+    roles_of(OWNER_ADDR),
+    RolesOf {
+      is_owner: true,
+      is_minter: false,
+      is_admin: false,
+    }
+  );
+  assert_eq!(
+    roles_of(MINTER_ADDR),
+    RolesOf {
+      is_owner: false,
+      is_minter: true,
+      is_admin: false,
+    }
+  );
+  assert_eq!(
+    roles_of(USER2_ADDR),
+    RolesOf {
+      is_owner: false,
+      is_minter: false,
+      is_admin: true,
+    }
+  );
+  assert_eq!(
+    roles_of(USER3_ADDR),
+    RolesOf {
+      is_owner: false,
+      is_minter: false,
+      is_admin: false,
+    }
+  );
+}
+
+/// Test that `totalSupply` counts the minted tokens and `totalBurned` starts
+/// at zero.
+#[concordium_test]
+fn test_total_supply() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2), TokenIdU32(3)],
+    token_uris: vec![
+      "ipfs://test1".to_string(),
+      "ipfs://test2".to_string(),
+      "ipfs://test3".to_string(),
+    ],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  let total_supply: u32 = invoke_view(&chain, contract_address, "totalSupply", &());
+  let total_burned: u32 = invoke_view(&chain, contract_address, "totalBurned", &());
+  assert_eq!(total_supply, 3);
+  assert_eq!(total_burned, 0);
+}
+
+/// Test that burned tokens are excluded from the total supply and counted by
+/// `totalBurned`.
+#[concordium_test]
+fn test_total_supply_after_burn() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2), TokenIdU32(3)],
+    token_uris: vec![
+      "ipfs://test1".to_string(),
+      "ipfs://test2".to_string(),
+      "ipfs://test3".to_string(),
+    ],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  let params = BurnParams {
+    token_id: TokenIdU32(2),
+    owner: USER_ADDR,
+  };
+  let update = update_contract(&mut chain, contract_address, USER2, "burn", &params)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::Unauthorized);
+  update_contract(&mut chain, contract_address, USER, "burn", &params).expect("Burn failed");
+
+  let total_supply: u32 = invoke_view(&chain, contract_address, "totalSupply", &());
+  let total_burned: u32 = invoke_view(&chain, contract_address, "totalBurned", &());
+  assert_eq!(total_supply, 2);
+  assert_eq!(total_burned, 1);
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(view_state.all_tokens, vec![TokenIdU32(1), TokenIdU32(3)]);
+}
+
+/// Test that the minter can be changed until it is locked.
+#[concordium_test]
+fn test_lock_minter() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+
+  let params = SetMinter { minter: NEW_MINTER };
+  update_contract(&mut chain, contract_address, OWNER, "setMinter", &params)
+    .expect("Set minter failed");
+
+  let update = update_contract(&mut chain, contract_address, USER, "lockMinter", &())
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::NotOwner));
+  update_contract(&mut chain, contract_address, OWNER, "lockMinter", &())
+    .expect("Lock minter failed");
+
+  let params = SetMinter { minter: MINTER };
+  let update = update_contract(&mut chain, contract_address, OWNER, "setMinter", &params)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::MinterLocked));
+
+  let contract_settings = get_view_settings(&chain, contract_address);
+  assert_eq!(contract_settings.minter, NEW_MINTER);
+  assert!(contract_settings.minter_locked);
+}
+
+/// Test that the contract cannot be initialized with a treasury share above
+/// 100%.
+#[concordium_test]
+fn test_init_rejects_invalid_fee_split() {
+  let mut chain = initialize_chain(MINT_START + 1);
+  let params = InitParams {
+    treasury: Some(USER3),
+    treasury_bps: MAX_TREASURY_BASIS_POINTS + 1,
+    ..default_init_params()
+  };
+
+  let error = try_initialize_contract(&mut chain, &params).expect_err("Init didnt fail");
+  assert!(matches!(
+    error.kind,
+    ContractInitErrorKind::ExecutionError {
+      error: InitExecutionError::Reject { reason, .. }
+    } if CustomContractError::from_reject_code(reason) == Some(CustomContractError::InvalidFeeSplit)
+  ));
+}
+
+/// Test that `publicMint` payments are split between the treasury and the
+/// minter.
+#[concordium_test]
+fn test_public_mint_treasury_split() {
+  let params = InitParams {
+    treasury: Some(USER3),
+    treasury_bps: 2000,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TOKEN_0, TOKEN_1],
+    token_uris: vec!["ipfs://test".to_string(), "ipfs://test1".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  public_mint(
+    &mut chain,
+    contract_address,
+    mint_params,
+    USER,
+    MINT_PRICE * 2,
+  )
+  .expect("Mint failed");
+
+  assert_eq!(
+    chain.account_balance_available(USER3),
+    Some(ACC_INITIAL_BALANCE + Amount::from_ccd(2))
+  );
+  assert_eq!(
+    chain.account_balance_available(MINTER),
+    Some(ACC_INITIAL_BALANCE + Amount::from_ccd(8))
+  );
+  let rv: ViewState = get_view_state(&chain, contract_address);
+  assert_eq!(rv.contract_balance, Amount::zero());
+}
+
+/// Test that `contractHoldings` only lists tokens owned by contracts.
+#[concordium_test]
+fn test_contract_holdings() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let marketplace = ContractAddress::new(100, 0);
+
+  // Minting to a contract does not invoke a receive hook, so the marketplace
+  // does not need to exist.
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, Address::Contract(marketplace)],
+    tokens: vec![TOKEN_0, TOKEN_1],
+    token_uris: vec!["ipfs://test".to_string(), "ipfs://test1".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  let holdings: ContractHoldings = invoke_view(&chain, contract_address, "contractHoldings", &());
+  assert_eq!(
+    holdings.items,
+    vec![(
+      marketplace,
+      ViewItems {
+        items: vec![TOKEN_1],
+        truncated: false,
+      }
+    )]
+  );
+  assert!(!holdings.truncated);
+  let view_address = get_view_address(&chain, contract_address, USER_ADDR);
+  assert_eq!(view_address.owned_tokens, vec![TOKEN_0]);
+}
+
+/// Test that the contract signals support for its extended entrypoints.
+#[concordium_test]
+fn test_supports_extended_standard() {
+  let (chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+
+  let params = SupportsQueryParams {
+    queries: vec![
+      StandardIdentifierOwned::new_unchecked("CIPHERS_NFT_EXT".to_string()),
+      StandardIdentifierOwned::new_unchecked("UNKNOWN".to_string()),
+    ],
+  };
+  let rv: SupportsQueryResponse = invoke_view(&chain, contract_address, "supports", &params);
+  assert!(matches!(
+    rv.results[..],
+    [SupportResult::Support, SupportResult::NoSupport]
+  ));
+}
+
+/// Test that setting the current minter again is a no-op, also when the
+/// minter is locked.
+#[concordium_test]
+fn test_set_minter_unchanged() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  update_contract(&mut chain, contract_address, OWNER, "lockMinter", &())
+    .expect("Lock minter failed");
+
+  let params = SetMinter { minter: MINTER };
+  update_contract(&mut chain, contract_address, OWNER, "setMinter", &params)
+    .expect("Set minter failed");
+  assert_eq!(get_view_settings(&chain, contract_address).minter, MINTER);
+
+  let params = SetMinter { minter: NEW_MINTER };
+  let update = update_contract(&mut chain, contract_address, OWNER, "setMinter", &params)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::MinterLocked));
+}
+
+/// Test that with an admin timelock the minter can only be changed through a
+/// queued action once its delay has passed.
+#[concordium_test]
+fn test_admin_timelock() {
+  let params = InitParams {
+    admin_timelock_ms: 100,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) = initialize_chain_and_contract_with_params(MINT_START, params);
+
+  let params = SetMinter { minter: NEW_MINTER };
+  let update = update_contract(&mut chain, contract_address, OWNER, "setMinter", &params)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::TimelockRequired));
+
+  let params = QueueAdminActionParams {
+    action: AdminAction::SetMinter(NEW_MINTER),
+    execute_after: MINT_START + 50,
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "queueAdminAction",
+    &params,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::TimelockTooShort));
+
+  let params = QueueAdminActionParams {
+    action: AdminAction::SetMinter(NEW_MINTER),
+    execute_after: MINT_START + 100,
+  };
+  let id: u32 = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "queueAdminAction",
+    &params,
+  )
+  .expect("Queue admin action failed")
+  .parse_return_value()
+  .expect("Admin action ID");
+
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "executeAdminAction",
+    &id,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(
+    rv,
+    Cis2Error::Custom(CustomContractError::TimelockNotExpired)
+  );
+  assert_eq!(get_view_settings(&chain, contract_address).minter, MINTER);
+
+  chain
+    .tick_block_time(Duration::from_millis(100))
+    .expect("Advance block time");
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "executeAdminAction",
+    &id,
+  )
+  .expect("Execute admin action failed");
+  assert_eq!(
+    get_view_settings(&chain, contract_address).minter,
+    NEW_MINTER
+  );
+
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "executeAdminAction",
+    &id,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(
+    rv,
+    Cis2Error::Custom(CustomContractError::UnknownAdminAction)
+  );
+}
+
+/// Test that with an admin timelock minters can only be added and removed
+/// through queued actions.
+#[concordium_test]
+fn test_admin_timelock_minters() {
+  let params = InitParams {
+    admin_timelock_ms: 100,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) = initialize_chain_and_contract_with_params(MINT_START, params);
+
+  for entrypoint in ["addMinter", "removeMinter"] {
+    let update = update_contract(&mut chain, contract_address, OWNER, entrypoint, &NEW_MINTER)
+      .expect_err("Call didnt fail");
+    let rv: ContractError = update
+      .parse_return_value()
+      .expect("ContractError return value");
+    assert_eq!(rv, Cis2Error::Custom(CustomContractError::TimelockRequired));
+  }
+  assert_eq!(
+    get_view_settings(&chain, contract_address).minters,
+    [MINTER]
+  );
+
+  let mut ids = Vec::new();
+  for action in [
+    AdminAction::AddMinter(NEW_MINTER),
+    AdminAction::RemoveMinter(MINTER),
+  ] {
+    let params = QueueAdminActionParams {
+      action,
+      execute_after: MINT_START + 100,
+    };
+    let id: u32 = update_contract(
+      &mut chain,
+      contract_address,
+      OWNER,
+      "queueAdminAction",
+      &params,
+    )
+    .expect("Queue admin action failed")
+    .parse_return_value()
+    .expect("Admin action ID");
+    ids.push(id);
+  }
+
+  chain
+    .tick_block_time(Duration::from_millis(100))
+    .expect("Advance block time");
+  for id in ids {
+    update_contract(
+      &mut chain,
+      contract_address,
+      OWNER,
+      "executeAdminAction",
+      &id,
+    )
+    .expect("Execute admin action failed");
+  }
+  assert_eq!(
+    get_view_settings(&chain, contract_address).minters,
+    [NEW_MINTER]
+  );
+}
+
+/// Test that admin actions without a timelock can be executed right away.
+#[concordium_test]
+fn test_admin_actions_without_timelock() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+
+  for action in [
+    AdminAction::SetMaxTotalSupply(20),
+    AdminAction::SetRoyalty {
+      basis_points: 250,
+      recipient: USER,
+    },
+  ] {
+    let params = QueueAdminActionParams {
+      action,
+      execute_after: MINT_START + 1,
+    };
+    let id: u32 = update_contract(
+      &mut chain,
+      contract_address,
+      OWNER,
+      "queueAdminAction",
+      &params,
+    )
+    .expect("Queue admin action failed")
+    .parse_return_value()
+    .expect("Admin action ID");
+    update_contract(
+      &mut chain,
+      contract_address,
+      OWNER,
+      "executeAdminAction",
+      &id,
+    )
+    .expect("Execute admin action failed");
+  }
+
+  let contract_settings = get_view_settings(&chain, contract_address);
+  assert_eq!(contract_settings.max_total_supply, 20);
+  assert_eq!(contract_settings.royalty_basis_points, 250);
+  assert_eq!(contract_settings.royalty_recipient, USER);
+}
+
+/// Test that the max total supply cannot be set below the tokens minted.
+#[concordium_test]
+fn test_admin_action_max_total_supply_below_minted() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  let update = queue_and_execute(
+    &mut chain,
+    contract_address,
+    AdminAction::SetMaxTotalSupply(0),
+  )
+  .expect_err("Max total supply below minted didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::InvalidMaxSupply));
+  assert_eq!(
+    get_view_settings(&chain, contract_address).max_total_supply,
+    MAX_TOTAL_SUPPLY
+  );
+}
+
+/// Test that `stateSize` counts the elements in the state.
+#[concordium_test]
+fn test_state_size() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR, USER2_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2), TokenIdU32(3)],
+    token_uris: vec![
+      "ipfs://test1".to_string(),
+      "ipfs://test2".to_string(),
+      "ipfs://test3".to_string(),
+    ],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+  let params = UpdateOperatorParams(vec![
+    UpdateOperator {
+      update: OperatorUpdate::Add,
+      operator: USER2_ADDR,
+    },
+    UpdateOperator {
+      update: OperatorUpdate::Add,
+      operator: USER3_ADDR,
+    },
+  ]);
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "updateOperator",
+    &params,
+  )
+  .expect("Update operator");
+  let params = SetImplementorsParams {
+    id: StandardIdentifierOwned::new_unchecked("CIS-3".to_string()),
+    implementors: vec![ContractAddress::new(100, 0)],
+  };
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setImplementors",
+    &params,
+  )
+  .expect("Set implementors");
+
+  let state_size: StateSize = invoke_view(&chain, contract_address, "stateSize", &());
+  assert_eq!(
+    state_size,
+    StateSize {
+      addresses: 2,
+      tokens: 3,
+      operators: 2,
+      implementors: 1,
+    }
+  );
+}
+
+/// Test that `viewAddressDetailed` includes the URI and mint count of every
+/// owned token.
+#[concordium_test]
+fn test_view_address_detailed() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER2_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2), TokenIdU32(3)],
+    token_uris: vec![
+      "ipfs://test1".to_string(),
+      "ipfs://test2".to_string(),
+      "ipfs://test3".to_string(),
+    ],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  let detailed: ViewAddressDetailed = invoke_view(
+    &chain,
+    contract_address,
+    "viewAddressDetailed",
+    &Some(USER_ADDR),
+  );
+  assert!(!detailed.truncated);
+  assert_eq!(
+    detailed.items,
+    vec![
+      OwnedTokenDetails {
+        token_id: TokenIdU32(1),
+        token_uri: "ipfs://test1".to_string(),
+        mint_count: 1,
+      },
+      OwnedTokenDetails {
+        token_id: TokenIdU32(3),
+        token_uri: "ipfs://test3".to_string(),
+        mint_count: 3,
+      },
+    ]
+  );
+}
+
+/// Test that a mint proof counts the leading zero bits of the hash and hashes
+/// the same message within a block time bucket.
+#[concordium_test]
+fn test_mint_proof_message() {
+  assert_eq!(leading_zero_bits(&[0, 0b0001_0000, 0xff]), 11);
+  assert_eq!(leading_zero_bits(&[0xff; 32]), 0);
+  assert_eq!(leading_zero_bits(&[0; 32]), 256);
+
+  let bucket_start = 2 * MINT_PROOF_BUCKET_MS;
+  let message = mint_proof_message(&USER_ADDR, 7, bucket_start);
+  assert_eq!(
+    message,
+    mint_proof_message(&USER_ADDR, 7, bucket_start + MINT_PROOF_BUCKET_MS - 1)
+  );
+  assert_ne!(
+    message,
+    mint_proof_message(&USER_ADDR, 7, bucket_start + MINT_PROOF_BUCKET_MS)
+  );
+  assert_ne!(message, mint_proof_message(&USER_ADDR, 8, bucket_start));
+  assert_ne!(message, mint_proof_message(&USER2_ADDR, 7, bucket_start));
+}
+
+/// Test that `publicMint` requires a valid proof of work when a difficulty is
+/// set.
+#[concordium_test]
+fn test_public_mint_proof() {
+  let params = InitParams {
+    mint_proof_difficulty: 8,
+    ..default_init_params()
+  };
+  let block_time = MINT_START + 1;
+  let (mut chain, contract_address) = initialize_chain_and_contract_with_params(block_time, params);
+
+  let proof_bits = |nonce: u64| {
+    let message = mint_proof_message(&USER_ADDR, nonce, block_time);
+    leading_zero_bits(&Sha256::digest(message))
+  };
+  let valid_nonce = (0..)
+    .find(|nonce| proof_bits(*nonce) >= 8)
+    .expect("Valid nonce");
+  let invalid_nonce = (0..)
+    .find(|nonce| proof_bits(*nonce) < 8)
+    .expect("Invalid nonce");
+
+  let params = PublicMintParams {
+    mint: c_mint_params(2),
+    nonce: invalid_nonce,
+  };
+  let update = public_mint_with_proof(&mut chain, contract_address, params, USER, MINT_PRICE)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::InvalidMintProof));
+
+  let params = PublicMintParams {
+    mint: c_mint_params(2),
+    nonce: valid_nonce,
+  };
+  public_mint_with_proof(&mut chain, contract_address, params, USER, MINT_PRICE)
+    .expect("Mint failed");
+}
+
+/// Test that only the owner can update the allowlist and that the cooldown
+/// and per-account cap are exposed by `viewSettings`.
+#[concordium_test]
+fn test_set_allowlisted() {
+  let params = InitParams {
+    mint_cooldown_ms: 100,
+    max_mints_per_account: 2,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+
+  let allowlist = SetAllowlisted {
+    address: USER_ADDR,
+    allowed: true,
+  };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "setAllowlisted",
+    &allowlist,
+  )
+  .expect_err("Only the owner can update the allowlist");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::NotOwner));
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setAllowlisted",
+    &allowlist,
+  )
+  .expect("Allowlist failed");
+
+  let settings = get_view_settings(&chain, contract_address);
+  assert_eq!(settings.mint_cooldown_ms, 100);
+  assert_eq!(settings.max_mints_per_account, 2);
+}
+
+/// Test that allowlisted addresses bypass the public mint cooldown and
+/// per-account cap.
+#[concordium_test]
+fn test_allowlist_bypasses_cooldown() {
+  let params = InitParams {
+    mint_cooldown_ms: 100,
+    max_mints_per_account: 2,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+
+  let allowlist = SetAllowlisted {
+    address: USER_ADDR,
+    allowed: true,
+  };
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "setAllowlisted",
+    &allowlist,
+  )
+  .expect_err("Only the owner can update the allowlist");
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setAllowlisted",
+    &allowlist,
+  )
+  .expect("Allowlist failed");
+
+  // The allowlisted address mints three tokens back to back.
+  for token in 1..=3 {
+    public_mint(
+      &mut chain,
+      contract_address,
+      c_mint_params(token),
+      USER,
+      MINT_PRICE,
+    )
+    .expect("Allowlisted mint failed");
+  }
+
+  // Another address is cooled down after its first mint.
+  let mint_params = |token| MintParams {
+    owners: vec![USER2_ADDR],
+    tokens: vec![TokenIdU32(token)],
+    token_uris: vec!["ipfs://test".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  public_mint(
+    &mut chain,
+    contract_address,
+    mint_params(4),
+    USER2,
+    MINT_PRICE,
+  )
+  .expect("Mint failed");
+  let update = public_mint(
+    &mut chain,
+    contract_address,
+    mint_params(5),
+    USER2,
+    MINT_PRICE,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::MintCooldown));
+
+  // After the cooldown it can mint again, up to the per-account cap.
+  chain
+    .tick_block_time(Duration::from_millis(100))
+    .expect("Tick block time");
+  public_mint(
+    &mut chain,
+    contract_address,
+    mint_params(5),
+    USER2,
+    MINT_PRICE,
+  )
+  .expect("Mint failed");
+  chain
+    .tick_block_time(Duration::from_millis(100))
+    .expect("Tick block time");
+  let update = public_mint(
+    &mut chain,
+    contract_address,
+    mint_params(6),
+    USER2,
+    MINT_PRICE,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(
+    rv,
+    Cis2Error::Custom(CustomContractError::AccountMintCapReached)
+  );
+}
+
+/// Test that `accountStats` counts the tokens ever minted to an address
+/// separately from the tokens it still holds.
+#[concordium_test]
+fn test_account_stats() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2), TokenIdU32(3)],
+    token_uris: vec![
+      "ipfs://test1".to_string(),
+      "ipfs://test2".to_string(),
+      "ipfs://test3".to_string(),
+    ],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(2),
+  )
+  .expect("Transfer failed");
+
+  let stats: AccountStats = invoke_view(&chain, contract_address, "accountStats", &USER_ADDR);
+  assert_eq!(stats, AccountStats { minted: 3, held: 2 });
+  let stats: AccountStats = invoke_view(&chain, contract_address, "accountStats", &USER2_ADDR);
+  assert_eq!(stats, AccountStats { minted: 0, held: 1 });
+}
+
+/// Test that the tokens of a batch get their mint count and events in the
+/// input order, not in token ID order.
+#[concordium_test]
+fn test_batch_mint_order() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let tokens = vec![TokenIdU32(30), TokenIdU32(10), TokenIdU32(20)];
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER2_ADDR, USER3_ADDR],
+    tokens: tokens.clone(),
+    token_uris: vec![
+      "ipfs://test30".to_string(),
+      "ipfs://test10".to_string(),
+      "ipfs://test20".to_string(),
+    ],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  let update =
+    mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  let params = ContractMintCountQueryParams {
+    queries: tokens.clone(),
+  };
+  let mint_counts: TokenMintCountQueryResponse =
+    invoke_view(&chain, contract_address, "getMintCountTokenID", &params);
+  assert_eq!(mint_counts.0, [1, 2, 3]);
+
+  let minted: Vec<(ContractTokenId, u32)> = update
+    .events()
+    .flat_map(|(_addr, events)| events)
+    .filter_map(|e| match e.parse().expect("Deserialize event") {
+      ContractEvent::Minted(event) => Some((event.token_id, event.mint_count)),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(minted, [(tokens[0], 1), (tokens[1], 2), (tokens[2], 3)]);
+}
+
+/// Test that the owner reclaims the tokens not claimed before the claim
+/// deadline by minting them to a treasury.
+#[concordium_test]
+fn test_reclaim_unclaimed() {
+  let params = InitParams {
+    claim_deadline: MINT_START + 50,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+
+  for (account, token_id) in [(USER, TOKEN_0), (USER2, TOKEN_1)] {
+    let params = AssignClaimsParams {
+      account,
+      tokens: vec![token_id],
+      token_uris: vec!["ipfs://test".to_string()],
+    };
+    update_contract(&mut chain, contract_address, OWNER, "assignClaims", &params)
+      .expect("Assign claims failed");
+  }
+  update_contract(&mut chain, contract_address, USER, "claim", &()).expect("Claim failed");
+
+  let treasury = Some(USER3_ADDR);
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "reclaimUnclaimed",
+    &treasury,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::ClaimNotExpired));
+
+  chain
+    .tick_block_time(Duration::from_millis(100))
+    .expect("Tick block time");
+  let update = update_contract(&mut chain, contract_address, USER2, "claim", &())
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::ClaimExpired));
+
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "reclaimUnclaimed",
+    &treasury,
+  )
+  .expect("Reclaim failed");
+
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(view_state.all_tokens, vec![TOKEN_0, TOKEN_1]);
+  assert_eq!(
+    view_state.state,
+    vec![
+      (
+        USER_ADDR,
+        ViewAddressState {
+          owned_tokens: vec![TOKEN_0],
+          operators: Vec::new(),
+        }
+      ),
+      (
+        USER3_ADDR,
+        ViewAddressState {
+          owned_tokens: vec![TOKEN_1],
+          operators: Vec::new(),
+        }
+      ),
+    ]
+  );
+}
+
+/// Test that `mintTo` results in the same state and events as minting a
+/// single token with `mint`.
+#[concordium_test]
+fn test_mint_to() {
+  let (mut batch_chain, batch_address) = initialize_chain_and_contract(MINT_START + 1);
+  let batch_update = mint_to_address(
+    &mut batch_chain,
+    batch_address,
+    c_mint_params(2),
+    None,
+    None,
+  )
+  .expect("Mint failed");
+
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "mintTo",
+    &c_mint_to_params(2),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::Unauthorized);
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    MINTER,
+    "mintTo",
+    &c_mint_to_params(2),
+  )
+  .expect("Mint to failed");
+
+  let events = |update: &ContractInvokeSuccess| -> Vec<ContractEvent> {
+    update
+      .events()
+      .flat_map(|(_addr, events)| events)
+      .map(|e| e.parse().expect("Deserialize event"))
+      .collect()
+  };
+  assert_eq!(events(&update), events(&batch_update));
+
+  let view_state = get_view_state(&chain, contract_address);
+  let batch_view_state = get_view_state(&batch_chain, batch_address);
+  assert_eq!(view_state.state, batch_view_state.state);
+  assert_eq!(view_state.all_tokens, batch_view_state.all_tokens);
+  assert_eq!(view_state.mint_count, batch_view_state.mint_count);
+}
+
+/// Test that a deposited token is held by the vault and returns to the
+/// redeemer, and that vault tokens can be transferred before redeeming.
+#[concordium_test]
+fn test_vault_deposit_and_redeem() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  mint_to_address(&mut chain, contract_address, c_mint_params(1), None, None).expect("Mint failed");
+  let vault = Address::Contract(contract_address);
+  let owners = |chain: &Chain| -> Vec<(Address, Vec<ContractTokenId>)> {
+    get_view_state(chain, contract_address)
+      .state
+      .into_iter()
+      .map(|(address, a_state)| (address, a_state.owned_tokens))
+      .collect()
+  };
+
+  // Depositing a token transfers it to the vault and credits a vault token.
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "vaultDeposit",
+    &TokenIdU32(1),
+  )
+  .expect("Deposit failed");
+  assert_eq!(
+    owners(&chain),
+    [(USER_ADDR, vec![]), (vault, vec![TokenIdU32(1)])]
+  );
+  let balance: VaultAmount = invoke_view(&chain, contract_address, "vaultBalanceOf", &USER_ADDR);
+  assert_eq!(balance, 1);
+
+  // The deposit round-trips back to the depositor.
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "vaultRedeem",
+    &TokenIdU32(1),
+  )
+  .expect("Redeem failed");
+  assert_eq!(
+    owners(&chain),
+    [(USER_ADDR, vec![TokenIdU32(1)]), (vault, vec![])]
+  );
+
+  // A vault token transferred to another address redeems the token there.
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "vaultDeposit",
+    &TokenIdU32(1),
+  )
+  .expect("Deposit failed");
+  let params = VaultTransferParams {
+    to: USER2_ADDR,
+    amount: 1,
+  };
+  update_contract(&mut chain, contract_address, USER, "vaultTransfer", &params)
+    .expect("Vault transfer failed");
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "vaultRedeem",
+    &TokenIdU32(1),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::InsufficientFunds);
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER2,
+    "vaultRedeem",
+    &TokenIdU32(1),
+  )
+  .expect("Redeem failed");
+  assert_eq!(
+    owners(&chain),
+    [
+      (USER_ADDR, vec![]),
+      (USER2_ADDR, vec![TokenIdU32(1)]),
+      (vault, vec![])
+    ]
+  );
+  let balance: VaultAmount = invoke_view(&chain, contract_address, "vaultBalanceOf", &USER2_ADDR);
+  assert_eq!(balance, 0);
+}
+
+/// Test that `tokensMissingUri` reports the tokens minted with an empty URI.
+#[concordium_test]
+fn test_tokens_missing_uri() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let missing: ViewItems<ContractTokenId> =
+    invoke_view(&chain, contract_address, "tokensMissingUri", &());
+  assert!(missing.items.is_empty());
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2)],
+    token_uris: vec!["ipfs://test1".to_string(), String::new()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  let missing: ViewItems<ContractTokenId> =
+    invoke_view(&chain, contract_address, "tokensMissingUri", &());
+  assert_eq!(missing.items, [TokenIdU32(2)]);
+}
+
+/// Test that frozen and locked tokens are listed by `restrictedTokens` and
+/// cannot be transferred, and that expired locks are no longer listed.
+#[concordium_test]
+fn test_restricted_tokens() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2)],
+    token_uris: vec!["ipfs://test1".to_string(), "ipfs://test2".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  let until = Timestamp::from_timestamp_millis(MINT_START + 500);
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "freeze",
+    &vec![TokenIdU32(1)],
+  )
+  .expect("Freeze failed");
+  let lock = LockTokenParams {
+    token_id: TokenIdU32(2),
+    until,
+  };
+  let update = update_contract(&mut chain, contract_address, USER, "lockToken", &lock)
+    .expect_err("Lock by non-owner didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::NotOwner.into());
+  update_contract(&mut chain, contract_address, OWNER, "lockToken", &lock).expect("Lock failed");
+
+  let restricted: RestrictedTokens = invoke_view(&chain, contract_address, "restrictedTokens", &());
+  assert_eq!(
+    restricted,
+    RestrictedTokens {
+      frozen: vec![TokenIdU32(1)],
+      locked: vec![(TokenIdU32(2), until)],
+      truncated: false,
+    }
+  );
+
+  for (token_id, error) in [
+    (TokenIdU32(1), CustomContractError::TokenFrozen),
+    (TokenIdU32(2), CustomContractError::TokenLocked),
+  ] {
+    let update = transfer_token(
+      &mut chain,
+      contract_address,
+      USER,
+      USER_ADDR,
+      Receiver::Account(USER2),
+      token_id,
+    )
+    .expect_err("Transfer didnt fail");
+    let rv: ContractError = update
+      .parse_return_value()
+      .expect("ContractError return value");
+    assert_eq!(rv, error.into());
+  }
+
+  chain
+    .tick_block_time(Duration::from_millis(500))
+    .expect("Tick block time");
+  let restricted: RestrictedTokens = invoke_view(&chain, contract_address, "restrictedTokens", &());
+  assert_eq!(restricted.locked, []);
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(2),
+  )
+  .expect("Transfer after lock failed");
+
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "unfreeze",
+    &vec![TokenIdU32(1)],
+  )
+  .expect("Unfreeze failed");
+  let restricted: RestrictedTokens = invoke_view(&chain, contract_address, "restrictedTokens", &());
+  assert_eq!(restricted.frozen, []);
+}
+
+/// Test that only the owner can freeze tokens, that a batch with a frozen
+/// token is rejected as a whole, and that the other tokens can still move.
+#[concordium_test]
+fn test_freeze_rejects_batch() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2)],
+    token_uris: vec!["ipfs://test1".to_string(), "ipfs://test2".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "freeze",
+    &vec![TokenIdU32(1)],
+  )
+  .expect_err("Freeze by non-owner didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::NotOwner.into());
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "freeze",
+    &vec![TokenIdU32(1)],
+  )
+  .expect("Freeze failed");
+
+  let transfers = TransferParams::from(
+    [TokenIdU32(2), TokenIdU32(1)]
+      .into_iter()
+      .map(|token_id| concordium_cis2::Transfer {
+        from: USER_ADDR,
+        to: Receiver::Account(USER2),
+        token_id,
+        amount: TokenAmountU8(1),
+        data: AdditionalData::empty(),
+      })
+      .collect::<Vec<_>>(),
+  );
+  let update = update_contract(&mut chain, contract_address, USER, "transfer", &transfers)
+    .expect_err("Batch with frozen token didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::TokenFrozen.into());
+  let view_state = get_view_state(&chain, contract_address);
+  assert!(view_state
+    .state
+    .iter()
+    .all(|(address, _)| *address != USER2_ADDR));
+
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(2),
+  )
+  .expect("Transfer of unfrozen token failed");
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(
+    view_state
+      .state
+      .into_iter()
+      .map(|(address, a_state)| (address, a_state.owned_tokens))
+      .collect::<Vec<_>>(),
+    [
+      (USER_ADDR, vec![TokenIdU32(1)]),
+      (USER2_ADDR, vec![TokenIdU32(2)]),
+    ]
+  );
+}
+
+/// Test that `viewLight` returns the counts and settings, and costs less
+/// energy than the full `view`.
+#[concordium_test]
+fn test_view_light() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER2_ADDR, USER2_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2), TokenIdU32(3)],
+    token_uris: vec!["ipfs://a-long-token-uri".to_string(); 3],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  let invoke = |entrypoint: &str| {
+    chain
+      .contract_invoke(
+        OWNER,
+        OWNER_ADDR,
+        Energy::from(10000),
+        UpdateContractPayload {
+          amount: Amount::zero(),
+          receive_name: OwnedReceiveName::new_unchecked(format!("ciphers_nft.{}", entrypoint)),
+          address: contract_address,
+          message: OwnedParameter::empty(),
+        },
+      )
+      .expect("Invoke view")
+  };
+  let light = invoke("viewLight");
+  let rv: ViewLight = light.parse_return_value().expect("ViewLight return value");
+  assert_eq!(
+    rv,
+    ViewLight {
+      addresses: 2,
+      counter: 3,
+      burned_counter: 0,
+      mint_start: MINT_START,
+      mint_deadline: MINT_DEADLINE,
+      max_total_supply: MAX_TOTAL_SUPPLY,
+      mint_price: MINT_PRICE,
+      minting_paused: false,
+      contract_balance: Amount::zero(),
+    }
+  );
+  assert!(light.energy_used < invoke("view").energy_used);
+}
+
+/// Test that `firstOwnerOf` keeps returning the address a token was minted to
+/// after the token is transferred.
+#[concordium_test]
+fn test_first_owner_of() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TOKEN_0,
+  )
+  .expect("Transfer failed");
+
+  let params = ContractFirstOwnerQueryParams {
+    queries: vec![TOKEN_0],
+  };
+  let FirstOwnerQueryResponse(first_owners) =
+    invoke_view(&chain, contract_address, "firstOwnerOf", &params);
+  assert_eq!(first_owners, [USER_ADDR]);
+
+  let params = ContractFirstOwnerQueryParams {
+    queries: vec![TOKEN_1],
+  };
+  let update = update_contract(&mut chain, contract_address, USER, "firstOwnerOf", &params)
+    .expect_err("Query of unknown token didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::InvalidTokenId);
+}
+
+/// Test that views list at most `MAX_VIEW_ITEMS` tokens and flag the result
+/// as truncated once there are more.
+#[concordium_test]
+fn test_view_items_truncated() {
+  let params = InitParams {
+    max_total_supply: MAX_VIEW_ITEMS as u32 + 1,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+
+  // Tokens are minted with an empty URI to keep the views below the energy
+  // of the helpers.
+  let mint = |chain: &mut Chain, token: u32| {
+    let mint_params = MintParams {
+      owners: vec![USER_ADDR],
+      tokens: vec![TokenIdU32(token)],
+      token_uris: vec![String::new()],
+      royalties: Vec::new(),
+      token_hashes: Vec::new(),
+    };
+    mint_to_address(chain, contract_address, mint_params, None, None).expect("Mint failed");
+  };
+  for token in 0..MAX_VIEW_ITEMS as u32 {
+    mint(&mut chain, token);
+  }
+  let missing: ViewItems<ContractTokenId> =
+    invoke_view(&chain, contract_address, "tokensMissingUri", &());
+  assert_eq!(missing.items.len(), MAX_VIEW_ITEMS);
+  assert!(!missing.truncated);
+  assert!(!get_view_state(&chain, contract_address).truncated);
+  let tokens_of: ViewItems<ContractTokenId> =
+    invoke_view(&chain, contract_address, "tokensOf", &USER_ADDR);
+  assert_eq!(tokens_of.items.len(), MAX_VIEW_ITEMS);
+  assert!(!tokens_of.truncated);
+
+  mint(&mut chain, MAX_VIEW_ITEMS as u32);
+  let missing: ViewItems<ContractTokenId> =
+    invoke_view(&chain, contract_address, "tokensMissingUri", &());
+  assert_eq!(missing.items.len(), MAX_VIEW_ITEMS);
+  assert!(missing.truncated);
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(view_state.all_tokens.len(), MAX_VIEW_ITEMS);
+  assert!(view_state.truncated);
+  let view_address = get_view_address(&chain, contract_address, USER_ADDR);
+  assert_eq!(view_address.owned_tokens.len(), MAX_VIEW_ITEMS);
+  assert!(view_address.truncated);
+  let tokens_of: ViewItems<ContractTokenId> =
+    invoke_view(&chain, contract_address, "tokensOf", &USER_ADDR);
+  assert_eq!(tokens_of.items.len(), MAX_VIEW_ITEMS);
+  assert!(tokens_of.truncated);
+}
+
+/// Test that a token cannot be transferred during the resale lock after its
+/// mint, and can be afterwards.
+#[concordium_test]
+fn test_resale_lock() {
+  let params = InitParams {
+    resale_lock_ms: 100,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  chain
+    .tick_block_time(Duration::from_millis(99))
+    .expect("Advance block time");
+  let update = transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TOKEN_0,
+  )
+  .expect_err("Transfer during resale lock didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::ResaleLocked.into());
+
+  chain
+    .tick_block_time(Duration::from_millis(1))
+    .expect("Advance block time");
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TOKEN_0,
+  )
+  .expect("Transfer after resale lock failed");
+}
+
+/// Test that an operator can burn a token, which removes it from the state
+/// without decrementing the mint counter and logs a `Burn` event, and that
+/// burning it again rejects with `InvalidTokenId`.
+#[concordium_test]
+fn test_burn_by_operator() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2)],
+    token_uris: vec!["ipfs://test1".to_string(), "ipfs://test2".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+  let operator = UpdateOperatorParams(vec![UpdateOperator {
+    update: OperatorUpdate::Add,
+    operator: USER2_ADDR,
+  }]);
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "updateOperator",
+    &operator,
+  )
+  .expect("Update operator failed");
+
+  let params = BurnParams {
+    token_id: TokenIdU32(1),
+    owner: USER_ADDR,
+  };
+  let update =
+    update_contract(&mut chain, contract_address, USER2, "burn", &params).expect("Burn failed");
+  let events = update
+    .events()
+    .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+    .collect::<Vec<Cis2Event<ContractTokenId, ContractTokenAmount>>>();
+  assert_eq!(
+    events,
+    [Cis2Event::Burn(BurnEvent {
+      token_id: TokenIdU32(1),
+      amount: TokenAmountU8(1),
+      owner: USER_ADDR,
+    })]
+  );
+
+  let view_state = get_view_state(&chain, contract_address);
+  assert_eq!(view_state.all_tokens, [TokenIdU32(2)]);
+  assert_eq!(view_state.token_uris, ["ipfs://test2".to_string()]);
+  assert_eq!(view_state.state[0].1.owned_tokens, [TokenIdU32(2)]);
+  assert_eq!(view_state.counter, 2);
+
+  let update = update_contract(&mut chain, contract_address, USER, "burn", &params)
+    .expect_err("Burning a burned token didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::InvalidTokenId);
+}
+
+/// Test that frozen and locked tokens cannot be burned, and that a burned
+/// token minted again is not restricted.
+#[concordium_test]
+fn test_burn_restricted_tokens() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mint_params = || MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2)],
+    token_uris: vec!["ipfs://test1".to_string(), "ipfs://test2".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params(), None, None).expect("Mint failed");
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "freeze",
+    &vec![TokenIdU32(1)],
+  )
+  .expect("Freeze failed");
+  let lock = LockTokenParams {
+    token_id: TokenIdU32(2),
+    until: Timestamp::from_timestamp_millis(MINT_START + 500),
+  };
+  update_contract(&mut chain, contract_address, OWNER, "lockToken", &lock).expect("Lock failed");
+
+  for (token_id, error) in [
+    (TokenIdU32(1), CustomContractError::TokenFrozen),
+    (TokenIdU32(2), CustomContractError::TokenLocked),
+  ] {
+    let params = BurnParams {
+      token_id,
+      owner: USER_ADDR,
+    };
+    let update = update_contract(&mut chain, contract_address, USER, "burn", &params)
+      .expect_err("Burn didnt fail");
+    let rv: ContractError = update
+      .parse_return_value()
+      .expect("ContractError return value");
+    assert_eq!(rv, error.into());
+  }
+
+  chain
+    .tick_block_time(Duration::from_millis(500))
+    .expect("Tick block time");
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "unfreeze",
+    &vec![TokenIdU32(1)],
+  )
+  .expect("Unfreeze failed");
+  for token_id in [TokenIdU32(1), TokenIdU32(2)] {
+    let params = BurnParams {
+      token_id,
+      owner: USER_ADDR,
+    };
+    update_contract(&mut chain, contract_address, USER, "burn", &params).expect("Burn failed");
+  }
+
+  mint_to_address(&mut chain, contract_address, mint_params(), None, None).expect("Mint failed");
+  let restricted: RestrictedTokens = invoke_view(&chain, contract_address, "restrictedTokens", &());
+  assert_eq!(restricted.frozen, []);
+  assert_eq!(restricted.locked, []);
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TokenIdU32(2),
+  )
+  .expect("Transfer of minted again token failed");
+}
+
+/// Test that with `burn_requires_admin` the token owner cannot burn alone, and
+/// the contract owner burns once made an operator of the token owner.
+#[concordium_test]
+fn test_burn_requires_admin() {
+  let params = InitParams {
+    burn_requires_admin: true,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+  mint_to_address(&mut chain, contract_address, c_mint_params(1), None, None).expect("Mint failed");
+
+  let params = BurnParams {
+    token_id: TokenIdU32(1),
+    owner: USER_ADDR,
+  };
+  let update = update_contract(&mut chain, contract_address, USER, "burn", &params)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::NotOwner));
+
+  // Without the token owner's approval the contract owner cannot burn.
+  let update = update_contract(&mut chain, contract_address, OWNER, "burn", &params)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::Unauthorized);
+
+  let operator_params = UpdateOperatorParams(vec![UpdateOperator {
+    update: OperatorUpdate::Add,
+    operator: OWNER_ADDR,
+  }]);
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "updateOperator",
+    &operator_params,
+  )
+  .expect("Update operator");
+  update_contract(&mut chain, contract_address, OWNER, "burn", &params).expect("Burn failed");
+
+  let total_burned: u32 = invoke_view(&chain, contract_address, "totalBurned", &());
+  assert_eq!(total_burned, 1);
+}
+
+/// Test that `adminConfig` reflects the init parameters and later admin
+/// changes.
+#[concordium_test]
+fn test_admin_config() {
+  let params = InitParams {
+    treasury: Some(USER3),
+    treasury_bps: 2500,
+    max_operators_per_owner: 3,
+    resale_lock_ms: 100,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+  update_contract(&mut chain, contract_address, OWNER, "pauseMinting", &())
+    .expect("Pause minting failed");
+  update_contract(&mut chain, contract_address, OWNER, "lockMinter", &())
+    .expect("Lock minter failed");
+
+  let config: AdminConfig = invoke_view(&chain, contract_address, "adminConfig", &());
+  assert_eq!(config.owner, OWNER);
+  assert_eq!(config.minter, MINTER);
+  assert!(config.minter_locked);
+  assert!(config.minting_paused);
+  assert_eq!(config.treasury, Some(USER3));
+  assert_eq!(config.treasury_bps, 2500);
+  assert_eq!(config.max_operators_per_owner, 3);
+  assert_eq!(config.resale_lock_ms, 100);
+  assert_eq!(config.royalty_basis_points, ROYALTY_BASIS_POINTS);
+  assert_eq!(config.mint_price, MINT_PRICE);
+}
+
+/// Test that `transferOwnership` moves the admin rights to the new owner and
+/// that the previous owner loses them.
+#[concordium_test]
+fn test_transfer_ownership() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let params = TransferOwnershipParams { new_owner: USER3 };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "transferOwnership",
+    &params,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::NotOwner.into());
+
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "transferOwnership",
+    &params,
+  )
+  .expect("Transfer ownership failed");
+  let events: Vec<ContractEvent> = update
+    .events()
+    .flat_map(|(_addr, events)| events)
+    .map(|e| e.parse().expect("Deserialize event"))
+    .collect();
+  assert_eq!(
+    events,
+    [ContractEvent::OwnershipTransferred(
+      OwnershipTransferredEvent {
+        previous_owner: OWNER,
+        new_owner: USER3,
+      }
+    )]
+  );
+  let config: AdminConfig = invoke_view(&chain, contract_address, "adminConfig", &());
+  assert_eq!(config.owner, USER3);
+
+  let set_minter = SetMinter { minter: USER2 };
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setMinter",
+    &set_minter,
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::NotOwner.into());
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setImplementors",
+    &SetImplementorsParams {
+      id: StandardIdentifierOwned::new_unchecked("CIS-3".to_string()),
+      implementors: Vec::new(),
+    },
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::NotOwner.into());
+
+  update_contract(
+    &mut chain,
+    contract_address,
+    USER3,
+    "setMinter",
+    &set_minter,
+  )
+  .expect("Set minter by the new owner failed");
+  assert_eq!(get_view_settings(&chain, contract_address).minter, USER2);
+}
+
+/// Test that `entrypoints` lists exactly the receive entrypoints declared in
+/// the sources, so a new entrypoint cannot be left out.
+#[concordium_test]
+fn test_entrypoints() {
+  let (chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mut entrypoints: Vec<String> = invoke_view(&chain, contract_address, "entrypoints", &());
+  for name in [
+    "mint",
+    "transfer",
+    "view",
+    "balanceOf",
+    "operatorOf",
+    "viewSettings",
+  ] {
+    assert!(
+      entrypoints.iter().any(|entrypoint| entrypoint == name),
+      "{} is listed",
+      name
+    );
+  }
+
+  let mut declared = Vec::new();
+  for file in std::fs::read_dir("src").expect("Read src") {
+    let source = std::fs::read_to_string(file.expect("Source file").path()).expect("Read source");
+    for receive in source.split("#[receive(").skip(1) {
+      let attribute = &receive[..receive.find(")]").expect("End of attribute")];
+      let name = attribute
+        .split("name = \"")
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .expect("Entrypoint name");
+      declared.push(name.to_string());
+    }
+  }
+  entrypoints.sort();
+  declared.sort();
+  assert_eq!(entrypoints, declared);
+}
+
+/// Test that `setPaused` stops both minting and transfers until unpaused, and
+/// can only be called by the owner.
+#[concordium_test]
+fn test_set_paused() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  mint_to_address(&mut chain, contract_address, c_mint_params(2), None, None).expect("Mint failed");
+
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "setPaused",
+    &SetPaused { paused: true },
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::NotOwner.into());
+
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setPaused",
+    &SetPaused { paused: true },
+  )
+  .expect("Pause failed");
+  assert!(get_view_settings(&chain, contract_address).paused);
+
+  let update = mint_to_address(&mut chain, contract_address, c_mint_params(42), None, None)
+    .expect_err("Mint while paused didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::ContractPaused.into());
+  let update = transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TOKEN_0,
+  )
+  .expect_err("Transfer while paused didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::ContractPaused.into());
+
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "setPaused",
+    &SetPaused { paused: false },
+  )
+  .expect("Unpause failed");
+  assert!(!get_view_settings(&chain, contract_address).paused);
+  mint_to_address(&mut chain, contract_address, c_mint_params(42), None, None)
+    .expect("Mint after unpause failed");
+  transfer_token(
+    &mut chain,
+    contract_address,
+    USER,
+    USER_ADDR,
+    Receiver::Account(USER2),
+    TOKEN_0,
+  )
+  .expect("Transfer after unpause failed");
+}
+
+/// Test that royalties set at mint are returned by `tokenRoyalty`, that
+/// tokens minted without one use the contract default, and that royalties
+/// above 10000 basis points are rejected.
+#[concordium_test]
+fn test_token_royalty_at_mint() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2)],
+    token_uris: vec!["ipfs://test1".to_string(), "ipfs://test2".to_string()],
+    royalties: vec![
+      TokenRoyalty {
+        recipient: USER3,
+        basis_points: 250,
+      },
+      TokenRoyalty {
+        recipient: USER2,
+        basis_points: 10000,
+      },
+    ],
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+  mint_to_address(&mut chain, contract_address, c_mint_params(3), None, None).expect("Mint failed");
+
+  let royalty: RoyaltyResponse =
+    invoke_view(&chain, contract_address, "tokenRoyalty", &TokenIdU32(1));
+  assert_eq!(
+    royalty,
+    RoyaltyResponse {
+      recipient: USER3,
+      basis_points: 250,
+    }
+  );
+  let royalty: RoyaltyResponse =
+    invoke_view(&chain, contract_address, "tokenRoyalty", &TokenIdU32(3));
+  assert_eq!(
+    royalty,
+    RoyaltyResponse {
+      recipient: OWNER,
+      basis_points: ROYALTY_BASIS_POINTS,
+    }
+  );
+
+  let update = update_contract(
+    &mut chain,
+    contract_address,
+    USER,
+    "tokenRoyalty",
+    &TokenIdU32(4),
+  )
+  .expect_err("Query of unknown token didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::InvalidTokenId);
+
+  let mint_params = MintParams {
+    royalties: vec![TokenRoyalty {
+      recipient: USER3,
+      basis_points: 10001,
+    }],
+    ..c_mint_params(4)
+  };
+  let update = mint_to_address(&mut chain, contract_address, mint_params, None, None)
+    .expect_err("Mint with invalid royalty didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, CustomContractError::InvalidRoyalty.into());
+}
+
+/// Test that `publicMint` rejects an amount below the price of the batch and
+/// accepts the exact price.
+#[concordium_test]
+fn test_public_mint_payment() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+
+  let mint_params = || MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TOKEN_0, TOKEN_1],
+    token_uris: vec!["ipfs://test".to_string(), "ipfs://test1".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  let update = public_mint(
+    &mut chain,
+    contract_address,
+    mint_params(),
+    USER,
+    MINT_PRICE * 2 - Amount::from_micro_ccd(1),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(
+    rv,
+    Cis2Error::Custom(CustomContractError::InsufficientPayment)
+  );
+
+  let balance = chain.account_balance_available(USER).expect("User exists");
+  let update = public_mint(
+    &mut chain,
+    contract_address,
+    mint_params(),
+    USER,
+    MINT_PRICE * 2,
+  )
+  .expect("Mint failed");
+  assert_eq!(
+    chain.account_balance_available(USER),
+    Some(balance - MINT_PRICE * 2 - update.transaction_fee)
+  );
+  let rv: ViewState = get_view_state(&chain, contract_address);
+  assert_eq!(rv.contract_balance, MINT_PRICE * 2);
+  assert_eq!(rv.counter, 2);
+}
+
+/// Test that the amount paid above the price of a `publicMint` is refunded.
+#[concordium_test]
+fn test_public_mint_refunds_overpayment() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TOKEN_0, TOKEN_1],
+    token_uris: vec!["ipfs://test".to_string(), "ipfs://test1".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  let update = public_mint(
+    &mut chain,
+    contract_address,
+    mint_params,
+    USER,
+    MINT_PRICE * 2 + Amount::from_ccd(3),
+  )
+  .expect("Mint failed");
+
+  assert_eq!(
+    chain.account_balance_available(USER),
+    Some(ACC_INITIAL_BALANCE - MINT_PRICE * 2 - update.transaction_fee)
+  );
+  let rv: ViewState = get_view_state(&chain, contract_address);
+  assert_eq!(rv.contract_balance, MINT_PRICE * 2);
+}
+
+/// Test that without `refund_overpayment` the amount paid above the price of a
+/// `publicMint` stays in the contract.
+#[concordium_test]
+fn test_public_mint_keeps_overpayment() {
+  let params = InitParams {
+    refund_overpayment: false,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR],
+    tokens: vec![TOKEN_0],
+    token_uris: vec!["ipfs://test".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  let update = public_mint(
+    &mut chain,
+    contract_address,
+    mint_params,
+    USER,
+    MINT_PRICE + Amount::from_ccd(3),
+  )
+  .expect("Mint failed");
+
+  assert_eq!(
+    chain.account_balance_available(USER),
+    Some(ACC_INITIAL_BALANCE - MINT_PRICE - Amount::from_ccd(3) - update.transaction_fee)
+  );
+  let rv: ViewState = get_view_state(&chain, contract_address);
+  assert_eq!(rv.contract_balance, MINT_PRICE + Amount::from_ccd(3));
+}
+
+/// Test that `publicMint` rejects a batch whose price overflows an `Amount`.
+#[concordium_test]
+fn test_public_mint_price_overflow() {
+  let params = InitParams {
+    mint_price: Amount::from_micro_ccd(u64::MAX / 2 + 1),
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TOKEN_0, TOKEN_1],
+    token_uris: vec!["ipfs://test".to_string(), "ipfs://test1".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  let update = public_mint(
+    &mut chain,
+    contract_address,
+    mint_params,
+    USER,
+    Amount::zero(),
+  )
+  .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(
+    rv,
+    Cis2Error::Custom(CustomContractError::InsufficientPayment)
+  );
+}
+
+/// Test that the owner withdraws the proceeds of `publicMint`, and that a
+/// withdrawal of an empty balance does nothing.
+#[concordium_test]
+fn test_withdraw() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER_ADDR],
+    tokens: vec![TOKEN_0, TOKEN_1],
+    token_uris: vec!["ipfs://test".to_string(), "ipfs://test1".to_string()],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  public_mint(
+    &mut chain,
+    contract_address,
+    mint_params,
+    USER,
+    MINT_PRICE * 2,
+  )
+  .expect("Mint failed");
+
+  let params = WithdrawParams { receiver: USER3 };
+  let update = update_contract(&mut chain, contract_address, USER, "withdraw", &params)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, Cis2Error::Custom(CustomContractError::NotOwner));
+
+  let update = update_contract(&mut chain, contract_address, OWNER, "withdraw", &params)
+    .expect("Withdraw failed");
+  let events: Vec<ContractEvent> = update
+    .events()
+    .flat_map(|(_addr, events)| events)
+    .map(|e| e.parse().expect("Deserialize event"))
+    .collect();
+  assert_eq!(
+    events,
+    vec![ContractEvent::Withdraw(WithdrawEvent {
+      receiver: USER3,
+      amount: MINT_PRICE * 2,
+    })]
+  );
+  assert_eq!(
+    chain.account_balance_available(USER3),
+    Some(ACC_INITIAL_BALANCE + MINT_PRICE * 2)
+  );
+  assert_eq!(
+    chain.contract_balance(contract_address),
+    Some(Amount::zero())
+  );
+
+  let update = update_contract(&mut chain, contract_address, OWNER, "withdraw", &params)
+    .expect("Withdraw failed");
+  assert_eq!(
+    update.events().flat_map(|(_addr, events)| events).count(),
+    0
+  );
+  assert_eq!(
+    chain.account_balance_available(USER3),
+    Some(ACC_INITIAL_BALANCE + MINT_PRICE * 2)
+  );
+}
+
+/// Test that `canMint` reports the first mint precondition that fails,
+/// without changing the state.
+#[concordium_test]
+fn test_can_mint() {
+  let params = InitParams {
+    max_total_supply: 2,
+    max_mints_per_account: 1,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START - 10, params);
+  let can_mint = |chain: &Chain, sender: Address, token_id: ContractTokenId| -> MintEligibility {
+    let params = CanMintParams {
+      sender,
+      token_id,
+      owner: sender,
+    };
+    invoke_view(chain, contract_address, "canMint", &params)
+  };
+
+  assert_eq!(
+    can_mint(&chain, MINTER_ADDR, TOKEN_0),
+    MintEligibility::NotStarted
+  );
+
+  chain
+    .tick_block_time(Duration::from_millis(10))
+    .expect("Tick block time");
+  assert_eq!(can_mint(&chain, MINTER_ADDR, TOKEN_0), MintEligibility::Ok);
+  assert_eq!(can_mint(&chain, USER_ADDR, TOKEN_0), MintEligibility::Ok);
+
+  public_mint(
+    &mut chain,
+    contract_address,
+    MintToParams {
+      owner: USER_ADDR,
+      token_id: TOKEN_0,
+      token_uri: "ipfs://test".to_string(),
+    }
+    .into(),
+    USER,
+    MINT_PRICE,
+  )
+  .expect("Mint failed");
+  assert_eq!(
+    can_mint(&chain, USER_ADDR, TOKEN_0),
+    MintEligibility::TokenExists
+  );
+  assert_eq!(
+    can_mint(&chain, USER_ADDR, TOKEN_1),
+    MintEligibility::AccountCapReached
+  );
+  assert_eq!(can_mint(&chain, USER2_ADDR, TOKEN_1), MintEligibility::Ok);
+
+  mint_to_address(&mut chain, contract_address, c_mint_params(1), None, None).expect("Mint failed");
+  assert_eq!(
+    can_mint(&chain, MINTER_ADDR, TOKEN_1),
+    MintEligibility::SoldOut
+  );
+
+  chain
+    .tick_block_time(Duration::from_millis(MINT_DEADLINE))
+    .expect("Tick block time");
+  assert_eq!(
+    can_mint(&chain, MINTER_ADDR, TOKEN_1),
+    MintEligibility::DeadlinePassed
+  );
+}
+
+/// Test that burning a token minted with a deposit releases the deposit to
+/// the burner, is kept by `withdraw` and cannot be redeemed twice.
+#[concordium_test]
+fn test_redeem_deposit() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let deposit = Amount::from_ccd(20);
+  chain
+    .contract_update(
+      SIGNER,
+      MINTER,
+      MINTER_ADDR,
+      Energy::from(10000),
+      UpdateContractPayload {
+        amount: deposit,
+        receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.mintWithDeposit".to_string()),
+        address: contract_address,
+        message: OwnedParameter::from_serial(&c_mint_to_params(1)).expect("Mint params"),
+      },
+    )
+    .expect("Mint failed");
+  // The deposit is not withdrawn with the mint proceeds.
+  update_contract(
+    &mut chain,
+    contract_address,
+    OWNER,
+    "withdraw",
+    &WithdrawParams { receiver: OWNER },
+  )
+  .expect("Withdraw failed");
+  assert_eq!(chain.contract_balance(contract_address), Some(deposit));
+
+  let params = BurnParams {
+    token_id: TokenIdU32(1),
+    owner: USER_ADDR,
+  };
+  let balance = chain.account_balance_available(USER).expect("User exists");
+  let update =
+    update_contract(&mut chain, contract_address, USER, "burn", &params).expect("Burn failed");
+  assert_eq!(
+    chain.account_balance_available(USER),
+    Some(balance + deposit - update.transaction_fee)
+  );
+  assert_eq!(
+    chain.contract_balance(contract_address),
+    Some(Amount::zero())
+  );
+
+  let update = update_contract(&mut chain, contract_address, USER, "burn", &params)
+    .expect_err("Call didnt fail");
+  let rv: ContractError = update
+    .parse_return_value()
+    .expect("ContractError return value");
+  assert_eq!(rv, ContractError::InvalidTokenId);
+}
+
+/// Test that two pages of `viewPaginated` return disjoint, complete sets of
+/// the tokens and their URIs.
+#[concordium_test]
+fn test_view_paginated() {
+  let params = InitParams {
+    max_total_supply: 20,
+    ..default_init_params()
+  };
+  let (mut chain, contract_address) =
+    initialize_chain_and_contract_with_params(MINT_START + 1, params);
+  for batch in 0..10 {
+    let tokens: Vec<ContractTokenId> = (batch * 2..(batch + 1) * 2).map(TokenIdU32).collect();
+    let mint_params = MintParams {
+      owners: vec![USER_ADDR; 2],
+      token_uris: tokens
+        .iter()
+        .map(|token| format!("ipfs://{}", token.0))
+        .collect(),
+      tokens,
+      royalties: Vec::new(),
+      token_hashes: Vec::new(),
+    };
+    mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+  }
+
+  let page = |skip: u32| -> ViewPage {
+    invoke_view(
+      &chain,
+      contract_address,
+      "viewPaginated",
+      &PaginationParams { skip, take: 10 },
+    )
+  };
+  let first = page(0);
+  let second = page(10);
+  assert_eq!(first.total, 20);
+  assert_eq!(second.total, 20);
+  assert_eq!(first.all_tokens.len(), 10);
+  assert_eq!(second.all_tokens.len(), 10);
+
+  let mut tokens: Vec<u32> = first
+    .all_tokens
+    .iter()
+    .chain(second.all_tokens.iter())
+    .map(|token| token.0)
+    .collect();
+  for (token, uri) in first
+    .all_tokens
+    .iter()
+    .zip(first.token_uris.iter())
+    .chain(second.all_tokens.iter().zip(second.token_uris.iter()))
+  {
+    assert_eq!(uri, &format!("ipfs://{}", token.0));
+  }
+  tokens.sort();
+  assert_eq!(tokens, (0..20).collect::<Vec<u32>>());
+
+  assert!(page(20).all_tokens.is_empty());
+}
+
+/// Test that `tokensOf` returns the tokens of each owner and an empty list for
+/// an address without tokens.
+#[concordium_test]
+fn test_tokens_of() {
+  let (mut chain, contract_address) = initialize_chain_and_contract(MINT_START + 1);
+  let mint_params = MintParams {
+    owners: vec![USER_ADDR, USER2_ADDR, USER_ADDR],
+    tokens: vec![TokenIdU32(1), TokenIdU32(2), TokenIdU32(3)],
+    token_uris: vec!["ipfs://test".to_string(); 3],
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  };
+  mint_to_address(&mut chain, contract_address, mint_params, None, None).expect("Mint failed");
+
+  let tokens_of = |address: Address| -> Vec<ContractTokenId> {
+    let tokens: ViewItems<ContractTokenId> =
+      invoke_view(&chain, contract_address, "tokensOf", &address);
+    assert!(!tokens.truncated);
+    tokens.items
+  };
+  assert_eq!(tokens_of(USER_ADDR), vec![TokenIdU32(1), TokenIdU32(3)]);
+  assert_eq!(tokens_of(USER2_ADDR), vec![TokenIdU32(2)]);
+  assert_eq!(tokens_of(USER3_ADDR), Vec::new());
+}
@@ -0,0 +1,40 @@
+//! Tests for the serialization of the parameters of the `ciphers_nft`
+//! contract.
+use ciphers_nft::{cis2::ContractTokenId, mint::MintParams};
+use concordium_std::{from_bytes, AccountAddress, Address, Serial};
+
+const USER_ADDR: Address = Address::Account(AccountAddress([3u8; 32]));
+
+/// Mint parameters with `len` elements in every array.
+fn mint_params(len: u32) -> MintParams {
+  MintParams {
+    owners: vec![USER_ADDR; len as usize],
+    tokens: (0..len).map(ContractTokenId::from).collect(),
+    token_uris: (0..len).map(|i| format!("ipfs://test{}", i)).collect(),
+    royalties: Vec::new(),
+    token_hashes: Vec::new(),
+  }
+}
+
+/// Test that `MintParams` with 255 elements, the maximum size of its arrays,
+/// round-trips.
+#[test]
+fn test_mint_params_max_size_round_trips() {
+  let params = mint_params(255);
+  let mut bytes = Vec::new();
+  params.serial(&mut bytes).expect("Serialize 255 elements");
+
+  let parsed: MintParams = from_bytes(&bytes).expect("Parse 255 elements");
+  assert_eq!(parsed.owners, params.owners);
+  assert_eq!(parsed.tokens, params.tokens);
+  assert_eq!(parsed.token_uris, params.token_uris);
+}
+
+/// Test that `MintParams` with 256 elements fails to serialize, since the
+/// array lengths are encoded in one byte.
+#[test]
+fn test_mint_params_over_max_size_fails() {
+  let params = mint_params(256);
+  let mut bytes = Vec::new();
+  assert!(params.serial(&mut bytes).is_err());
+}
@@ -0,0 +1,65 @@
+//! A contract receiving CIS2 tokens, used by the `ciphers_nft` integration
+//! tests.
+//!
+//! It accepts every token sent to it through `onReceivingCis2`, and records
+//! the parameter of the last call to `callback` so tests can check what a
+//! token contract invoked it with.
+//!
+//! Build it with `cargo concordium build --out receiver.wasm.v1`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use concordium_cis2::*;
+use concordium_std::*;
+
+type ContractTokenId = TokenIdU32;
+type ContractTokenAmount = TokenAmountU8;
+
+#[derive(Serialize, SchemaType)]
+pub struct State {
+  /// Number of tokens received through `onReceivingCis2`.
+  pub received: u32,
+  /// The sender and parameter of the last call to `callback`.
+  pub last_callback: Option<(Address, Vec<u8>)>,
+}
+
+#[init(contract = "receiver")]
+fn init(_ctx: &InitContext, _state_builder: &mut StateBuilder) -> InitResult<State> {
+  Ok(State {
+    received: 0,
+    last_callback: None,
+  })
+}
+
+/// Accept the tokens sent to this contract.
+#[receive(
+  contract = "receiver",
+  name = "onReceivingCis2",
+  parameter = "OnReceivingCis2Params<ContractTokenId, ContractTokenAmount>",
+  mutable
+)]
+fn on_receiving_cis2(ctx: &ReceiveContext, host: &mut Host<State>) -> ReceiveResult<()> {
+  let _params: OnReceivingCis2Params<ContractTokenId, ContractTokenAmount> =
+    ctx.parameter_cursor().get()?;
+  host.state_mut().received += 1;
+  Ok(())
+}
+
+/// Record the sender and the raw parameter of the call.
+#[receive(contract = "receiver", name = "callback", mutable)]
+fn callback(ctx: &ReceiveContext, host: &mut Host<State>) -> ReceiveResult<()> {
+  let mut parameter = vec![0; ctx.parameter_cursor().size() as usize];
+  ctx.parameter_cursor().read_exact(&mut parameter)?;
+  host.state_mut().last_callback = Some((ctx.sender(), parameter));
+  Ok(())
+}
+
+/// View the state of the contract.
+#[receive(contract = "receiver", name = "view", return_value = "State")]
+fn view(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<State> {
+  let state = host.state();
+  Ok(State {
+    received: state.received,
+    last_callback: state.last_callback.clone(),
+  })
+}
@@ -1,13 +1,41 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 //! # A Concordium V1 smart contract
+use concordium_cis2::{Cis2Client, Cis2ClientError, TokenAmountU64, TokenIdVec};
 use concordium_std::*;
-use core::{fmt::Debug, option};
-use std::collections::BTreeMap;
+use core::fmt::Debug;
+use std::collections::{BTreeMap, BTreeSet};
 
-type VotingOption = String;
-type VotingIndex = u32;
-type VotingCount = u32;
+pub type VotingOption = String;
+pub type VotingIndex = u32;
+pub type VotingCount = u32;
+
+/// Maximum length in bytes of a voting option.
+pub const MAX_OPTION_LENGTH: usize = 100;
+
+/// Maximum number of delegations followed from an account to the ballot its
+/// vote counts for.
+pub const MAX_DELEGATION_DEPTH: usize = 8;
+
+/// How `winner` resolves a tie between options with the same number of votes.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieBreak {
+  /// A tie has no winner.
+  ReturnNone,
+  /// The tied option listed first in `options` wins.
+  LowestIndex,
+  /// The tied option is picked using the hash of the ballots as seed.
+  HashSeed,
+}
+
+/// The CIS2 tokens weighting the ballots of a token-gated vote.
+#[derive(Serialize, SchemaType, Clone, Debug)]
+pub struct TokenGating {
+  /// The CIS2 contract queried with `balanceOf`.
+  pub contract: ContractAddress,
+  /// The tokens whose balances are summed into the weight of a ballot.
+  pub token_ids: Vec<TokenIdVec>,
+}
 
 /// Your smart contract state.
 #[derive(Serialize, SchemaType, Clone)]
@@ -16,25 +44,220 @@ pub struct State {
   options: Vec<VotingOption>,
   end_time: Timestamp,
   ballots: BTreeMap<AccountAddress, VotingIndex>,
+  tie_break: TieBreak,
+  /// The delegate each account has authorized to cast its ballot.
+  vote_delegates: BTreeMap<AccountAddress, AccountAddress>,
+  /// Whether the first `view` after `end_time` locks the tally.
+  auto_finalize: bool,
+  /// The tally locked by `view`, once finalized.
+  final_tally: Option<Vec<VotingCount>>,
+  /// Minimum time in milliseconds between two ballots of an account.
+  min_vote_interval_ms: u64,
+  /// When each account last cast a ballot.
+  last_vote_at: BTreeMap<AccountAddress, u64>,
+  /// Maximum number of options, bounding the cost of the tally.
+  max_options: u32,
+  /// Deposit required to add an option with `addOption`.
+  option_deposit: Amount,
+  /// Number of votes an option needs for its deposit to be refunded.
+  deposit_threshold: VotingCount,
+  /// The proposer and the unclaimed deposit of every option added with a
+  /// deposit.
+  option_deposits: BTreeMap<VotingIndex, (AccountAddress, Amount)>,
+  /// The tokens weighting the ballots, `None` for one vote per account.
+  gating: Option<TokenGating>,
+  /// The weight of the ballot of each account, set with `setWeight` or, in a
+  /// token-gated vote, queried when the ballot is cast or the vote delegated.
+  /// Accounts without a weight have one vote.
+  weights: BTreeMap<AccountAddress, VotingCount>,
+  /// The account whose weight counts each gating token, so a token
+  /// transferred after weighting one ballot does not weigh another.
+  counted_tokens: BTreeMap<TokenIdVec, AccountAddress>,
+  /// The account that initialized the contract, the only one allowed to set
+  /// weights, to close the vote and to withdraw forfeited deposits.
+  owner: AccountAddress,
+  /// Number of votes needed for `finalize` to reach the quorum.
+  min_participation: VotingCount,
+  /// The options approved by each account with `voteMany`, counted apart
+  /// from the single-choice ballots.
+  approvals: BTreeMap<AccountAddress, BTreeSet<VotingIndex>>,
+  /// The account each account delegated its vote to with `delegate`.
+  delegations: BTreeMap<AccountAddress, AccountAddress>,
+  /// Whether the owner ended the vote before `end_time` with `close`.
+  closed: bool,
+}
+
+impl State {
+  /// Whether the vote ended, because `end_time` passed or it was closed.
+  fn is_finished(&self, now: Timestamp) -> bool {
+    self.closed || self.end_time < now
+  }
+
+  /// Position of the given option in `options`.
+  fn option_index(&self, voting_option: &VotingOption) -> Result<VotingIndex, ContractError> {
+    match self
+      .options
+      .iter()
+      .position(|option| option == voting_option)
+    {
+      Some(index) => Ok(index as u32),
+      None => Err(ContractError::InvalidVotingOption),
+    }
+  }
+
+  /// Record the ballot of `voter` with the given weight, which is only kept
+  /// for token-gated votes, returning the option of the replaced ballot.
+  /// Changing a ballot within `min_vote_interval_ms` of the previous one is
+  /// rejected.
+  fn cast(
+    &mut self,
+    voter: AccountAddress,
+    voting_index: VotingIndex,
+    weight: VotingCount,
+    now: Timestamp,
+  ) -> Result<Option<VotingIndex>, ContractError> {
+    let now = now.timestamp_millis();
+    if let Some(last_vote_at) = self.last_vote_at.get(&voter) {
+      if now < last_vote_at.saturating_add(self.min_vote_interval_ms) {
+        return Err(ContractError::VoteTooSoon);
+      }
+    }
+    let previous = self.ballots.insert(voter, voting_index);
+    if self.gating.is_some() {
+      self.weights.insert(voter, weight);
+    }
+    self.last_vote_at.insert(voter, now);
+    Ok(previous)
+  }
+
+  /// Number of approvals for each option, indexed like `options`. Every
+  /// approval counts with the weight of its voter.
+  fn approval_tally(&self) -> Vec<VotingCount> {
+    let mut counts: Vec<VotingCount> = vec![0; self.options.len()];
+    for (voter, voting_indices) in self.approvals.iter() {
+      let weight = self.weights.get(voter).copied().unwrap_or(1);
+      for voting_index in voting_indices {
+        counts[*voting_index as usize] = counts[*voting_index as usize].saturating_add(weight);
+      }
+    }
+    counts
+  }
+
+  /// The ballot the vote of `account` counts for: its own ballot, or else
+  /// the ballot its delegation chain ends in, if that is reached within
+  /// `MAX_DELEGATION_DEPTH` delegations.
+  fn resolve_ballot(&self, account: &AccountAddress) -> Option<VotingIndex> {
+    let mut current = account;
+    for _ in 0..=MAX_DELEGATION_DEPTH {
+      if let Some(voting_index) = self.ballots.get(current) {
+        return Some(*voting_index);
+      }
+      current = self.delegations.get(current)?;
+    }
+    None
+  }
+
+  /// Number of votes for each option, indexed like `options`. Every ballot
+  /// counts with the weight of its voter, and with the weights of the
+  /// accounts delegating to it without a ballot of their own.
+  fn tally(&self) -> Vec<VotingCount> {
+    if let Some(final_tally) = &self.final_tally {
+      return final_tally.clone();
+    }
+    let mut counts: Vec<VotingCount> = vec![0; self.options.len()];
+    let delegators = self
+      .delegations
+      .keys()
+      .filter(|delegator| !self.ballots.contains_key(delegator));
+    for voter in self.ballots.keys().chain(delegators) {
+      if let Some(voting_index) = self.resolve_ballot(voter) {
+        let weight = self.weights.get(voter).copied().unwrap_or(1);
+        counts[voting_index as usize] = counts[voting_index as usize].saturating_add(weight);
+      }
+    }
+    counts
+  }
 }
 
 #[derive(Serialize, SchemaType)]
-struct InitParameter {
-  description: String,
-  options: Vec<VotingOption>,
-  end_time: Timestamp,
+pub struct InitParameter {
+  pub description: String,
+  pub options: Vec<VotingOption>,
+  pub end_time: Timestamp,
+  pub tie_break: TieBreak,
+  pub auto_finalize: bool,
+  /// Minimum time in milliseconds before an account can change its ballot.
+  pub min_vote_interval_ms: u64,
+  /// Maximum number of options, including the options added later.
+  pub max_options: u32,
+  /// Deposit required to add an option, zero to add options for free.
+  pub option_deposit: Amount,
+  /// Number of votes an option needs after `end_time` for its deposit to be
+  /// refunded.
+  pub deposit_threshold: VotingCount,
+  /// Weigh each ballot by the voter's balance of the given CIS2 tokens at
+  /// vote time, `None` for one vote per account. Each token counts for a
+  /// single account, see `vote_weight`.
+  pub gating: Option<TokenGating>,
+  /// Number of votes, counted with their weights, needed for the vote to be
+  /// valid, 0 for no quorum.
+  pub min_participation: VotingCount,
+}
+
+/// Check that `option` can be added to the `options` of a vote.
+fn check_option(options: &[VotingOption], option: &VotingOption) -> Result<(), ContractError> {
+  if option.len() > MAX_OPTION_LENGTH {
+    return Err(ContractError::OptionTooLong);
+  }
+  if options.contains(option) {
+    return Err(ContractError::DuplicateOption);
+  }
+  Ok(())
 }
 
 /// Init function that creates a new smart contract.
-#[init(contract = "voting", parameter = "InitParameter")]
+/// Rejects without options, with duplicate options or with an option longer
+/// than `MAX_OPTION_LENGTH`.
+#[init(
+  contract = "voting",
+  parameter = "InitParameter",
+  event = "VotingEvent"
+)]
 fn init(ctx: &impl HasInitContext, _state_builder: &mut StateBuilder) -> InitResult<State> {
   let param: InitParameter = ctx.parameter_cursor().get()?;
+  if param.options.is_empty() {
+    return Err(ContractError::NoOptions.into());
+  }
+  if param.options.len() > param.max_options as usize {
+    return Err(ContractError::TooManyOptions.into());
+  }
+  for (index, option) in param.options.iter().enumerate() {
+    check_option(&param.options[..index], option)?;
+  }
 
   Ok(State {
     description: param.description,
     options: param.options,
     end_time: param.end_time,
     ballots: BTreeMap::new(),
+    tie_break: param.tie_break,
+    vote_delegates: BTreeMap::new(),
+    auto_finalize: param.auto_finalize,
+    final_tally: None,
+    min_vote_interval_ms: param.min_vote_interval_ms,
+    last_vote_at: BTreeMap::new(),
+    max_options: param.max_options,
+    option_deposit: param.option_deposit,
+    deposit_threshold: param.deposit_threshold,
+    option_deposits: BTreeMap::new(),
+    gating: param.gating,
+    weights: BTreeMap::new(),
+    counted_tokens: BTreeMap::new(),
+    owner: ctx.init_origin(),
+    min_participation: param.min_participation,
+    approvals: BTreeMap::new(),
+    delegations: BTreeMap::new(),
+    closed: false,
   })
 }
 
@@ -49,20 +272,102 @@ pub enum ContractError {
   VotingFinished,
   ContractVoter,
   InvalidVotingOption,
+  /// The sender is not the authorized delegate of the voter.
+  DelegateNotAuthorized,
+  /// The account changed its ballot within the minimum vote interval.
+  VoteTooSoon,
+  /// The number of options would exceed `max_options`.
+  TooManyOptions,
+  /// The sender is not the owner of the contract.
+  NotOwner,
+  /// The amount attached to `addOption` is not the option deposit.
+  WrongDeposit,
+  /// The vote has not ended yet.
+  VotingNotFinished,
+  /// The option has no unclaimed deposit.
+  NoOptionDeposit,
+  /// The option did not reach the deposit threshold.
+  DepositForfeited,
+  /// Failed to transfer the deposit to the proposer.
+  #[from(TransferError)]
+  TransferFailed,
+  /// Failed to log an event.
+  #[from(LogError)]
+  LogFailed,
+  /// The voter holds none of the gating tokens, or only tokens counted for
+  /// another account.
+  NoGatingTokens,
+  /// Failed to query the balance of the voter from the gating contract.
+  GatingQueryFailed,
+  /// The option is listed more than once.
+  DuplicateOption,
+  /// The vote is initialized without options.
+  NoOptions,
+  /// The option is longer than `MAX_OPTION_LENGTH`.
+  OptionTooLong,
+  /// The account delegated its vote to itself.
+  SelfDelegation,
+  /// The delegation would make the delegation chain loop back to the sender.
+  DelegationCycle,
+}
+
+impl<T> From<Cis2ClientError<T>> for ContractError {
+  fn from(_: Cis2ClientError<T>) -> Self {
+    ContractError::GatingQueryFailed
+  }
+}
+
+/// The weight of a ballot of `voter`: its balance of the gating tokens, or 1
+/// without token gating. The weight is queried when the ballot is cast and
+/// kept until it is cast again, so each gating token counts for a single
+/// account: the first one whose weight was queried while holding it, until
+/// the weight of that account is queried again without it. Rejects voters
+/// without any gating tokens that are not counted for another account.
+fn vote_weight(
+  host: &mut Host<State>,
+  voter: AccountAddress,
+) -> Result<VotingCount, ContractError> {
+  let Some(gating) = host.state().gating.clone() else {
+    return Ok(1);
+  };
+  let client = Cis2Client::new(gating.contract);
+  let mut balance: u64 = 0;
+  for token_id in gating.token_ids {
+    let amount: TokenAmountU64 =
+      client.balance_of::<State, _, _, ()>(host, token_id.clone(), Address::Account(voter))?;
+    let counted_tokens = &mut host.state_mut().counted_tokens;
+    let counted_for = counted_tokens.get(&token_id).copied();
+    if amount.0 == 0 {
+      if counted_for == Some(voter) {
+        counted_tokens.remove(&token_id);
+      }
+    } else if counted_for.unwrap_or(voter) == voter {
+      counted_tokens.insert(token_id, voter);
+      balance = balance.saturating_add(amount.0);
+    }
+  }
+  if balance == 0 {
+    return Err(ContractError::NoGatingTokens);
+  }
+  Ok(VotingCount::try_from(balance).unwrap_or(VotingCount::MAX))
 }
 
-/// Receive function. The input parameter is the boolean variable `throw_error`.
-///  If `throw_error == true`, the receive function will throw a custom error.
-///  If `throw_error == false`, the receive function executes successfully.
+/// Cast or change the ballot of the sender.
+/// Logs a `VoteCast` event.
 #[receive(
   contract = "voting",
   name = "vote",
   parameter = "VotingOption",
   error = "ContractError",
+  enable_logger,
   mutable
 )]
-fn vote(ctx: &ReceiveContext, host: &mut Host<State>) -> Result<(), ContractError> {
-  if host.state().end_time < ctx.metadata().slot_time() {
+fn vote(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> Result<(), ContractError> {
+  if host.state().is_finished(ctx.metadata().slot_time()) {
     return Err(ContractError::VotingFinished);
   }
   let acc = match ctx.sender() {
@@ -71,53 +376,711 @@ fn vote(ctx: &ReceiveContext, host: &mut Host<State>) -> Result<(), ContractErro
   };
 
   let voting_option: VotingOption = ctx.parameter_cursor().get()?;
-  let voting_index = match host
-    .state()
-    .options
+  let voting_index = host.state().option_index(&voting_option)?;
+  let weight = vote_weight(host, acc)?;
+
+  let previous = host
+    .state_mut()
+    .cast(acc, voting_index, weight, ctx.metadata().slot_time())?;
+  logger.log(&VotingEvent::VoteCast {
+    voter: acc,
+    option_index: voting_index,
+    previous,
+  })?;
+
+  Ok(())
+}
+
+/// Approve any number of options, replacing the options the sender approved
+/// before. Approvals are tallied by `viewApproval`, apart from the ballots of
+/// `vote`. In a token-gated vote the weight of the sender is queried as in
+/// `vote`.
+#[receive(
+  contract = "voting",
+  name = "voteMany",
+  parameter = "Vec<VotingOption>",
+  error = "ContractError",
+  mutable
+)]
+fn vote_many(ctx: &ReceiveContext, host: &mut Host<State>) -> Result<(), ContractError> {
+  if host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingFinished);
+  }
+  let acc = match ctx.sender() {
+    Address::Account(acc) => acc,
+    Address::Contract(_) => return Err(ContractError::ContractVoter),
+  };
+
+  let voting_options: Vec<VotingOption> = ctx.parameter_cursor().get()?;
+  let voting_indices = voting_options
     .iter()
-    .position(|option| *option == voting_option)
-  {
-    Some(index) => index as u32,
-    None => return Err(ContractError::InvalidVotingOption),
+    .map(|voting_option| host.state().option_index(voting_option))
+    .collect::<Result<BTreeSet<_>, _>>()?;
+  let weight = vote_weight(host, acc)?;
+
+  let state = host.state_mut();
+  state.approvals.insert(acc, voting_indices);
+  if state.gating.is_some() {
+    state.weights.insert(acc, weight);
+  }
+
+  Ok(())
+}
+
+/// Delegate the vote of the sender to another account, replacing any earlier
+/// delegation. Without a ballot of its own, the vote of the sender counts for
+/// the ballot of the delegate, or of the account the delegate delegates to,
+/// up to `MAX_DELEGATION_DEPTH` delegations away. In a token-gated vote the
+/// weight of the sender is queried as in `vote`.
+/// Logs a `DelegationChanged` event.
+#[receive(
+  contract = "voting",
+  name = "delegate",
+  parameter = "AccountAddress",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn delegate(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> Result<(), ContractError> {
+  if host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingFinished);
+  }
+  let acc = match ctx.sender() {
+    Address::Account(acc) => acc,
+    Address::Contract(_) => return Err(ContractError::ContractVoter),
   };
 
-  host
-    .state_mut()
-    .ballots
-    .entry(acc)
-    .and_modify(|old_voting_index| *old_voting_index = voting_index)
-    .or_insert(voting_index);
+  let target: AccountAddress = ctx.parameter_cursor().get()?;
+  if target == acc {
+    return Err(ContractError::SelfDelegation);
+  }
+  let weight = vote_weight(host, acc)?;
+  let state = host.state_mut();
+  // The delegations never form a cycle, so the chain from `target` ends.
+  let mut current = &target;
+  while let Some(next) = state.delegations.get(current) {
+    if *next == acc {
+      return Err(ContractError::DelegationCycle);
+    }
+    current = next;
+  }
+  let old_delegatee = state.delegations.insert(acc, target);
+  if state.gating.is_some() {
+    state.weights.insert(acc, weight);
+  }
+  logger.log(&VotingEvent::DelegationChanged {
+    delegator: acc,
+    old_delegatee,
+    new_delegatee: Some(target),
+  })?;
 
   Ok(())
 }
 
+/// Withdraw the delegation of the sender made with `delegate`, if any, so its
+/// vote only counts through its own ballot.
+/// Logs a `DelegationChanged` event when the sender had delegated its vote.
+#[receive(
+  contract = "voting",
+  name = "undelegate",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn undelegate(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> Result<(), ContractError> {
+  if host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingFinished);
+  }
+  let acc = match ctx.sender() {
+    Address::Account(acc) => acc,
+    Address::Contract(_) => return Err(ContractError::ContractVoter),
+  };
+
+  if let Some(old_delegatee) = host.state_mut().delegations.remove(&acc) {
+    logger.log(&VotingEvent::DelegationChanged {
+      delegator: acc,
+      old_delegatee: Some(old_delegatee),
+      new_delegatee: None,
+    })?;
+  }
+
+  Ok(())
+}
+
+/// Withdraw the ballot of the sender, so it no longer counts for any option.
+/// Succeeds without a ballot as well.
+/// Logs a `VoteRetracted` event when the sender had a ballot.
+#[receive(
+  contract = "voting",
+  name = "retract",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn retract(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> Result<(), ContractError> {
+  if host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingFinished);
+  }
+  let acc = match ctx.sender() {
+    Address::Account(acc) => acc,
+    Address::Contract(_) => return Err(ContractError::ContractVoter),
+  };
+
+  if let Some(option_index) = host.state_mut().ballots.remove(&acc) {
+    logger.log(&VotingEvent::VoteRetracted {
+      voter: acc,
+      option_index,
+    })?;
+  }
+
+  Ok(())
+}
+
+/// End the vote before `end_time`. Everything that is possible after
+/// `end_time` is possible once the vote is closed.
+/// Can only be called by the account that initialized the contract.
+#[receive(contract = "voting", name = "close", error = "ContractError", mutable)]
+fn close(ctx: &ReceiveContext, host: &mut Host<State>) -> Result<(), ContractError> {
+  if !ctx.sender().matches_account(&host.state().owner) {
+    return Err(ContractError::NotOwner);
+  }
+  if host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingFinished);
+  }
+
+  host.state_mut().closed = true;
+
+  Ok(())
+}
+
+/// The parameter of `setWeight`.
 #[derive(Serialize, SchemaType)]
-struct VotingView {
-  description: String,
-  options: Vec<VotingOption>,
-  end_time: Timestamp,
-  tally: BTreeMap<VotingOption, VotingCount>,
+pub struct SetWeightParameter {
+  pub voter: AccountAddress,
+  /// The number of votes the ballot of `voter` counts for.
+  pub weight: VotingCount,
+}
+
+/// Set the weight of the ballot of an account while the vote is open, for
+/// example its staked amount. Applies to a ballot already cast as well.
+/// In a token-gated vote the weight is replaced by the token balance when
+/// the account casts its ballot.
+/// Can only be called by the account that initialized the contract.
+#[receive(
+  contract = "voting",
+  name = "setWeight",
+  parameter = "SetWeightParameter",
+  error = "ContractError",
+  mutable
+)]
+fn set_weight(ctx: &ReceiveContext, host: &mut Host<State>) -> Result<(), ContractError> {
+  if !ctx.sender().matches_account(&host.state().owner) {
+    return Err(ContractError::NotOwner);
+  }
+  if host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingFinished);
+  }
+
+  let param: SetWeightParameter = ctx.parameter_cursor().get()?;
+  host.state_mut().weights.insert(param.voter, param.weight);
+
+  Ok(())
+}
+
+/// Add an option to the vote while it is open.
+/// Can be called by any account, which must attach exactly the
+/// `option_deposit` and is recorded as the proposer of the option. The
+/// deposit is refunded by `claimOptionDeposit`.
+/// Rejects options that are listed already or longer than
+/// `MAX_OPTION_LENGTH`.
+#[receive(
+  contract = "voting",
+  name = "addOption",
+  parameter = "VotingOption",
+  error = "ContractError",
+  payable,
+  mutable
+)]
+fn add_option(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  amount: Amount,
+) -> Result<(), ContractError> {
+  let proposer = match ctx.sender() {
+    Address::Account(acc) => acc,
+    Address::Contract(_) => return Err(ContractError::ContractVoter),
+  };
+  if amount != host.state().option_deposit {
+    return Err(ContractError::WrongDeposit);
+  }
+  if host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingFinished);
+  }
+
+  let voting_option: VotingOption = ctx.parameter_cursor().get()?;
+  let state = host.state_mut();
+  if state.options.len() >= state.max_options as usize {
+    return Err(ContractError::TooManyOptions);
+  }
+  check_option(&state.options, &voting_option)?;
+  state.options.push(voting_option);
+  if amount > Amount::zero() {
+    let voting_index = state.options.len() as VotingIndex - 1;
+    state
+      .option_deposits
+      .insert(voting_index, (proposer, amount));
+  }
+
+  Ok(())
+}
+
+/// Refund the deposit of an option to its proposer once the vote has ended,
+/// if the option received at least `deposit_threshold` votes. The deposit of
+/// an option below the threshold is forfeited and can be withdrawn by the owner
+/// with `withdrawForfeitedDeposits`.
+/// Can be called by anyone.
+#[receive(
+  contract = "voting",
+  name = "claimOptionDeposit",
+  parameter = "VotingOption",
+  error = "ContractError",
+  mutable
+)]
+fn claim_option_deposit(ctx: &ReceiveContext, host: &mut Host<State>) -> Result<(), ContractError> {
+  if !host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingNotFinished);
+  }
+
+  let voting_option: VotingOption = ctx.parameter_cursor().get()?;
+  let state = host.state_mut();
+  let voting_index = state.option_index(&voting_option)?;
+  let (proposer, deposit) = match state.option_deposits.get(&voting_index) {
+    Some(option_deposit) => *option_deposit,
+    None => return Err(ContractError::NoOptionDeposit),
+  };
+  if state.tally()[voting_index as usize] < state.deposit_threshold {
+    return Err(ContractError::DepositForfeited);
+  }
+  state.option_deposits.remove(&voting_index);
+  host.invoke_transfer(&proposer, deposit)?;
+
+  Ok(())
+}
+
+/// Transfer the deposits of every option below `deposit_threshold` to the
+/// owner once the vote has ended, returning the amount withdrawn.
+/// Can only be called by the account that initialized the contract.
+#[receive(
+  contract = "voting",
+  name = "withdrawForfeitedDeposits",
+  return_value = "Amount",
+  error = "ContractError",
+  mutable
+)]
+fn withdraw_forfeited_deposits(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+) -> Result<Amount, ContractError> {
+  let owner = host.state().owner;
+  if !ctx.sender().matches_account(&owner) {
+    return Err(ContractError::NotOwner);
+  }
+  if !host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingNotFinished);
+  }
+
+  let state = host.state_mut();
+  let tally = state.tally();
+  let threshold = state.deposit_threshold;
+  let mut forfeited = Amount::zero();
+  state.option_deposits.retain(|voting_index, (_, deposit)| {
+    if tally[*voting_index as usize] >= threshold {
+      return true;
+    }
+    forfeited += *deposit;
+    false
+  });
+  if forfeited > Amount::zero() {
+    host.invoke_transfer(&owner, forfeited)?;
+  }
+
+  Ok(forfeited)
+}
+
+/// Events logged by the contract.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub enum VotingEvent {
+  /// The account `delegator` delegated its vote to with `delegate` changed,
+  /// `None` meaning no delegation.
+  DelegationChanged {
+    delegator: AccountAddress,
+    old_delegatee: Option<AccountAddress>,
+    new_delegatee: Option<AccountAddress>,
+  },
+  /// `voter` cast a ballot for `option_index`, replacing its ballot for
+  /// `previous` if any.
+  VoteCast {
+    voter: AccountAddress,
+    option_index: VotingIndex,
+    previous: Option<VotingIndex>,
+  },
+  /// `voter` withdrew its ballot for `option_index` with `retract`.
+  VoteRetracted {
+    voter: AccountAddress,
+    option_index: VotingIndex,
+  },
+  /// The delegate `voter` authorized to cast its ballot through `voteBatch`
+  /// changed, `None` meaning no delegate.
+  DelegateAuthorized {
+    voter: AccountAddress,
+    old_delegate: Option<AccountAddress>,
+    new_delegate: Option<AccountAddress>,
+  },
+}
+
+/// Authorize an account to cast ballots on behalf of the sender through
+/// `voteBatch`. Replaces any previously authorized delegate.
+/// Logs a `DelegateAuthorized` event.
+#[receive(
+  contract = "voting",
+  name = "authorizeDelegate",
+  parameter = "AccountAddress",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn authorize_delegate(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> Result<(), ContractError> {
+  let acc = match ctx.sender() {
+    Address::Account(acc) => acc,
+    Address::Contract(_) => return Err(ContractError::ContractVoter),
+  };
+
+  let delegate: AccountAddress = ctx.parameter_cursor().get()?;
+  let old_delegate = host.state_mut().vote_delegates.insert(acc, delegate);
+  logger.log(&VotingEvent::DelegateAuthorized {
+    voter: acc,
+    old_delegate,
+    new_delegate: Some(delegate),
+  })?;
+
+  Ok(())
+}
+
+/// Revoke the delegate authorized by the sender, if any.
+/// Logs a `DelegateAuthorized` event when a delegate was authorized.
+#[receive(
+  contract = "voting",
+  name = "revokeDelegate",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn revoke_delegate(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> Result<(), ContractError> {
+  let acc = match ctx.sender() {
+    Address::Account(acc) => acc,
+    Address::Contract(_) => return Err(ContractError::ContractVoter),
+  };
+
+  if let Some(old_delegate) = host.state_mut().vote_delegates.remove(&acc) {
+    logger.log(&VotingEvent::DelegateAuthorized {
+      voter: acc,
+      old_delegate: Some(old_delegate),
+      new_delegate: None,
+    })?;
+  }
+
+  Ok(())
+}
+
+/// Ballots cast by a delegate, each a voter together with its chosen option.
+pub type VoteBatchParameter = Vec<(AccountAddress, VotingOption)>;
+
+/// Cast a ballot for each voter in the batch. The sender must be the delegate
+/// authorized by every voter in the batch, otherwise nothing is recorded.
+/// Logs a `VoteCast` event per ballot.
+#[receive(
+  contract = "voting",
+  name = "voteBatch",
+  parameter = "VoteBatchParameter",
+  error = "ContractError",
+  enable_logger,
+  mutable
+)]
+fn vote_batch(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  logger: &mut Logger,
+) -> Result<(), ContractError> {
+  if host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingFinished);
+  }
+  let delegate = match ctx.sender() {
+    Address::Account(acc) => acc,
+    Address::Contract(_) => return Err(ContractError::ContractVoter),
+  };
+
+  let ballots: VoteBatchParameter = ctx.parameter_cursor().get()?;
+  for (voter, voting_option) in ballots {
+    if host.state().vote_delegates.get(&voter) != Some(&delegate) {
+      return Err(ContractError::DelegateNotAuthorized);
+    }
+    let voting_index = host.state().option_index(&voting_option)?;
+    let weight = vote_weight(host, voter)?;
+    let previous =
+      host
+        .state_mut()
+        .cast(voter, voting_index, weight, ctx.metadata().slot_time())?;
+    logger.log(&VotingEvent::VoteCast {
+      voter,
+      option_index: voting_index,
+      previous,
+    })?;
+  }
+
+  Ok(())
+}
+
+#[derive(Serialize, SchemaType, Debug)]
+pub struct VotingView {
+  pub description: String,
+  pub options: Vec<VotingOption>,
+  pub end_time: Timestamp,
+  pub tally: BTreeMap<VotingOption, VotingCount>,
+  /// Whether the tally is locked.
+  pub finalized: bool,
 }
 /// View function that returns the content of the state.
-#[receive(contract = "voting", name = "view", return_value = "VotingView")]
-fn view<'b>(_ctx: &ReceiveContext, host: &'b Host<State>) -> ReceiveResult<VotingView> {
+/// With `auto_finalize` set, the first call after `end_time` locks the tally
+/// returned by all later calls.
+#[receive(
+  contract = "voting",
+  name = "view",
+  return_value = "VotingView",
+  mutable
+)]
+fn view(ctx: &ReceiveContext, host: &mut Host<State>) -> ReceiveResult<VotingView> {
+  let state = host.state_mut();
+  if state.auto_finalize
+    && state.final_tally.is_none()
+    && state.is_finished(ctx.metadata().slot_time())
+  {
+    state.final_tally = Some(state.tally());
+  }
   let state = host.state();
   let description = state.description.clone();
   let options = state.options.clone();
   let end_time = state.end_time;
-  let mut tally = BTreeMap::new();
+  let tally = options
+    .iter()
+    .cloned()
+    .zip(state.tally())
+    .filter(|(_, count)| *count > 0)
+    .collect();
 
-  for (_, voting_index) in state.ballots.iter() {
-    let voting_option = options[*voting_index as usize].clone();
-    tally
-      .entry(voting_option)
-      .and_modify(|count| *count += 1)
-      .or_insert(1);
-  }
   Ok(VotingView {
     description,
     options,
     end_time,
     tally,
+    finalized: state.final_tally.is_some(),
+  })
+}
+
+/// Whether ballots can still be cast.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VotingStatus {
+  Open,
+  Finished,
+}
+
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct VotingStatusView {
+  pub status: VotingStatus,
+  /// Milliseconds until `end_time`, 0 once voting is finished.
+  pub ends_in_ms: u64,
+  /// Number of accounts that cast a ballot.
+  pub total_ballots: u32,
+}
+
+/// View function that returns the status of the vote, the time remaining and
+/// the number of ballots cast.
+#[receive(
+  contract = "voting",
+  name = "votingStatus",
+  return_value = "VotingStatusView"
+)]
+fn voting_status(ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<VotingStatusView> {
+  let state = host.state();
+  let slot_time = ctx.metadata().slot_time();
+  let (status, ends_in_ms) = if state.is_finished(slot_time) {
+    (VotingStatus::Finished, 0)
+  } else {
+    (
+      VotingStatus::Open,
+      state.end_time.timestamp_millis() - slot_time.timestamp_millis(),
+    )
+  };
+
+  Ok(VotingStatusView {
+    status,
+    ends_in_ms,
+    total_ballots: state.ballots.len() as u32,
+  })
+}
+
+/// Every option together with its number of votes, in the order of `options`.
+pub type FullTally = Vec<(VotingOption, VotingCount)>;
+
+/// View function that returns the count of every option, including the
+/// options without votes.
+#[receive(contract = "voting", name = "fullTally", return_value = "FullTally")]
+fn full_tally(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<FullTally> {
+  let state = host.state();
+  Ok(state.options.iter().cloned().zip(state.tally()).collect())
+}
+
+/// View function that returns the approvals of every option cast with
+/// `voteMany`, including the options without approvals.
+#[receive(contract = "voting", name = "viewApproval", return_value = "FullTally")]
+fn view_approval(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<FullTally> {
+  let state = host.state();
+  Ok(
+    state
+      .options
+      .iter()
+      .cloned()
+      .zip(state.approval_tally())
+      .collect(),
+  )
+}
+
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct WinnerResponse {
+  /// The winning option, `None` if no votes were cast or the tie could not
+  /// be broken.
+  pub option: Option<VotingOption>,
+  /// The number of votes for the winning option(s).
+  pub count: VotingCount,
+  /// Whether two or more options share the highest count.
+  pub tied: bool,
+}
+
+/// View function that returns the option with the most votes. Ties are
+/// resolved according to the `tie_break` mode set at init.
+#[receive(
+  contract = "voting",
+  name = "winner",
+  return_value = "WinnerResponse",
+  crypto_primitives
+)]
+fn winner(
+  _ctx: &ReceiveContext,
+  host: &Host<State>,
+  crypto_primitives: &impl HasCryptoPrimitives,
+) -> ReceiveResult<WinnerResponse> {
+  Ok(winner_of(host.state(), crypto_primitives))
+}
+
+/// The option with the most votes, see `winner`.
+fn winner_of(state: &State, crypto_primitives: &impl HasCryptoPrimitives) -> WinnerResponse {
+  let tally = state.tally();
+  let count = tally.iter().copied().max().unwrap_or(0);
+  if count == 0 {
+    return WinnerResponse {
+      option: None,
+      count,
+      tied: false,
+    };
+  }
+
+  let leaders: Vec<usize> = (0..tally.len()).filter(|i| tally[*i] == count).collect();
+  let tied = leaders.len() > 1;
+  let winner_index = match (tied, state.tie_break) {
+    (false, _) | (true, TieBreak::LowestIndex) => Some(leaders[0]),
+    (true, TieBreak::ReturnNone) => None,
+    (true, TieBreak::HashSeed) => {
+      let hash = crypto_primitives.hash_sha2_256(&to_bytes(&state.ballots));
+      let seed = u32::from_le_bytes([hash.0[0], hash.0[1], hash.0[2], hash.0[3]]);
+      Some(leaders[seed as usize % leaders.len()])
+    }
+  };
+
+  WinnerResponse {
+    option: winner_index.map(|index| state.options[index].clone()),
+    count,
+    tied,
+  }
+}
+
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct FinalizeResult {
+  /// Whether the votes reached `min_participation`.
+  pub reached_quorum: bool,
+  /// The number of votes, counted with their weights.
+  pub total_votes: VotingCount,
+  /// The winning option as returned by `winner`, `None` without a quorum.
+  pub winner: Option<VotingOption>,
+}
+
+/// Lock the tally once the vote has ended and return whether it reached the
+/// quorum and its winner. Can be called by anyone, the result does not
+/// change after the first call.
+#[receive(
+  contract = "voting",
+  name = "finalize",
+  return_value = "FinalizeResult",
+  error = "ContractError",
+  crypto_primitives,
+  mutable
+)]
+fn finalize(
+  ctx: &ReceiveContext,
+  host: &mut Host<State>,
+  crypto_primitives: &impl HasCryptoPrimitives,
+) -> Result<FinalizeResult, ContractError> {
+  if !host.state().is_finished(ctx.metadata().slot_time()) {
+    return Err(ContractError::VotingNotFinished);
+  }
+
+  let state = host.state_mut();
+  if state.final_tally.is_none() {
+    state.final_tally = Some(state.tally());
+  }
+  let state = host.state();
+  let total_votes = state
+    .tally()
+    .iter()
+    .fold(0, |total: VotingCount, count| total.saturating_add(*count));
+  let reached_quorum = total_votes >= state.min_participation;
+  let winner = if reached_quorum {
+    winner_of(state, crypto_primitives).option
+  } else {
+    None
+  };
+
+  Ok(FinalizeResult {
+    reached_quorum,
+    total_votes,
+    winner,
   })
 }
@@ -1,101 +1,1379 @@
+use concordium_cis2::{AdditionalData, Receiver, TokenIdU32, TokenIdVec, Transfer, TransferParams};
 use concordium_smart_contract_testing::*;
+use concordium_std::{MetadataUrl, Serial};
+use std::collections::BTreeMap;
 use voting::*;
 
-/// A test account.
+/// Test accounts.
 const ALICE: AccountAddress = AccountAddress([0u8; 32]);
 const ALICE_ADDR: Address = Address::Account(ALICE);
+const BOB: AccountAddress = AccountAddress([1u8; 32]);
+const CHARLIE: AccountAddress = AccountAddress([2u8; 32]);
+const DAVE: AccountAddress = AccountAddress([3u8; 32]);
 
-/// The initial balance of the ALICE test account.
+/// The initial balance of the test accounts.
 const ACC_INITIAL_BALANCE: Amount = Amount::from_ccd(10_000);
 
 /// A [`Signer`] with one set of keys, used for signing transactions.
 const SIGNER: Signer = Signer::with_one_key();
 
-/// Test that invoking the `receive` endpoint with the `false` parameter
-/// succeeds in updating the contract.
+/// The end of the voting period.
+const END_TIME: u64 = 1000;
+
+/// The maximum number of options.
+const MAX_OPTIONS: u32 = 64;
+
+/// The deposit required to add an option in `test_option_deposit`.
+const OPTION_DEPOSIT: Amount = Amount::from_ccd(100);
+
+/// Test that there is no winner before any votes are cast.
+#[test]
+fn test_winner_no_votes() {
+  let (chain, init) = initialize(TieBreak::LowestIndex);
+
+  let winner = get_winner(&chain, init.contract_address);
+  assert_eq!(
+    winner,
+    WinnerResponse {
+      option: None,
+      count: 0,
+      tied: false,
+    }
+  );
+}
+
+/// Test that the option with the most votes wins.
+#[test]
+fn test_winner_clear_winner() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  vote(&mut chain, init.contract_address, ALICE, "C").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, BOB, "C").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, CHARLIE, "A").expect("Vote succeeds");
+
+  let winner = get_winner(&chain, init.contract_address);
+  assert_eq!(
+    winner,
+    WinnerResponse {
+      option: Some("C".to_string()),
+      count: 2,
+      tied: false,
+    }
+  );
+}
+
+/// Test that a tie has no winner in `ReturnNone` mode.
+#[test]
+fn test_winner_tie_return_none() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  cast_tie(&mut chain, init.contract_address);
+
+  let winner = get_winner(&chain, init.contract_address);
+  assert_eq!(
+    winner,
+    WinnerResponse {
+      option: None,
+      count: 1,
+      tied: true,
+    }
+  );
+}
+
+/// Test that a tie is won by the first listed option in `LowestIndex` mode.
+#[test]
+fn test_winner_tie_lowest_index() {
+  let (mut chain, init) = initialize(TieBreak::LowestIndex);
+  cast_tie(&mut chain, init.contract_address);
+
+  let winner = get_winner(&chain, init.contract_address);
+  assert_eq!(
+    winner,
+    WinnerResponse {
+      option: Some("B".to_string()),
+      count: 1,
+      tied: true,
+    }
+  );
+}
+
+/// Test that a tie is deterministically won by one of the tied options in
+/// `HashSeed` mode.
 #[test]
-fn test_throw_no_error() {
-    let (mut chain, init) = initialize();
+fn test_winner_tie_hash_seed() {
+  let (mut chain, init) = initialize(TieBreak::HashSeed);
+  cast_tie(&mut chain, init.contract_address);
+
+  let winner = get_winner(&chain, init.contract_address);
+  assert!(winner.tied);
+  assert_eq!(winner.count, 1);
+  let option = winner.option.clone().expect("Tie is broken");
+  assert!(option == "B" || option == "C");
+  // The same ballots always resolve to the same winner.
+  assert_eq!(get_winner(&chain, init.contract_address), winner);
+}
+
+/// Test that a delegate casts the ballots of all accounts that authorized it
+/// in a single transaction.
+#[test]
+fn test_vote_batch() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  for voter in [ALICE, BOB, CHARLIE] {
+    update(
+      &mut chain,
+      init.contract_address,
+      voter,
+      "authorizeDelegate",
+      &DAVE,
+    )
+    .expect("Authorize delegate");
+  }
+
+  let ballots: VoteBatchParameter = vec![
+    (ALICE, "A".to_string()),
+    (BOB, "B".to_string()),
+    (CHARLIE, "B".to_string()),
+  ];
+  update(
+    &mut chain,
+    init.contract_address,
+    DAVE,
+    "voteBatch",
+    &ballots,
+  )
+  .expect("Vote batch succeeds");
+
+  let view = get_view(&chain, init.contract_address);
+  assert_eq!(
+    view.tally,
+    BTreeMap::from([("A".to_string(), 1), ("B".to_string(), 2)])
+  );
+}
+
+/// Test that a ballot logs its option, and the option it replaces when a
+/// ballot is changed.
+#[test]
+fn test_vote_cast_events() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  let mut events = Vec::new();
+  for option in ["B", "C"] {
+    let update = vote(&mut chain, init.contract_address, ALICE, option).expect("Vote succeeds");
+    events.extend(
+      update
+        .events()
+        .flat_map(|(_addr, events)| events)
+        .map(|event| event.parse::<VotingEvent>().expect("Deserialize event")),
+    );
+  }
+
+  assert_eq!(
+    events,
+    vec![
+      VotingEvent::VoteCast {
+        voter: ALICE,
+        option_index: 1,
+        previous: None,
+      },
+      VotingEvent::VoteCast {
+        voter: ALICE,
+        option_index: 2,
+        previous: Some(1),
+      },
+    ]
+  );
+}
+
+/// Test that authorizing a delegate, replacing it and revoking it log the
+/// delegates before and after the change.
+#[test]
+fn test_delegate_authorized_events() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  let mut changes = Vec::new();
+  for (entrypoint, delegate) in [
+    ("authorizeDelegate", Some(BOB)),
+    ("authorizeDelegate", Some(CHARLIE)),
+    ("revokeDelegate", None),
+    ("revokeDelegate", None),
+  ] {
+    let update = match delegate {
+      Some(delegate) => update(
+        &mut chain,
+        init.contract_address,
+        ALICE,
+        entrypoint,
+        &delegate,
+      ),
+      None => update(&mut chain, init.contract_address, ALICE, entrypoint, &()),
+    }
+    .expect("Update delegate succeeds");
+    changes.extend(
+      update
+        .events()
+        .flat_map(|(_addr, events)| events)
+        .map(|event| event.parse::<VotingEvent>().expect("Deserialize event")),
+    );
+  }
+
+  assert_eq!(
+    changes,
+    vec![
+      VotingEvent::DelegateAuthorized {
+        voter: ALICE,
+        old_delegate: None,
+        new_delegate: Some(BOB),
+      },
+      VotingEvent::DelegateAuthorized {
+        voter: ALICE,
+        old_delegate: Some(BOB),
+        new_delegate: Some(CHARLIE),
+      },
+      VotingEvent::DelegateAuthorized {
+        voter: ALICE,
+        old_delegate: Some(CHARLIE),
+        new_delegate: None,
+      },
+    ]
+  );
+}
+
+/// Test that delegating a vote, re-delegating it and undelegating log the
+/// delegatees before and after the change.
+#[test]
+fn test_delegate_events() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  let mut changes = Vec::new();
+  for delegate in [Some(BOB), Some(CHARLIE), None, None] {
+    let update = match delegate {
+      Some(delegate) => update(
+        &mut chain,
+        init.contract_address,
+        ALICE,
+        "delegate",
+        &delegate,
+      ),
+      None => update(&mut chain, init.contract_address, ALICE, "undelegate", &()),
+    }
+    .expect("Update delegation succeeds");
+    changes.extend(
+      update
+        .events()
+        .flat_map(|(_addr, events)| events)
+        .map(|event| event.parse::<VotingEvent>().expect("Deserialize event")),
+    );
+  }
+
+  assert_eq!(
+    changes,
+    vec![
+      VotingEvent::DelegationChanged {
+        delegator: ALICE,
+        old_delegatee: None,
+        new_delegatee: Some(BOB),
+      },
+      VotingEvent::DelegationChanged {
+        delegator: ALICE,
+        old_delegatee: Some(BOB),
+        new_delegatee: Some(CHARLIE),
+      },
+      VotingEvent::DelegationChanged {
+        delegator: ALICE,
+        old_delegatee: Some(CHARLIE),
+        new_delegatee: None,
+      },
+    ]
+  );
+  vote(&mut chain, init.contract_address, CHARLIE, "A").expect("Vote succeeds");
+  assert_eq!(
+    get_view(&chain, init.contract_address).tally,
+    BTreeMap::from([("A".to_string(), 1)])
+  );
+}
+
+/// Test that a delegate cannot vote for an account that did not authorize it.
+#[test]
+fn test_vote_batch_unauthorized_voter() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  update(
+    &mut chain,
+    init.contract_address,
+    ALICE,
+    "authorizeDelegate",
+    &DAVE,
+  )
+  .expect("Authorize delegate");
+
+  let ballots: VoteBatchParameter = vec![(ALICE, "A".to_string()), (BOB, "B".to_string())];
+  let update = update(
+    &mut chain,
+    init.contract_address,
+    DAVE,
+    "voteBatch",
+    &ballots,
+  )
+  .expect_err("Vote batch fails");
+
+  let error: ContractError = update.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::DelegateNotAuthorized);
+  assert!(get_view(&chain, init.contract_address).tally.is_empty());
+}
+
+/// Test that the first view after the end time locks the tally when
+/// `auto_finalize` is set.
+#[test]
+fn test_view_auto_finalize() {
+  let (mut chain, init) = initialize_with_params(InitParameter {
+    description: "Test vote".to_string(),
+    options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+    end_time: Timestamp::from_timestamp_millis(END_TIME),
+    tie_break: TieBreak::ReturnNone,
+    auto_finalize: true,
+    min_vote_interval_ms: 0,
+    max_options: MAX_OPTIONS,
+    option_deposit: Amount::zero(),
+    deposit_threshold: 0,
+    gating: None,
+    min_participation: 0,
+  });
+  vote(&mut chain, init.contract_address, ALICE, "A").expect("Vote succeeds");
+  update(&mut chain, init.contract_address, BOB, "view", &()).expect("View succeeds");
+  assert!(!get_view(&chain, init.contract_address).finalized);
+
+  chain
+    .tick_block_time(Duration::from_millis(END_TIME + 1))
+    .expect("Advance block time");
+  let view: VotingView = update(&mut chain, init.contract_address, BOB, "view", &())
+    .expect("View succeeds")
+    .parse_return_value()
+    .expect("VotingView return value");
+  assert!(view.finalized);
+  assert_eq!(view.tally, BTreeMap::from([("A".to_string(), 1)]));
+
+  let update = vote(&mut chain, init.contract_address, BOB, "A").expect_err("Vote fails");
+  let error: ContractError = update.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::VotingFinished);
+  let view = get_view(&chain, init.contract_address);
+  assert!(view.finalized);
+  assert_eq!(view.tally, BTreeMap::from([("A".to_string(), 1)]));
+}
+
+/// Test that a delegated vote counts for the ballot at the end of the
+/// delegation chain unless the delegator votes itself.
+#[test]
+fn test_delegate_vote() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  for (delegator, delegate) in [(BOB, ALICE), (CHARLIE, BOB), (DAVE, BOB)] {
+    update(
+      &mut chain,
+      init.contract_address,
+      delegator,
+      "delegate",
+      &delegate,
+    )
+    .expect("Delegate succeeds");
+  }
+  vote(&mut chain, init.contract_address, ALICE, "A").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, DAVE, "B").expect("Vote succeeds");
+
+  let view = get_view(&chain, init.contract_address);
+  assert_eq!(
+    view.tally,
+    BTreeMap::from([("A".to_string(), 3), ("B".to_string(), 1)])
+  );
+}
+
+/// Test that delegating to oneself and delegations that close a cycle are
+/// rejected.
+#[test]
+fn test_delegate_rejects_cycle() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  for (delegator, delegate, expected) in [
+    (ALICE, ALICE, Some(ContractError::SelfDelegation)),
+    (ALICE, BOB, None),
+    (BOB, CHARLIE, None),
+    (CHARLIE, ALICE, Some(ContractError::DelegationCycle)),
+    (BOB, ALICE, Some(ContractError::DelegationCycle)),
+  ] {
+    let result = update(
+      &mut chain,
+      init.contract_address,
+      delegator,
+      "delegate",
+      &delegate,
+    );
+    let error = result
+      .err()
+      .map(|update| update.parse_return_value().expect("ContractError"));
+    assert_eq!(error, expected);
+  }
+}
+
+/// Test that only the owner can close the vote early, and that ballots are
+/// rejected once it is closed.
+#[test]
+fn test_close_early() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  vote(&mut chain, init.contract_address, BOB, "A").expect("Vote succeeds");
+  let error: ContractError = update(&mut chain, init.contract_address, BOB, "close", &())
+    .expect_err("Close by non-owner fails")
+    .parse_return_value()
+    .expect("ContractError");
+  assert_eq!(error, ContractError::NotOwner);
+
+  update(&mut chain, init.contract_address, ALICE, "close", &()).expect("Close succeeds");
+  let error: ContractError = vote(&mut chain, init.contract_address, CHARLIE, "B")
+    .expect_err("Vote after close fails")
+    .parse_return_value()
+    .expect("ContractError");
+  assert_eq!(error, ContractError::VotingFinished);
+  assert_eq!(
+    get_voting_status(&chain, init.contract_address),
+    VotingStatusView {
+      status: VotingStatus::Finished,
+      ends_in_ms: 0,
+      total_ballots: 1,
+    }
+  );
+}
+
+/// Test that initialization rejects duplicate options, no options and too
+/// long options.
+#[test]
+fn test_init_rejects_invalid_options() {
+  let param = |options: Vec<String>| InitParameter {
+    description: "Test vote".to_string(),
+    options,
+    end_time: Timestamp::from_timestamp_millis(END_TIME),
+    tie_break: TieBreak::ReturnNone,
+    auto_finalize: false,
+    min_vote_interval_ms: 0,
+    max_options: MAX_OPTIONS,
+    option_deposit: Amount::zero(),
+    deposit_threshold: 0,
+    gating: None,
+    min_participation: 0,
+  };
+  // The reject reasons of `DuplicateOption`, `NoOptions` and `OptionTooLong`,
+  // the 17th to 19th variants of `ContractError`.
+  for (options, reason) in [
+    (vec!["A".to_string(), "A".to_string()], -17),
+    (Vec::new(), -18),
+    (vec!["A".repeat(MAX_OPTION_LENGTH + 1)], -19),
+  ] {
+    let error =
+      try_initialize_contract(&mut initialize_chain(), param(options)).expect_err("Init fails");
+    assert!(
+      matches!(
+        error.kind,
+        ContractInitErrorKind::ExecutionError {
+          error: InitExecutionError::Reject { reason: r, .. }
+        } if r == reason
+      ),
+      "Reject reason {}",
+      reason
+    );
+  }
+
+  try_initialize_contract(
+    &mut initialize_chain(),
+    param(vec!["A".to_string(), "A".repeat(MAX_OPTION_LENGTH)]),
+  )
+  .expect("Init with distinct options succeeds");
+}
+
+/// Test that `finalize` is rejected before `end_time`, and after it reports
+/// the winner only if the votes reached the quorum.
+#[test]
+fn test_finalize_quorum() {
+  for (voters, expected) in [
+    (
+      &[ALICE, BOB][..],
+      FinalizeResult {
+        reached_quorum: false,
+        total_votes: 2,
+        winner: None,
+      },
+    ),
+    (
+      &[ALICE, BOB, CHARLIE][..],
+      FinalizeResult {
+        reached_quorum: true,
+        total_votes: 3,
+        winner: Some("A".to_string()),
+      },
+    ),
+  ] {
+    let (mut chain, init) = initialize_with_params(InitParameter {
+      description: "Test vote".to_string(),
+      options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+      end_time: Timestamp::from_timestamp_millis(END_TIME),
+      tie_break: TieBreak::ReturnNone,
+      auto_finalize: false,
+      min_vote_interval_ms: 0,
+      max_options: MAX_OPTIONS,
+      option_deposit: Amount::zero(),
+      deposit_threshold: 0,
+      gating: None,
+      min_participation: 3,
+    });
+    for voter in voters {
+      vote(&mut chain, init.contract_address, *voter, "A").expect("Vote succeeds");
+    }
+    let error: ContractError = update(&mut chain, init.contract_address, BOB, "finalize", &())
+      .expect_err("Finalize fails")
+      .parse_return_value()
+      .expect("ContractError");
+    assert_eq!(error, ContractError::VotingNotFinished);
 
-    // Update the contract via the `receive` entrypoint with the parameter `false`.
     chain
-        .contract_update(
-            SIGNER,
-            ALICE,
-            ALICE_ADDR,
-            Energy::from(10_000),
-            UpdateContractPayload {
-                address: init.contract_address,
-                amount: Amount::zero(),
-                receive_name: OwnedReceiveName::new_unchecked("voting.receive".to_string()),
-                message: OwnedParameter::from_serial(&false).expect("Parameter within size bounds"),
-            },
-        )
-        .expect("Update succeeds with `false` as input.");
-}
-
-/// Test that invoking the `receive` endpoint with the `true` parameter
-/// results in the `YourError` being thrown.
-#[test]
-fn test_throw_error() {
-    let (mut chain, init) = initialize();
-
-    // Update the contract via the `receive` entrypoint with the parameter `true`.
-    let update = chain
-        .contract_update(
-            SIGNER,
-            ALICE,
-            ALICE_ADDR,
-            Energy::from(10_000),
-            UpdateContractPayload {
-                address: init.contract_address,
-                amount: Amount::zero(),
-                receive_name: OwnedReceiveName::new_unchecked("voting.receive".to_string()),
-                message: OwnedParameter::from_serial(&true).expect("Parameter within size bounds"),
-            },
-        )
-        .expect_err("Update fails with `true` as input.");
-
-    // Check that the contract returned `YourError`.
-    let error: Error = update.parse_return_value().expect("Deserialize `Error`");
-    assert_eq!(error, Error::YourError);
+      .tick_block_time(Duration::from_millis(END_TIME + 1))
+      .expect("Advance block time");
+    let result: FinalizeResult = update(&mut chain, init.contract_address, BOB, "finalize", &())
+      .expect("Finalize succeeds")
+      .parse_return_value()
+      .expect("FinalizeResult return value");
+    assert_eq!(result, expected);
+    assert!(get_view(&chain, init.contract_address).finalized);
+  }
+}
+
+/// Test that `fullTally` includes the options without votes.
+#[test]
+fn test_full_tally() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  vote(&mut chain, init.contract_address, ALICE, "C").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, BOB, "C").expect("Vote succeeds");
+
+  let full_tally: FullTally = chain
+    .contract_invoke(
+      ALICE,
+      ALICE_ADDR,
+      Energy::from(10_000),
+      UpdateContractPayload {
+        address: init.contract_address,
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("voting.fullTally".to_string()),
+        message: OwnedParameter::empty(),
+      },
+    )
+    .expect("Invoke fullTally")
+    .parse_return_value()
+    .expect("FullTally return value");
+  assert_eq!(
+    full_tally,
+    vec![
+      ("A".to_string(), 0),
+      ("B".to_string(), 0),
+      ("C".to_string(), 2)
+    ]
+  );
+}
+
+/// Test that `voteMany` counts an approval for every selected option, apart
+/// from the single-choice ballots, and that a new approval replaces the last.
+#[test]
+fn test_approval_vote() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  let approve = |chain: &mut Chain, voter: AccountAddress, options: &[&str]| {
+    let options: Vec<VotingOption> = options.iter().map(|option| option.to_string()).collect();
+    update(chain, init.contract_address, voter, "voteMany", &options)
+  };
+  approve(&mut chain, ALICE, &["C"]).expect("Approval succeeds");
+  approve(&mut chain, ALICE, &["A", "B"]).expect("Approval succeeds");
+  approve(&mut chain, BOB, &["B"]).expect("Approval succeeds");
+  let update = approve(&mut chain, BOB, &["A", "D"]).expect_err("Unknown option fails");
+  let error: ContractError = update.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::InvalidVotingOption);
+  vote(&mut chain, init.contract_address, CHARLIE, "C").expect("Vote succeeds");
+
+  let approvals: FullTally = chain
+    .contract_invoke(
+      ALICE,
+      ALICE_ADDR,
+      Energy::from(10_000),
+      UpdateContractPayload {
+        address: init.contract_address,
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("voting.viewApproval".to_string()),
+        message: OwnedParameter::empty(),
+      },
+    )
+    .expect("Invoke viewApproval")
+    .parse_return_value()
+    .expect("FullTally return value");
+  assert_eq!(
+    approvals,
+    vec![
+      ("A".to_string(), 1),
+      ("B".to_string(), 2),
+      ("C".to_string(), 0)
+    ]
+  );
+  assert_eq!(
+    get_view(&chain, init.contract_address).tally,
+    BTreeMap::from([("C".to_string(), 1)])
+  );
+}
+
+/// Test that `votingStatus` reports the remaining time before `end_time` and
+/// the finished status after it.
+#[test]
+fn test_voting_status() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  vote(&mut chain, init.contract_address, ALICE, "A").expect("Vote succeeds");
+  chain
+    .tick_block_time(Duration::from_millis(400))
+    .expect("Advance block time");
+
+  assert_eq!(
+    get_voting_status(&chain, init.contract_address),
+    VotingStatusView {
+      status: VotingStatus::Open,
+      ends_in_ms: END_TIME - 400,
+      total_ballots: 1,
+    }
+  );
+
+  chain
+    .tick_block_time(Duration::from_millis(END_TIME))
+    .expect("Advance block time");
+  assert_eq!(
+    get_voting_status(&chain, init.contract_address),
+    VotingStatusView {
+      status: VotingStatus::Finished,
+      ends_in_ms: 0,
+      total_ballots: 1,
+    }
+  );
+}
+
+/// Test that an account cannot change its ballot within the minimum vote
+/// interval, while a first ballot is always accepted.
+#[test]
+fn test_min_vote_interval() {
+  let (mut chain, init) = initialize_with_params(InitParameter {
+    description: "Test vote".to_string(),
+    options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+    end_time: Timestamp::from_timestamp_millis(END_TIME),
+    tie_break: TieBreak::ReturnNone,
+    auto_finalize: false,
+    min_vote_interval_ms: 100,
+    max_options: MAX_OPTIONS,
+    option_deposit: Amount::zero(),
+    deposit_threshold: 0,
+    gating: None,
+    min_participation: 0,
+  });
+  vote(&mut chain, init.contract_address, ALICE, "A").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, BOB, "A").expect("Vote succeeds");
+
+  let update = vote(&mut chain, init.contract_address, ALICE, "B").expect_err("Vote fails");
+  let error: ContractError = update.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::VoteTooSoon);
+
+  chain
+    .tick_block_time(Duration::from_millis(100))
+    .expect("Advance block time");
+  vote(&mut chain, init.contract_address, ALICE, "B").expect("Vote succeeds");
+  assert_eq!(
+    get_view(&chain, init.contract_address).tally,
+    BTreeMap::from([("A".to_string(), 1), ("B".to_string(), 1)])
+  );
+}
+
+/// Test that init rejects more than `max_options` options and that
+/// `addOption` stops at the limit.
+#[test]
+fn test_max_options() {
+  let options: Vec<VotingOption> = (0..=MAX_OPTIONS).map(|i| i.to_string()).collect();
+  let param = InitParameter {
+    description: "Test vote".to_string(),
+    options: options.clone(),
+    end_time: Timestamp::from_timestamp_millis(END_TIME),
+    tie_break: TieBreak::ReturnNone,
+    auto_finalize: false,
+    min_vote_interval_ms: 0,
+    max_options: MAX_OPTIONS,
+    option_deposit: Amount::zero(),
+    deposit_threshold: 0,
+    gating: None,
+    min_participation: 0,
+  };
+  let mut chain = Chain::new();
+  chain.create_account(Account::new(ALICE, ACC_INITIAL_BALANCE));
+  let module = module_load_v1("./concordium-out/module.wasm.v1").expect("Module exists at path");
+  let deployment = chain
+    .module_deploy_v1(SIGNER, ALICE, module)
+    .expect("Deploy valid module");
+  let error = chain
+    .contract_init(
+      SIGNER,
+      ALICE,
+      Energy::from(10_000),
+      InitContractPayload {
+        amount: Amount::zero(),
+        mod_ref: deployment.module_reference,
+        init_name: OwnedContractName::new_unchecked("init_voting".to_string()),
+        param: OwnedParameter::from_serial(&param).expect("Init params"),
+      },
+    )
+    .expect_err("Init fails");
+  // `TooManyOptions` is the seventh variant of `ContractError`.
+  assert!(matches!(
+    error.kind,
+    ContractInitErrorKind::ExecutionError {
+      error: InitExecutionError::Reject { reason: -7, .. }
+    }
+  ));
+
+  // Start one option below the limit.
+  let (mut chain, init) = initialize_with_params(InitParameter {
+    options: options[..MAX_OPTIONS as usize - 1].to_vec(),
+    ..param
+  });
+  update(
+    &mut chain,
+    init.contract_address,
+    BOB,
+    "addOption",
+    &"Extra".to_string(),
+  )
+  .expect("Add option succeeds");
+  let rejected = update(
+    &mut chain,
+    init.contract_address,
+    ALICE,
+    "addOption",
+    &"Another".to_string(),
+  )
+  .expect_err("Add option fails");
+  let error: ContractError = rejected.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::TooManyOptions);
+  assert_eq!(
+    get_view(&chain, init.contract_address).options.len(),
+    MAX_OPTIONS as usize
+  );
+}
+
+/// Test that the deposit of an option is refunded to its proposer if the
+/// option reaches the threshold, and forfeited to the owner otherwise.
+#[test]
+fn test_option_deposit() {
+  let (mut chain, init) = initialize_with_params(InitParameter {
+    description: "Test vote".to_string(),
+    options: vec!["A".to_string()],
+    end_time: Timestamp::from_timestamp_millis(END_TIME),
+    tie_break: TieBreak::ReturnNone,
+    auto_finalize: false,
+    min_vote_interval_ms: 0,
+    max_options: MAX_OPTIONS,
+    option_deposit: OPTION_DEPOSIT,
+    deposit_threshold: 2,
+    gating: None,
+    min_participation: 0,
+  });
+  let rejected = update(
+    &mut chain,
+    init.contract_address,
+    BOB,
+    "addOption",
+    &"B".to_string(),
+  )
+  .expect_err("Add option fails");
+  let error: ContractError = rejected.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::WrongDeposit);
+  // Bob proposes "B", which clears the threshold, and Charlie proposes "C",
+  // which does not.
+  for (proposer, option) in [(BOB, "B"), (CHARLIE, "C")] {
+    update_with_amount(
+      &mut chain,
+      init.contract_address,
+      proposer,
+      "addOption",
+      &option.to_string(),
+      OPTION_DEPOSIT,
+    )
+    .expect("Add option succeeds");
+  }
+  vote(&mut chain, init.contract_address, BOB, "B").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, CHARLIE, "B").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, DAVE, "C").expect("Vote succeeds");
+
+  let rejected =
+    claim_option_deposit(&mut chain, init.contract_address, "B").expect_err("Claim fails");
+  let error: ContractError = rejected.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::VotingNotFinished);
+  let rejected = update(
+    &mut chain,
+    init.contract_address,
+    ALICE,
+    "withdrawForfeitedDeposits",
+    &(),
+  )
+  .expect_err("Withdraw fails");
+  let error: ContractError = rejected.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::VotingNotFinished);
+
+  chain
+    .tick_block_time(Duration::from_millis(END_TIME + 1))
+    .expect("Advance block time");
+  let bob_balance = chain.account_balance_available(BOB).expect("Bob exists");
+  let charlie_balance = chain
+    .account_balance_available(CHARLIE)
+    .expect("Charlie exists");
+  let success =
+    claim_option_deposit(&mut chain, init.contract_address, "B").expect("Claim succeeds");
+  assert_eq!(
+    chain.account_balance_available(BOB),
+    Some(bob_balance + OPTION_DEPOSIT - success.transaction_fee)
+  );
+  assert_eq!(
+    chain.contract_balance(init.contract_address),
+    Some(OPTION_DEPOSIT)
+  );
+
+  for (option, expected) in [
+    ("B", ContractError::NoOptionDeposit),
+    ("C", ContractError::DepositForfeited),
+    ("A", ContractError::NoOptionDeposit),
+  ] {
+    let rejected =
+      claim_option_deposit(&mut chain, init.contract_address, option).expect_err("Claim fails");
+    let error: ContractError = rejected.parse_return_value().expect("ContractError");
+    assert_eq!(error, expected);
+  }
+  assert_eq!(
+    chain.account_balance_available(CHARLIE),
+    Some(charlie_balance)
+  );
+
+  let rejected = update(
+    &mut chain,
+    init.contract_address,
+    CHARLIE,
+    "withdrawForfeitedDeposits",
+    &(),
+  )
+  .expect_err("Withdraw fails");
+  let error: ContractError = rejected.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::NotOwner);
+  let alice_balance = chain
+    .account_balance_available(ALICE)
+    .expect("Alice exists");
+  let mut fees = Amount::zero();
+  for expected in [OPTION_DEPOSIT, Amount::zero()] {
+    let success = update(
+      &mut chain,
+      init.contract_address,
+      ALICE,
+      "withdrawForfeitedDeposits",
+      &(),
+    )
+    .expect("Withdraw succeeds");
+    fees += success.transaction_fee;
+    let withdrawn: Amount = success.parse_return_value().expect("Amount return value");
+    assert_eq!(withdrawn, expected);
+  }
+  assert_eq!(
+    chain.account_balance_available(ALICE),
+    Some(alice_balance + OPTION_DEPOSIT - fees)
+  );
+  assert_eq!(
+    chain.contract_balance(init.contract_address),
+    Some(Amount::zero())
+  );
+}
+
+/// Alice votes for "B" and Bob votes for "C".
+fn cast_tie(chain: &mut Chain, contract_address: ContractAddress) {
+  vote(chain, contract_address, ALICE, "B").expect("Vote succeeds");
+  vote(chain, contract_address, BOB, "C").expect("Vote succeeds");
+}
+
+/// Helper method for voting on an option.
+fn vote(
+  chain: &mut Chain,
+  contract_address: ContractAddress,
+  voter: AccountAddress,
+  option: &str,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+  update(chain, contract_address, voter, "vote", &option.to_string())
+}
+
+/// Helper method for claiming the deposit of an option, called by Bob.
+fn claim_option_deposit(
+  chain: &mut Chain,
+  contract_address: ContractAddress,
+  option: &str,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+  update(
+    chain,
+    contract_address,
+    BOB,
+    "claimOptionDeposit",
+    &option.to_string(),
+  )
+}
+
+/// Helper method for calling an entrypoint of the contract as `sender`.
+fn update<P: Serial>(
+  chain: &mut Chain,
+  contract_address: ContractAddress,
+  sender: AccountAddress,
+  entrypoint: &str,
+  parameter: &P,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+  update_with_amount(
+    chain,
+    contract_address,
+    sender,
+    entrypoint,
+    parameter,
+    Amount::zero(),
+  )
+}
+
+/// Helper method for calling an entrypoint of the contract as `sender` with
+/// an attached amount.
+fn update_with_amount<P: Serial>(
+  chain: &mut Chain,
+  contract_address: ContractAddress,
+  sender: AccountAddress,
+  entrypoint: &str,
+  parameter: &P,
+  amount: Amount,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+  chain.contract_update(
+    SIGNER,
+    sender,
+    Address::Account(sender),
+    Energy::from(30_000),
+    UpdateContractPayload {
+      address: contract_address,
+      amount,
+      receive_name: OwnedReceiveName::new_unchecked(format!("voting.{}", entrypoint)),
+      message: OwnedParameter::from_serial(parameter).expect("Parameter within size bounds"),
+    },
+  )
+}
+
+/// Helper method for invoking the `view` function.
+fn get_view(chain: &Chain, contract_address: ContractAddress) -> VotingView {
+  chain
+    .contract_invoke(
+      ALICE,
+      ALICE_ADDR,
+      Energy::from(10_000),
+      UpdateContractPayload {
+        address: contract_address,
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("voting.view".to_string()),
+        message: OwnedParameter::empty(),
+      },
+    )
+    .expect("Invoke view")
+    .parse_return_value()
+    .expect("VotingView return value")
+}
+
+/// Helper method for invoking the `winner` view.
+fn get_winner(chain: &Chain, contract_address: ContractAddress) -> WinnerResponse {
+  chain
+    .contract_invoke(
+      ALICE,
+      ALICE_ADDR,
+      Energy::from(10_000),
+      UpdateContractPayload {
+        address: contract_address,
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("voting.winner".to_string()),
+        message: OwnedParameter::empty(),
+      },
+    )
+    .expect("Invoke winner")
+    .parse_return_value()
+    .expect("WinnerResponse return value")
+}
+
+/// Test that the ballots of a token-gated vote are weighted by the voter's
+/// balance of the gating NFTs, and that voters without any are rejected.
+#[test]
+fn test_token_gated_vote() {
+  let mut chain = initialize_chain();
+  let nft = initialize_nft(&mut chain);
+  mint_gating_nfts(&mut chain, nft, &[(BOB, 1), (BOB, 2), (CHARLIE, 3)]);
+  let init = initialize_token_gated(&mut chain, nft);
+  let vote = |chain: &mut Chain, voter: AccountAddress, option: &str| {
+    update(
+      chain,
+      init.contract_address,
+      voter,
+      "vote",
+      &option.to_string(),
+    )
+  };
+  vote(&mut chain, BOB, "A").expect("Vote succeeds");
+  vote(&mut chain, CHARLIE, "B").expect("Vote succeeds");
+  let update = vote(&mut chain, DAVE, "B").expect_err("Vote without NFTs fails");
+  let error: ContractError = update.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::NoGatingTokens);
+
+  let view = get_view(&chain, init.contract_address);
+  assert_eq!(
+    view.tally,
+    BTreeMap::from([("A".to_string(), 2), ("B".to_string(), 1)])
+  );
+}
+
+/// Test that a delegated vote in a token-gated vote counts with the
+/// delegator's balance of the gating NFTs, and that accounts without any
+/// cannot delegate.
+#[test]
+fn test_token_gated_delegate() {
+  let mut chain = initialize_chain();
+  let nft = initialize_nft(&mut chain);
+  mint_gating_nfts(&mut chain, nft, &[(BOB, 1), (BOB, 2), (CHARLIE, 3)]);
+  let init = initialize_token_gated(&mut chain, nft);
+
+  update(&mut chain, init.contract_address, BOB, "delegate", &CHARLIE).expect("Delegate succeeds");
+  let update = update(
+    &mut chain,
+    init.contract_address,
+    DAVE,
+    "delegate",
+    &CHARLIE,
+  )
+  .expect_err("Delegate without NFTs fails");
+  let error: ContractError = update.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::NoGatingTokens);
+  vote(&mut chain, init.contract_address, CHARLIE, "B").expect("Vote succeeds");
+
+  let view = get_view(&chain, init.contract_address);
+  assert_eq!(view.tally, BTreeMap::from([("B".to_string(), 3)]));
+}
+
+/// Test that a gating NFT transferred after weighting a ballot only counts
+/// for the new owner once the previous owner votes again.
+#[test]
+fn test_token_gated_transfer_then_revote() {
+  let mut chain = initialize_chain();
+  let nft = initialize_nft(&mut chain);
+  mint_gating_nfts(&mut chain, nft, &[(BOB, 1), (BOB, 2), (CHARLIE, 3)]);
+  let init = initialize_token_gated(&mut chain, nft);
+
+  vote(&mut chain, init.contract_address, BOB, "A").expect("Vote succeeds");
+  let transfer = TransferParams(vec![Transfer {
+    token_id: TokenIdU32(2),
+    amount: ciphers_nft::cis2::ContractTokenAmount::from(1),
+    from: Address::Account(BOB),
+    to: Receiver::from_account(CHARLIE),
+    data: AdditionalData::empty(),
+  }]);
+  chain
+    .contract_update(
+      SIGNER,
+      BOB,
+      Address::Account(BOB),
+      Energy::from(10_000),
+      UpdateContractPayload {
+        address: nft,
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.transfer".to_string()),
+        message: OwnedParameter::from_serial(&transfer).expect("Transfer params"),
+      },
+    )
+    .expect("Transfer succeeds");
+
+  // Token 2 still counts for the ballot of Bob.
+  vote(&mut chain, init.contract_address, CHARLIE, "B").expect("Vote succeeds");
+  let view = get_view(&chain, init.contract_address);
+  assert_eq!(
+    view.tally,
+    BTreeMap::from([("A".to_string(), 2), ("B".to_string(), 1)])
+  );
+
+  vote(&mut chain, init.contract_address, BOB, "A").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, CHARLIE, "B").expect("Vote succeeds");
+  let view = get_view(&chain, init.contract_address);
+  assert_eq!(
+    view.tally,
+    BTreeMap::from([("A".to_string(), 1), ("B".to_string(), 2)])
+  );
+}
+
+/// Test that ballots count with the weights set by the owner, that accounts
+/// without a weight keep one vote and that only the owner sets weights.
+#[test]
+fn test_weighted_vote() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  for (voter, weight) in [(ALICE, 5), (BOB, 3)] {
+    update(
+      &mut chain,
+      init.contract_address,
+      ALICE,
+      "setWeight",
+      &SetWeightParameter { voter, weight },
+    )
+    .expect("Set weight succeeds");
+  }
+  let update = update(
+    &mut chain,
+    init.contract_address,
+    BOB,
+    "setWeight",
+    &SetWeightParameter {
+      voter: BOB,
+      weight: 100,
+    },
+  )
+  .expect_err("Set weight by non-owner fails");
+  let error: ContractError = update.parse_return_value().expect("ContractError");
+  assert_eq!(error, ContractError::NotOwner);
+
+  vote(&mut chain, init.contract_address, ALICE, "A").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, BOB, "B").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, CHARLIE, "B").expect("Vote succeeds");
+  vote(&mut chain, init.contract_address, DAVE, "C").expect("Vote succeeds");
+
+  let view = get_view(&chain, init.contract_address);
+  assert_eq!(
+    view.tally,
+    BTreeMap::from([
+      ("A".to_string(), 5),
+      ("B".to_string(), 4),
+      ("C".to_string(), 1)
+    ])
+  );
+}
+
+/// Test that a retracted ballot no longer counts and logs its option, and
+/// that retracting without a ballot succeeds without an event.
+#[test]
+fn test_retract() {
+  let (mut chain, init) = initialize(TieBreak::ReturnNone);
+  vote(&mut chain, init.contract_address, ALICE, "A").expect("Vote succeeds");
+  assert_eq!(
+    get_view(&chain, init.contract_address).tally,
+    BTreeMap::from([("A".to_string(), 1)])
+  );
+
+  let mut events = Vec::new();
+  for _ in 0..2 {
+    let update =
+      update(&mut chain, init.contract_address, ALICE, "retract", &()).expect("Retract succeeds");
+    events.extend(
+      update
+        .events()
+        .flat_map(|(_addr, events)| events)
+        .map(|event| event.parse::<VotingEvent>().expect("Deserialize event")),
+    );
+  }
+  assert_eq!(
+    events,
+    vec![VotingEvent::VoteRetracted {
+      voter: ALICE,
+      option_index: 0,
+    }]
+  );
+  assert!(get_view(&chain, init.contract_address).tally.is_empty());
+}
+
+/// Helper method for invoking the `votingStatus` view.
+fn get_voting_status(chain: &Chain, contract_address: ContractAddress) -> VotingStatusView {
+  chain
+    .contract_invoke(
+      ALICE,
+      ALICE_ADDR,
+      Energy::from(10_000),
+      UpdateContractPayload {
+        address: contract_address,
+        amount: Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("voting.votingStatus".to_string()),
+        message: OwnedParameter::empty(),
+      },
+    )
+    .expect("Invoke votingStatus")
+    .parse_return_value()
+    .expect("VotingStatusView return value")
 }
 
 /// Helper method for initializing the contract.
 ///
 /// Does the following:
 ///  - Creates the [`Chain`]
-///  - Creates one account, `Alice` with `10_000` CCD as the initial balance.
-///  - Initializes the contract.
+///  - Creates the test accounts with `10_000` CCD as the initial balance.
+///  - Initializes the contract with the options "A", "B" and "C".
 ///  - Returns the [`Chain`] and the [`ContractInitSuccess`]
-fn initialize() -> (Chain, ContractInitSuccess) {
-    // Initialize the test chain.
-    let mut chain = Chain::new();
-
-    // Create the test account.
-    chain.create_account(Account::new(ALICE, ACC_INITIAL_BALANCE));
-
-    // Load the module.
-    let module = module_load_v1("./concordium-out/module.wasm.v1").expect("Module exists at path");
-    // Deploy the module.
-    let deployment = chain
-        .module_deploy_v1(SIGNER, ALICE, module)
-        .expect("Deploy valid module");
-
-    // Initialize the contract.
-    let init = chain
-        .contract_init(
-            SIGNER,
-            ALICE,
-            Energy::from(10_000),
-            InitContractPayload {
-                amount: Amount::zero(),
-                mod_ref: deployment.module_reference,
-                init_name: OwnedContractName::new_unchecked("init_voting".to_string()),
-                param: OwnedParameter::empty(),
-            },
-        )
-        .expect("Initializing contract");
-
-    (chain, init)
+fn initialize(tie_break: TieBreak) -> (Chain, ContractInitSuccess) {
+  initialize_with_params(InitParameter {
+    description: "Test vote".to_string(),
+    options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+    end_time: Timestamp::from_timestamp_millis(END_TIME),
+    tie_break,
+    auto_finalize: false,
+    min_vote_interval_ms: 0,
+    max_options: MAX_OPTIONS,
+    option_deposit: Amount::zero(),
+    deposit_threshold: 0,
+    gating: None,
+    min_participation: 0,
+  })
+}
+
+/// Helper method for initializing the contract with the given parameter.
+fn initialize_with_params(param: InitParameter) -> (Chain, ContractInitSuccess) {
+  let mut chain = initialize_chain();
+  let init = initialize_contract(&mut chain, param);
+  (chain, init)
+}
+
+/// Helper method for creating the [`Chain`] with the test accounts, each with
+/// `10_000` CCD as the initial balance.
+fn initialize_chain() -> Chain {
+  let mut chain = Chain::new();
+  chain.create_account(Account::new(ALICE, ACC_INITIAL_BALANCE));
+  chain.create_account(Account::new(BOB, ACC_INITIAL_BALANCE));
+  chain.create_account(Account::new(CHARLIE, ACC_INITIAL_BALANCE));
+  chain.create_account(Account::new(DAVE, ACC_INITIAL_BALANCE));
+  chain
+}
+
+/// Helper method for deploying and initializing the contract on the chain.
+fn initialize_contract(chain: &mut Chain, param: InitParameter) -> ContractInitSuccess {
+  try_initialize_contract(chain, param).expect("Initializing contract")
+}
+
+/// Helper method for deploying the contract and trying to initialize it.
+fn try_initialize_contract(
+  chain: &mut Chain,
+  param: InitParameter,
+) -> Result<ContractInitSuccess, ContractInitError> {
+  // Load the module.
+  let module = module_load_v1("./concordium-out/module.wasm.v1").expect("Module exists at path");
+  // Deploy the module.
+  let deployment = chain
+    .module_deploy_v1(SIGNER, ALICE, module)
+    .expect("Deploy valid module");
+
+  // Initialize the contract.
+  chain.contract_init(
+    SIGNER,
+    ALICE,
+    Energy::from(10_000),
+    InitContractPayload {
+      amount: Amount::zero(),
+      mod_ref: deployment.module_reference,
+      init_name: OwnedContractName::new_unchecked("init_voting".to_string()),
+      param: OwnedParameter::from_serial(&param).expect("Init params"),
+    },
+  )
+}
+
+/// Helper method for minting each gating NFT to its owner, with `ALICE` as
+/// the minter.
+fn mint_gating_nfts(chain: &mut Chain, nft: ContractAddress, tokens: &[(AccountAddress, u32)]) {
+  for (owner, token) in tokens {
+    let params = ciphers_nft::mint::MintToParams {
+      owner: Address::Account(*owner),
+      token_id: TokenIdU32(*token),
+      token_uri: format!("ipfs://{}", token),
+    };
+    chain
+      .contract_update(
+        SIGNER,
+        ALICE,
+        ALICE_ADDR,
+        Energy::from(10_000),
+        UpdateContractPayload {
+          address: nft,
+          amount: Amount::zero(),
+          receive_name: OwnedReceiveName::new_unchecked("ciphers_nft.mintTo".to_string()),
+          message: OwnedParameter::from_serial(&params).expect("Mint params"),
+        },
+      )
+      .expect("Mint succeeds");
+  }
+}
+
+/// Helper method for initializing a vote on "A" and "B" gated by the tokens
+/// 1 to 3 of the given NFT contract.
+fn initialize_token_gated(chain: &mut Chain, nft: ContractAddress) -> ContractInitSuccess {
+  initialize_contract(
+    chain,
+    InitParameter {
+      description: "Token-gated vote".to_string(),
+      options: vec!["A".to_string(), "B".to_string()],
+      end_time: Timestamp::from_timestamp_millis(END_TIME),
+      tie_break: TieBreak::ReturnNone,
+      auto_finalize: false,
+      min_vote_interval_ms: 0,
+      max_options: MAX_OPTIONS,
+      option_deposit: Amount::zero(),
+      deposit_threshold: 0,
+      gating: Some(TokenGating {
+        contract: nft,
+        token_ids: (1u32..=3)
+          .map(|token| TokenIdVec(token.to_le_bytes().to_vec()))
+          .collect(),
+      }),
+      min_participation: 0,
+    },
+  )
+}
+
+/// Helper method for deploying a `ciphers_nft` contract with `ALICE` as the
+/// minter, used as the gating contract of a token-gated vote.
+fn initialize_nft(chain: &mut Chain) -> ContractAddress {
+  let module = module_load_v1("../nft/ciphers_nft.wasm.v1").expect("Module exists at path");
+  let deployment = chain
+    .module_deploy_v1(SIGNER, ALICE, module)
+    .expect("Deploy valid module");
+  let params = ciphers_nft::init::InitParams {
+    name: "Gating NFT".to_string(),
+    symbol: "GATE".to_string(),
+    contract_uri: MetadataUrl {
+      url: "ipfs://contractURI".to_string(),
+      hash: None,
+    },
+    minter: ALICE,
+    mint_start: 0,
+    mint_deadline: END_TIME,
+    max_total_supply: 10,
+    mint_price: Amount::zero(),
+    emit_events: false,
+    deadline_grace_ms: 0,
+    royalty_basis_points: 0,
+    royalty_recipient: ALICE,
+    require_unique_uri: false,
+    operators_enabled: true,
+    max_operators_per_owner: 0,
+    minter_locked: false,
+    treasury: None,
+    treasury_bps: 0,
+    admin_timelock_ms: 0,
+    refund_overpayment: false,
+    trading_after_mint: false,
+    mint_proof_difficulty: 0,
+    mint_cooldown_ms: 0,
+    max_mints_per_account: 0,
+    burn_requires_admin: false,
+    claim_deadline: 0,
+    resale_lock_ms: 0,
+    reject_operator_self_transfers: false,
+    enforce_receiver_whitelist: false,
+    placeholder_uri: None,
+    permit_replay_window_ms: 0,
+    initial_implementors: Vec::new(),
+  };
+  chain
+    .contract_init(
+      SIGNER,
+      ALICE,
+      Energy::from(10_000),
+      InitContractPayload {
+        amount: Amount::zero(),
+        mod_ref: deployment.module_reference,
+        init_name: OwnedContractName::new_unchecked("init_ciphers_nft".to_string()),
+        param: OwnedParameter::from_serial(&params).expect("Init params"),
+      },
+    )
+    .expect("Initializing NFT contract")
+    .contract_address
 }